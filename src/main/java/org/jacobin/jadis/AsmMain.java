@@ -0,0 +1,35 @@
+/*
+ * Copyright (c) 2021 by Andrew Binstock.
+ *
+ * Portions of this file are copyright Oracle Corp.
+ * Those portions are licensed under GPL v. 2.0
+ * with the Oracle classpath exception. Due to the
+ * requirements of that license, the portions that
+ * are copyrighted by Andrew Binstock are licensed
+ * using the same terms and requirements.
+ *
+ */
+
+package org.jacobin.jadis;
+
+import java.io.PrintWriter;
+
+import org.jacobin.jadis.asm.AsmTask;
+
+/*
+ * jadis-asm: assembles the output of --format=krakatau back into class files
+ * @author alb (@platypusguy)
+ */
+public class AsmMain {
+
+    /**
+     * @param args command-line arguments
+     */
+    public static void main( String[] args ) {
+        PrintWriter out = new PrintWriter( System.out );
+        AsmTask t = new AsmTask();
+        t.setLog( out );
+        int rc = t.run( args );
+        System.exit( rc );
+    }
+}
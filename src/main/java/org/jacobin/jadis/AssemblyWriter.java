@@ -0,0 +1,1564 @@
+/*
+ * Copyright (c) 2021 by Andrew Binstock.
+ *
+ * Portions of this file are copyright Oracle Corp.
+ * Those portions are licensed under GPL v. 2.0
+ * with the Oracle classpath exception. Due to the
+ * requirements of that license, the portions that
+ * are copyrighted by Andrew Binstock are licensed
+ * using the same terms and requirements.
+ *
+ */
+
+package org.jacobin.jadis;
+
+import java.util.ArrayList;
+import java.util.HashMap;
+import java.util.List;
+import java.util.Map;
+import java.util.TreeSet;
+
+import org.jacobin.jadis.classfile.Annotation;
+import org.jacobin.jadis.classfile.AnnotationDefault_attribute;
+import org.jacobin.jadis.classfile.Attribute;
+import org.jacobin.jadis.classfile.BootstrapMethods_attribute;
+import org.jacobin.jadis.classfile.BootstrapMethods_attribute.BootstrapMethodSpecifier;
+import org.jacobin.jadis.classfile.ClassFile;
+import org.jacobin.jadis.classfile.Code_attribute;
+import org.jacobin.jadis.classfile.ConstantPool;
+import org.jacobin.jadis.classfile.ConstantPool.*;
+import org.jacobin.jadis.classfile.ConstantPoolException;
+import org.jacobin.jadis.classfile.ConstantValue_attribute;
+import org.jacobin.jadis.classfile.CustomAttribute;
+import org.jacobin.jadis.classfile.DefaultAttribute;
+import org.jacobin.jadis.classfile.Deprecated_attribute;
+import org.jacobin.jadis.classfile.EnclosingMethod_attribute;
+import org.jacobin.jadis.classfile.Exceptions_attribute;
+import org.jacobin.jadis.classfile.Field;
+import org.jacobin.jadis.classfile.InnerClasses_attribute;
+import org.jacobin.jadis.classfile.Instruction;
+import org.jacobin.jadis.classfile.Instruction.TypeKind;
+import org.jacobin.jadis.classfile.LineNumberTable_attribute;
+import org.jacobin.jadis.classfile.LocalVariableTable_attribute;
+import org.jacobin.jadis.classfile.LocalVariableTypeTable_attribute;
+import org.jacobin.jadis.classfile.Method;
+import org.jacobin.jadis.classfile.MethodParameters_attribute;
+import org.jacobin.jadis.classfile.ModuleMainClass_attribute;
+import org.jacobin.jadis.classfile.ModulePackages_attribute;
+import org.jacobin.jadis.classfile.ModuleResolution_attribute;
+import org.jacobin.jadis.classfile.Module_attribute;
+import org.jacobin.jadis.classfile.NestHost_attribute;
+import org.jacobin.jadis.classfile.NestMembers_attribute;
+import org.jacobin.jadis.classfile.Opcode;
+import org.jacobin.jadis.classfile.PermittedSubclasses_attribute;
+import org.jacobin.jadis.classfile.Record_attribute;
+import org.jacobin.jadis.classfile.RuntimeAnnotations_attribute;
+import org.jacobin.jadis.classfile.RuntimeParameterAnnotations_attribute;
+import org.jacobin.jadis.classfile.RuntimeTypeAnnotations_attribute;
+import org.jacobin.jadis.classfile.Signature_attribute;
+import org.jacobin.jadis.classfile.SourceDebugExtension_attribute;
+import org.jacobin.jadis.classfile.SourceFile_attribute;
+import org.jacobin.jadis.classfile.StackMapTable_attribute;
+import org.jacobin.jadis.classfile.StackMapTable_attribute.*;
+import org.jacobin.jadis.classfile.Synthetic_attribute;
+import org.jacobin.jadis.classfile.TypeAnnotation;
+
+import static org.jacobin.jadis.classfile.AccessFlags.*;
+import static org.jacobin.jadis.classfile.StackMapTable_attribute.verification_type_info.*;
+
+/*
+ *  Writes a class file as an assembly listing, for --format=jasmin and
+ *  --format=krakatau, so that it can be edited and reassembled.
+ *
+ *  Instructions refer to their targets by symbolic labels, named L followed by
+ *  the offset of the target in the original code. The exception table, the
+ *  LineNumberTable, LocalVariableTable and LocalVariableTypeTable attributes,
+ *  and the StackMapTable attribute are written as directives that refer to the
+ *  same labels. The Krakatau syntax writes stack map frames as they are given
+ *  in the class file; the Jasmin syntax, which has only full frames, writes each
+ *  frame in full.
+ *
+ *  In Krakatau syntax, annotations, the default values of annotation elements,
+ *  method parameters, record components and modules are written as blocks of
+ *  directives; attributes that hold no indexes into the constant pool, and
+ *  have no other directive, are written with .attribute and their content, as
+ *  are attributes that could not be decoded, or that are unknown or decoded by
+ *  plugins, such as those of other vendors. The content is written as it is,
+ *  so any indexes into the constant pool in it are not changed to match the
+ *  constant pool of the reassembled class.
+ *  Anything that cannot be expressed in the syntax being written, such as an
+ *  invokedynamic instruction in Jasmin syntax, or an attribute with no directive,
+ *  is written as a comment, and counted so that a warning can be given.
+ *
+ *  <p><b>This is NOT part of any supported API.
+ *  If you write code that depends on this, you do so at your own risk.
+ *  This code and its internal interfaces are subject to change or
+ *  deletion without notice.</b>
+ */
+public class AssemblyWriter extends BasicWriter {
+    static AssemblyWriter instance(Context context) {
+        AssemblyWriter instance = context.get(AssemblyWriter.class);
+        if (instance == null)
+            instance = new AssemblyWriter(context);
+        return instance;
+    }
+
+    protected AssemblyWriter(Context context) {
+        super(context);
+        context.put(AssemblyWriter.class, this);
+        options = Options.instance(context);
+        classWriter = ClassWriter.instance(context);
+        constantWriter = ConstantWriter.instance(context);
+    }
+
+    /**
+     * Writes a class file as an assembly listing, in the syntax given by --format.
+     * If any items cannot be expressed in the syntax, and are written as comments,
+     * the listing ends with a warning, which is also a comment.
+     * @param cf the class file
+     * @param fileName the name of the class file, for the warning
+     */
+    public void write(ClassFile cf, String fileName) {
+        this.cf = cf;
+        constant_pool = cf.constant_pool;
+        krakatau = (options.format == Options.Format.KRAKATAU);
+        unsupported = 0;
+        classWriter.setClassFile(cf);   // for the values of constants in comments
+
+        Attribute bsms = cf.attributes.get(Attribute.BootstrapMethods);
+        bootstrapMethods = (bsms instanceof BootstrapMethods_attribute)
+                ? ((BootstrapMethods_attribute) bsms).bootstrap_method_specifiers
+                : new BootstrapMethodSpecifier[0];
+
+        writeClassHeader();
+        for (Field f: cf.fields)
+            writeField(f);
+        for (Method m: cf.methods)
+            writeMethod(m);
+        if (krakatau) {
+            println();
+            println(".end class");
+        }
+        if (unsupported > 0) {
+            // a comment, so that the listing can still be assembled
+            println("; " + messages.getMessage("warn.prefix") + " "
+                    + messages.getMessage("warn.not.assemblable", fileName, unsupported, syntaxName()));
+        }
+        println();
+    }
+
+    private void writeClassHeader() {
+        boolean isInterface = cf.access_flags.is(ACC_INTERFACE);
+        if (krakatau) {
+            println(".version " + cf.major_version + " " + cf.minor_version);
+            println(".class " + flags(cf.access_flags.flags, CLASS_FLAGS) + className(cf.this_class));
+        } else {
+            println(".bytecode " + cf.major_version + "." + cf.minor_version);
+            Attribute sf = cf.attributes.get(Attribute.SourceFile);
+            if (sf instanceof SourceFile_attribute)
+                println(".source " + word(utf8(((SourceFile_attribute) sf).sourcefile_index)));
+            // Jasmin gives ACC_INTERFACE by the directive, and always sets ACC_SUPER
+            int f = cf.access_flags.flags & ~(ACC_INTERFACE | ACC_SUPER);
+            println((isInterface ? ".interface " : ".class ") + flags(f, CLASS_FLAGS) + className(cf.this_class));
+        }
+        if (cf.super_class != 0)
+            println(".super " + className(cf.super_class));
+        for (int i: cf.interfaces)
+            println(".implements " + className(i));
+
+        for (Attribute attr: cf.attributes) {
+            String name = attrName(attr);
+            switch (kind(attr, name)) {
+                case Attribute.SourceFile:
+                    if (krakatau)
+                        println(".sourcefile " + quote(utf8(((SourceFile_attribute) attr).sourcefile_index)));
+                    break;
+                case Attribute.Signature:
+                    println(".signature " + quote(utf8(((Signature_attribute) attr).signature_index)));
+                    break;
+                case Attribute.Deprecated:
+                    println(".deprecated");
+                    break;
+                case Attribute.BootstrapMethods:
+                    break;      // given by the invokedynamic instructions and dynamic constants
+                case Attribute.EnclosingMethod:
+                    writeEnclosingMethod((EnclosingMethod_attribute) attr);
+                    break;
+                case Attribute.InnerClasses:
+                    writeInnerClasses((InnerClasses_attribute) attr);
+                    break;
+                case Attribute.NestHost:
+                    if (krakatau)
+                        println(".nesthost " + className(((NestHost_attribute) attr).top_index));
+                    else
+                        writeUnsupported(name);
+                    break;
+                case Attribute.NestMembers:
+                    if (krakatau)
+                        println(".nestmembers" + classNames(((NestMembers_attribute) attr).members_indexes));
+                    else
+                        writeUnsupported(name);
+                    break;
+                case Attribute.PermittedSubclasses:
+                    if (krakatau)
+                        println(".permittedsubclasses" + classNames(((PermittedSubclasses_attribute) attr).subtypes));
+                    else
+                        writeUnsupported(name);
+                    break;
+                default:
+                    List<String> lines = krakatau ? attributeLines(attr, kind(attr, name)) : null;
+                    if (lines == null)
+                        writeUnsupported(name);
+                    else
+                        writeLines("", lines);
+            }
+        }
+    }
+
+    private void writeEnclosingMethod(EnclosingMethod_attribute attr) {
+        String method = null;
+        if (attr.method_index != 0) {
+            try {
+                CONSTANT_NameAndType_info nat = constant_pool.getNameAndTypeInfo(attr.method_index);
+                method = krakatau
+                        ? word(nat.getName()) + " " + word(nat.getType())
+                        : "/" + nat.getName() + nat.getType();
+            } catch (ConstantPoolException e) {
+                method = report(e);
+            }
+        }
+        if (krakatau)
+            println(".enclosing method " + className(attr.class_index) + " " + (method == null ? "[0]" : method));
+        else
+            println(".enclosing method " + className(attr.class_index) + (method == null ? "" : method));
+    }
+
+    private void writeInnerClasses(InnerClasses_attribute attr) {
+        if (krakatau) {
+            println(".innerclasses");
+            for (InnerClasses_attribute.Info info: attr.classes) {
+                println("    " + optClassName(info.inner_class_info_index)
+                        + " " + optClassName(info.outer_class_info_index)
+                        + " " + (info.inner_name_index == 0 ? "[0]" : word(utf8(info.inner_name_index)))
+                        + " " + flags(info.inner_class_access_flags.flags, INNER_CLASS_FLAGS).trim());
+            }
+            println(".end innerclasses");
+        } else {
+            for (InnerClasses_attribute.Info info: attr.classes) {
+                int f = info.inner_class_access_flags.flags;
+                StringBuilder sb = new StringBuilder();
+                sb.append((f & ACC_INTERFACE) != 0 ? ".inner interface " : ".inner class ");
+                sb.append(flags(f & ~ACC_INTERFACE, INNER_CLASS_FLAGS));
+                if (info.inner_name_index != 0)
+                    sb.append(word(utf8(info.inner_name_index))).append(" ");
+                if (info.inner_class_info_index != 0)
+                    sb.append("inner ").append(className(info.inner_class_info_index)).append(" ");
+                if (info.outer_class_info_index != 0)
+                    sb.append("outer ").append(className(info.outer_class_info_index));
+                println(sb.toString().trim());
+            }
+        }
+    }
+
+    private void writeField(Field f) {
+        println();
+        StringBuilder sb = new StringBuilder(".field ");
+        sb.append(flags(f.access_flags.flags, FIELD_FLAGS));
+        sb.append(word(utf8(f.name_index))).append(" ").append(word(utf8(f.descriptor.index)));
+
+        List<String> directives = new ArrayList<>();
+        for (Attribute attr: f.attributes) {
+            String name = attrName(attr);
+            switch (kind(attr, name)) {
+                case Attribute.ConstantValue:
+                    sb.append(" = ").append(constant(((ConstantValue_attribute) attr).constantvalue_index));
+                    break;
+                case Attribute.Signature:
+                    String sig = quote(utf8(((Signature_attribute) attr).signature_index));
+                    if (krakatau)
+                        directives.add(".signature " + sig);
+                    else
+                        sb.append(" signature ").append(sig);
+                    break;
+                case Attribute.Deprecated:
+                    directives.add(".deprecated");
+                    break;
+                default:
+                    List<String> lines = krakatau ? attributeLines(attr, kind(attr, name)) : null;
+                    if (lines == null)
+                        directives.add(unsupported(name));
+                    else
+                        directives.addAll(lines);
+            }
+        }
+
+        if (directives.isEmpty()) {
+            println(sb);
+        } else if (krakatau) {
+            println(sb + " .fieldattributes");
+            for (String d: directives)
+                println("    " + d);
+            println(".end fieldattributes");
+        } else {
+            println(sb);
+            for (String d: directives)
+                println("    " + d);
+            println(".end field");
+        }
+    }
+
+    private void writeMethod(Method m) {
+        println();
+        String name = word(utf8(m.name_index));
+        String descriptor = utf8(m.descriptor.index);
+        println(".method " + flags(m.access_flags.flags, METHOD_FLAGS)
+                + (krakatau ? name + " : " + word(descriptor) : name + descriptor));
+
+        for (Attribute attr: m.attributes) {
+            String attrName = attrName(attr);
+            switch (kind(attr, attrName)) {
+                case Attribute.Code:
+                    writeCode((Code_attribute) attr, m);
+                    break;
+                case Attribute.Exceptions:
+                    for (int i: ((Exceptions_attribute) attr).exception_index_table)
+                        println("    .throws " + className(i));
+                    break;
+                case Attribute.Signature:
+                    println("    .signature " + quote(utf8(((Signature_attribute) attr).signature_index)));
+                    break;
+                case Attribute.Deprecated:
+                    println("    .deprecated");
+                    break;
+                default:
+                    List<String> lines = krakatau ? attributeLines(attr, kind(attr, attrName)) : null;
+                    if (lines == null)
+                        println("    " + unsupported(attrName));
+                    else
+                        writeLines("    ", lines);
+            }
+        }
+        println(".end method");
+    }
+
+    private void writeCode(Code_attribute attr, Method m) {
+        List<Instruction> instrs = new ArrayList<>();
+        for (Instruction instr: attr.getInstructions())
+            instrs.add(instr);
+
+        labels = new TreeSet<>();
+        for (Instruction instr: instrs)
+            instr.accept(targetFinder, null);
+        for (Code_attribute.Exception_data handler: attr.exception_table) {
+            labels.add(handler.start_pc);
+            labels.add(handler.end_pc);
+            labels.add(handler.handler_pc);
+        }
+
+        // in Jasmin syntax, the signature of a local variable is given with its descriptor,
+        // by the same .var directive
+        Map<String, String> signatures = new HashMap<>();
+        if (!krakatau) {
+            for (Attribute a: attr.attributes) {
+                if (a instanceof LocalVariableTypeTable_attribute) {
+                    for (LocalVariableTypeTable_attribute.Entry e: ((LocalVariableTypeTable_attribute) a).local_variable_table)
+                        signatures.put(e.index + " " + e.start_pc + " " + e.length, utf8(e.signature_index));
+                }
+            }
+        }
+
+        Map<Integer, List<String>> lines = new HashMap<>();
+        List<String> localVars = new ArrayList<>();
+        List<String> localVarTypes = new ArrayList<>();
+        Map<Integer, List<String>> frames = new HashMap<>();
+        List<String> others = new ArrayList<>();
+        for (Attribute a: attr.attributes) {
+            String name = attrName(a);
+            switch (kind(a, name)) {
+                case Attribute.LineNumberTable:
+                    for (LineNumberTable_attribute.Entry e: ((LineNumberTable_attribute) a).line_number_table) {
+                        labels.add(e.start_pc);
+                        lines.computeIfAbsent(e.start_pc, k -> new ArrayList<>()).add(String.valueOf(e.line_number));
+                    }
+                    break;
+                case Attribute.LocalVariableTable:
+                    for (LocalVariableTable_attribute.Entry e: ((LocalVariableTable_attribute) a).local_variable_table) {
+                        labels.add(e.start_pc);
+                        labels.add(e.start_pc + e.length);
+                        String signature = signatures.remove(e.index + " " + e.start_pc + " " + e.length);
+                        localVars.add(e.index + " is " + word(utf8(e.name_index))
+                                + " " + word(utf8(e.descriptor_index))
+                                + (signature == null ? "" : " signature " + quote(signature))
+                                + " from " + label(e.start_pc) + " to " + label(e.start_pc + e.length));
+                    }
+                    break;
+                case Attribute.LocalVariableTypeTable:
+                    if (!krakatau)
+                        break;      // given with the LocalVariableTable, above
+                    for (LocalVariableTypeTable_attribute.Entry e: ((LocalVariableTypeTable_attribute) a).local_variable_table) {
+                        labels.add(e.start_pc);
+                        labels.add(e.start_pc + e.length);
+                        localVarTypes.add(e.index + " is " + word(utf8(e.name_index))
+                                + " " + quote(utf8(e.signature_index))
+                                + " from " + label(e.start_pc) + " to " + label(e.start_pc + e.length));
+                    }
+                    break;
+                case Attribute.StackMapTable:
+                    new FrameWriter(m, frames).write((StackMapTable_attribute) a);
+                    break;
+                default:
+                    List<String> attrLines = krakatau ? attributeLines(a, kind(a, name)) : null;
+                    if (attrLines == null)
+                        others.add(unsupported(name));
+                    else
+                        others.addAll(attrLines);
+            }
+        }
+
+        // signatures of local variables that are not in the LocalVariableTable
+        if (!signatures.isEmpty())
+            others.add(unsupported(Attribute.LocalVariableTypeTable));
+
+        if (krakatau) {
+            println("    .code stack " + attr.max_stack + " locals " + attr.max_locals);
+        } else {
+            println("    .limit stack " + attr.max_stack);
+            println("    .limit locals " + attr.max_locals);
+        }
+
+        String indent = krakatau ? "        " : "    ";
+        for (Code_attribute.Exception_data handler: attr.exception_table) {
+            String type = (handler.catch_type == 0)
+                    ? (krakatau ? "[0]" : "all")
+                    : className(handler.catch_type);
+            println(indent + ".catch " + type + " from " + label(handler.start_pc)
+                    + " to " + label(handler.end_pc) + " using " + label(handler.handler_pc));
+        }
+
+        for (Instruction instr: instrs) {
+            int pc = instr.getPC();
+            if (!krakatau && labels.contains(pc))
+                println(label(pc) + ":");
+            for (String frame: frames.getOrDefault(pc, List.of()))
+                println(indent + frame);
+            if (!krakatau) {
+                for (String line: lines.getOrDefault(pc, List.of()))
+                    println(indent + ".line " + line);
+            }
+            String text = instr.accept(instructionWriter, indent);
+            if (text == null) {
+                unsupported++;
+                text = "; " + instr.getMnemonic() + " " + unsupportedOperand
+                        + " cannot be written in " + syntaxName() + " syntax";
+            }
+            if (krakatau)
+                println(String.format("%-8s", label(pc) + ":") + text);
+            else
+                println(indent + text);
+        }
+        if (labels.contains(attr.code_length))
+            println(label(attr.code_length) + ":");
+
+        if (krakatau) {
+            if (!lines.isEmpty()) {
+                println(indent + ".linenumbertable");
+                for (Attribute a: attr.attributes) {
+                    if (a instanceof LineNumberTable_attribute) {
+                        for (LineNumberTable_attribute.Entry e: ((LineNumberTable_attribute) a).line_number_table)
+                            println(indent + "    " + label(e.start_pc) + " " + e.line_number);
+                    }
+                }
+                println(indent + ".end linenumbertable");
+            }
+            writeTable(indent, "localvariabletable", localVars);
+            writeTable(indent, "localvariabletypetable", localVarTypes);
+        } else {
+            for (String v: localVars)
+                println(indent + ".var " + v);
+        }
+        for (String o: others)
+            println(indent + o);
+
+        if (krakatau)
+            println("    .end code");
+    }
+
+    private void writeLines(String indent, List<String> lines) {
+        for (String line: lines)
+            println(indent + line);
+    }
+
+    private void writeTable(String indent, String name, List<String> entries) {
+        if (entries.isEmpty())
+            return;
+        println(indent + "." + name);
+        for (String e: entries)
+            println(indent + "    " + e);
+        println(indent + ".end " + name);
+    }
+
+    /*
+     *  Returns the lines of an attribute that is written in Krakatau syntax as a
+     *  block of directives, given the name it was decoded by, or null if it has
+     *  no directive. Attributes that hold no indexes into the constant pool, and
+     *  no other syntax, are written with .attribute and their content as a byte
+     *  string, as are the attributes that were not decoded, and those decoded
+     *  by plugins.
+     */
+    private List<String> attributeLines(Attribute attr, String name) {
+        List<String> lines = new ArrayList<>();
+        switch (name) {
+            case Attribute.RuntimeVisibleAnnotations:
+            case Attribute.RuntimeInvisibleAnnotations:
+                lines.add(".runtime " + visibility(name) + " annotations");
+                for (Annotation a: ((RuntimeAnnotations_attribute) attr).annotations)
+                    annotation(lines, "    ", ".annotation", a);
+                lines.add(".end runtime");
+                break;
+            case Attribute.RuntimeVisibleParameterAnnotations:
+            case Attribute.RuntimeInvisibleParameterAnnotations:
+                lines.add(".runtime " + visibility(name) + " paramannotations");
+                for (Annotation[] param: ((RuntimeParameterAnnotations_attribute) attr).parameter_annotations) {
+                    lines.add("    .paramannotation");
+                    for (Annotation a: param)
+                        annotation(lines, "        ", ".annotation", a);
+                    lines.add("    .end paramannotation");
+                }
+                lines.add(".end runtime");
+                break;
+            case Attribute.RuntimeVisibleTypeAnnotations:
+            case Attribute.RuntimeInvisibleTypeAnnotations:
+                lines.add(".runtime " + visibility(name) + " typeannotations");
+                for (TypeAnnotation ta: ((RuntimeTypeAnnotations_attribute) attr).annotations) {
+                    lines.add("    .typeannotation " + typeAnnotationTarget(ta.position)
+                            + " " + word(utf8(ta.annotation.type_index)));
+                    elementValuePairs(lines, "        ", ta.annotation);
+                    lines.add("    .end typeannotation");
+                }
+                lines.add(".end runtime");
+                break;
+            case Attribute.AnnotationDefault:
+                elementValue(lines, "", ".annotationdefault ", ((AnnotationDefault_attribute) attr).default_value);
+                break;
+            case Attribute.MethodParameters:
+                lines.add(".methodparameters");
+                for (MethodParameters_attribute.Entry e: ((MethodParameters_attribute) attr).method_parameter_table) {
+                    String flags = flags(e.flags, PARAMETER_FLAGS).trim();
+                    lines.add("    " + optUtf8(e.name_index) + (flags.isEmpty() ? "" : " " + flags));
+                }
+                lines.add(".end methodparameters");
+                break;
+            case Attribute.Record:
+                lines.add(".record");
+                for (Record_attribute.ComponentInfo c: ((Record_attribute) attr).component_info_arr)
+                    writeRecordComponent(lines, c);
+                lines.add(".end record");
+                break;
+            case Attribute.Module:
+                writeModule(lines, (Module_attribute) attr);
+                break;
+            case Attribute.ModulePackages: {
+                StringBuilder sb = new StringBuilder(".modulepackages");
+                for (int p: ((ModulePackages_attribute) attr).packages_index)
+                    sb.append(" ").append(packageName(p));
+                lines.add(sb.toString());
+                break;
+            }
+            case Attribute.ModuleMainClass:
+                lines.add(".modulemainclass " + className(((ModuleMainClass_attribute) attr).main_class_index));
+                break;
+            case Attribute.Synthetic:
+                lines.add(".attribute " + word(name) + " " + byteString(new byte[0]));
+                break;
+            case Attribute.SourceDebugExtension:
+                lines.add(".attribute " + word(name) + " "
+                        + byteString(((SourceDebugExtension_attribute) attr).debug_extension));
+                break;
+            case Attribute.ModuleResolution: {
+                int f = ((ModuleResolution_attribute) attr).resolution_flags;
+                lines.add(".attribute " + word(name) + " " + byteString(new byte[] { (byte) (f >> 8), (byte) f }));
+                break;
+            }
+            default: {
+                byte[] info = info(attr);
+                if (info == null)
+                    return null;
+                lines.add(".attribute " + word(attrName(attr)) + " " + byteString(info));
+            }
+        }
+        return lines;
+    }
+
+    /*
+     *  Returns the content of an attribute that was not decoded, or that was
+     *  decoded by a plugin, as it was read, or null if it is not known.
+     */
+    private static byte[] info(Attribute attr) {
+        if (attr instanceof DefaultAttribute)
+            return ((DefaultAttribute) attr).info;
+        if (attr instanceof CustomAttribute)
+            return ((CustomAttribute) attr).getInfo();
+        return null;
+    }
+
+    /*
+     *  Returns the name of an attribute, to choose how to write it, or an empty
+     *  string if the name is that of an attribute in ATTRIBUTE_CLASSES but the
+     *  attribute is not of its class, since it could not be decoded, or it was
+     *  decoded by a plugin. Such an attribute is written by its content.
+     */
+    private static String kind(Attribute attr, String name) {
+        Class<? extends Attribute> c = ATTRIBUTE_CLASSES.get(name);
+        return (c == null || c.isInstance(attr)) ? name : "";
+    }
+
+    private static String visibility(String attrName) {
+        return attrName.startsWith("RuntimeVisible") ? "visible" : "invisible";
+    }
+
+    /*
+     *  Adds an annotation: the given directive or keyword and the type, then
+     *  its element values, then .end annotation.
+     */
+    private void annotation(List<String> lines, String indent, String head, Annotation a) {
+        lines.add(indent + head + " " + word(utf8(a.type_index)));
+        elementValuePairs(lines, indent + "    ", a);
+        lines.add(indent + ".end annotation");
+    }
+
+    private void elementValuePairs(List<String> lines, String indent, Annotation a) {
+        for (Annotation.element_value_pair pair: a.element_value_pairs)
+            elementValue(lines, indent, word(utf8(pair.element_name_index)) + " = ", pair.value);
+    }
+
+    /*
+     *  Adds an element value, after the given prefix: the kind, then the value.
+     *  Nested annotations and arrays take the lines up to their .end directive.
+     */
+    private void elementValue(List<String> lines, String indent, String prefix, Annotation.element_value ev) {
+        switch (ev.tag) {
+            case 's':
+                lines.add(indent + prefix + "string "
+                        + quote(utf8(((Annotation.Primitive_element_value) ev).const_value_index)));
+                break;
+            case 'e': {
+                Annotation.Enum_element_value e = (Annotation.Enum_element_value) ev;
+                lines.add(indent + prefix + "enum " + word(utf8(e.type_name_index))
+                        + " " + word(utf8(e.const_name_index)));
+                break;
+            }
+            case 'c':
+                lines.add(indent + prefix + "class " + word(utf8(((Annotation.Class_element_value) ev).class_info_index)));
+                break;
+            case '@':
+                annotation(lines, indent, prefix + "annotation",
+                        ((Annotation.Annotation_element_value) ev).annotation_value);
+                break;
+            case '[':
+                lines.add(indent + prefix + "array");
+                for (Annotation.element_value v: ((Annotation.Array_element_value) ev).values)
+                    elementValue(lines, indent + "    ", "", v);
+                lines.add(indent + ".end array");
+                break;
+            default:
+                lines.add(indent + prefix + ELEMENT_VALUE_KINDS.get((char) ev.tag)
+                        + " " + constant(((Annotation.Primitive_element_value) ev).const_value_index));
+        }
+    }
+
+    /*
+     *  Returns the target of a type annotation: the target type, then a keyword
+     *  and the values that locate the target, then the type path. Offsets in
+     *  the code are given by labels.
+     */
+    private String typeAnnotationTarget(TypeAnnotation.Position pos) {
+        StringBuilder sb = new StringBuilder(String.format("0x%02x ", pos.type.targetTypeValue()));
+        switch (pos.type) {
+            case CLASS_TYPE_PARAMETER:
+            case METHOD_TYPE_PARAMETER:
+                sb.append("typeparam ").append(pos.parameter_index);
+                break;
+            case CLASS_EXTENDS:
+                sb.append("super ").append(pos.type_index);
+                break;
+            case CLASS_TYPE_PARAMETER_BOUND:
+            case METHOD_TYPE_PARAMETER_BOUND:
+                sb.append("typeparambound ").append(pos.parameter_index).append(" ").append(pos.bound_index);
+                break;
+            case FIELD:
+            case METHOD_RETURN:
+            case METHOD_RECEIVER:
+                sb.append("empty");
+                break;
+            case METHOD_FORMAL_PARAMETER:
+                sb.append("methodparam ").append(pos.parameter_index);
+                break;
+            case THROWS:
+                sb.append("throws ").append(pos.type_index);
+                break;
+            case LOCAL_VARIABLE:
+            case RESOURCE_VARIABLE:
+                sb.append("localvar");
+                for (int i = 0; i < pos.lvarOffset.length; i++) {
+                    sb.append(" ").append(codeLabel(pos.lvarOffset[i]))
+                            .append(" ").append(codeLabel(pos.lvarOffset[i] + pos.lvarLength[i]))
+                            .append(" ").append(pos.lvarIndex[i]);
+                }
+                break;
+            case EXCEPTION_PARAMETER:
+                sb.append("catch ").append(pos.exception_index);
+                break;
+            case INSTANCEOF:
+            case NEW:
+            case CONSTRUCTOR_REFERENCE:
+            case METHOD_REFERENCE:
+                sb.append("offset ").append(codeLabel(pos.offset));
+                break;
+            default:
+                // a cast, or a type argument of a call or reference
+                sb.append("typearg ").append(codeLabel(pos.offset)).append(" ").append(pos.type_index);
+        }
+        sb.append(" typepath");
+        for (TypeAnnotation.Position.TypePathEntry e: pos.location) {
+            switch (e.tag) {
+                case ARRAY:
+                    sb.append(" array");
+                    break;
+                case INNER_TYPE:
+                    sb.append(" inner");
+                    break;
+                case WILDCARD:
+                    sb.append(" wildcard");
+                    break;
+                default:
+                    sb.append(" typearg ").append(e.arg);
+            }
+        }
+        return sb.toString();
+    }
+
+    /*
+     *  Returns the label of an offset in the code to which an attribute refers,
+     *  so that the label is written with the code.
+     */
+    private String codeLabel(int pc) {
+        if (labels != null)
+            labels.add(pc);
+        return label(pc);
+    }
+
+    private void writeRecordComponent(List<String> lines, Record_attribute.ComponentInfo c) {
+        String component = "    " + word(utf8(c.name_index)) + " " + word(utf8(c.descriptor.index));
+        List<String> directives = new ArrayList<>();
+        for (Attribute attr: c.attributes) {
+            String name = attrName(attr);
+            List<String> attrLines;
+            if (name.equals(Attribute.Signature))
+                directives.add(".signature " + quote(utf8(((Signature_attribute) attr).signature_index)));
+            else if ((attrLines = attributeLines(attr, name)) != null)
+                directives.addAll(attrLines);
+            else
+                directives.add(unsupported(name));
+        }
+        if (directives.isEmpty()) {
+            lines.add(component);
+            return;
+        }
+        lines.add(component + " .recordattributes");
+        for (String d: directives)
+            lines.add("        " + d);
+        lines.add("    .end recordattributes");
+    }
+
+    /*
+     *  Adds a Module attribute: the flags, name and version of the module, then
+     *  a directive for each of its requires, exports, opens, uses and provides.
+     */
+    private void writeModule(List<String> lines, Module_attribute attr) {
+        lines.add(".module " + flags(attr.module_flags, MODULE_FLAGS) + moduleName(attr.module_name)
+                + " version " + optUtf8(attr.module_version_index));
+        for (Module_attribute.RequiresEntry e: attr.requires) {
+            lines.add("    .requires " + flags(e.requires_flags, REQUIRES_FLAGS) + moduleName(e.requires_index)
+                    + " version " + optUtf8(e.requires_version_index));
+        }
+        for (Module_attribute.ExportsEntry e: attr.exports)
+            lines.add("    .exports " + exportsOrOpens(e.exports_flags, e.exports_index, e.exports_to_index));
+        for (Module_attribute.OpensEntry e: attr.opens)
+            lines.add("    .opens " + exportsOrOpens(e.opens_flags, e.opens_index, e.opens_to_index));
+        for (int i: attr.uses_index)
+            lines.add("    .uses " + className(i));
+        for (Module_attribute.ProvidesEntry e: attr.provides)
+            lines.add("    .provides " + className(e.provides_index) + " with" + classNames(e.with_index));
+        lines.add(".end module");
+    }
+
+    private String exportsOrOpens(int flags, int packageIndex, int[] to) {
+        StringBuilder sb = new StringBuilder(flags(flags, EXPORTS_FLAGS));
+        sb.append(packageName(packageIndex));
+        if (to.length > 0) {
+            sb.append(" to");
+            for (int m: to)
+                sb.append(" ").append(moduleName(m));
+        }
+        return sb.toString();
+    }
+
+    private String moduleName(int index) {
+        try {
+            return word(constant_pool.getModuleInfo(index).getName());
+        } catch (ConstantPoolException e) {
+            return report(e);
+        }
+    }
+
+    private String packageName(int index) {
+        try {
+            return word(constant_pool.getPackageInfo(index).getName());
+        } catch (ConstantPoolException e) {
+            return report(e);
+        }
+    }
+
+    private String optUtf8(int index) {
+        return (index == 0) ? "[0]" : word(utf8(index));
+    }
+
+    /*
+     *  Returns bytes as a byte string, b'...', in which the bytes other than
+     *  printable ASCII characters are given as \xNN escapes.
+     */
+    private static String byteString(byte[] bytes) {
+        StringBuilder sb = new StringBuilder("b'");
+        for (byte b: bytes) {
+            int c = b & 0xff;
+            if (c >= ' ' && c < 0x7f && c != '\\' && c != '\'')
+                sb.append((char) c);
+            else
+                sb.append(String.format("\\x%02x", c));
+        }
+        return sb.append("'").toString();
+    }
+
+    // where
+    private final Instruction.KindVisitor<Void, Void> targetFinder = new Instruction.KindVisitor<>() {
+        public Void visitNoOperands(Instruction instr, Void p) {
+            return null;
+        }
+
+        public Void visitArrayType(Instruction instr, TypeKind kind, Void p) {
+            return null;
+        }
+
+        public Void visitBranch(Instruction instr, int offset, Void p) {
+            labels.add(instr.getPC() + offset);
+            return null;
+        }
+
+        public Void visitConstantPoolRef(Instruction instr, int index, Void p) {
+            return null;
+        }
+
+        public Void visitConstantPoolRefAndValue(Instruction instr, int index, int value, Void p) {
+            return null;
+        }
+
+        public Void visitLocal(Instruction instr, int index, Void p) {
+            return null;
+        }
+
+        public Void visitLocalAndValue(Instruction instr, int index, int value, Void p) {
+            return null;
+        }
+
+        public Void visitLookupSwitch(Instruction instr, int default_, int npairs, int[] matches, int[] offsets, Void p) {
+            labels.add(instr.getPC() + default_);
+            for (int offset: offsets)
+                labels.add(instr.getPC() + offset);
+            return null;
+        }
+
+        public Void visitTableSwitch(Instruction instr, int default_, int low, int high, int[] offsets, Void p) {
+            labels.add(instr.getPC() + default_);
+            for (int offset: offsets)
+                labels.add(instr.getPC() + offset);
+            return null;
+        }
+
+        public Void visitValue(Instruction instr, int value, Void p) {
+            return null;
+        }
+
+        public Void visitUnknown(Instruction instr, Void p) {
+            return null;
+        }
+    };
+
+    /*
+     *  Returns the text of an instruction, or null if it cannot be written in
+     *  the current syntax, in which case unsupportedOperand describes its operand.
+     *  The parameter is the indentation of the instruction, for the lines of a switch.
+     */
+    private final Instruction.KindVisitor<String, String> instructionWriter = new Instruction.KindVisitor<>() {
+        public String visitNoOperands(Instruction instr, String indent) {
+            return mnemonic(instr);
+        }
+
+        public String visitArrayType(Instruction instr, TypeKind kind, String indent) {
+            return mnemonic(instr) + " " + kind.name;
+        }
+
+        public String visitBranch(Instruction instr, int offset, String indent) {
+            return mnemonic(instr) + " " + label(instr.getPC() + offset);
+        }
+
+        public String visitConstantPoolRef(Instruction instr, int index, String indent) {
+            String operand;
+            switch (instr.getOpcode()) {
+                case LDC:
+                case LDC_W:
+                case LDC2_W:
+                    operand = constant(index);
+                    break;
+                case NEW:
+                case ANEWARRAY:
+                case CHECKCAST:
+                case INSTANCEOF:
+                    operand = className(index);
+                    break;
+                case INVOKESTATIC:
+                case INVOKESPECIAL:
+                    operand = memberRef(index, false);
+                    break;
+                default:
+                    operand = memberRef(index, true);
+            }
+            if (operand == null) {
+                unsupportedOperand = "#" + index + " (" + constantWriter.stringValue(index) + ")";
+                return null;
+            }
+            return mnemonic(instr) + " " + operand;
+        }
+
+        public String visitConstantPoolRefAndValue(Instruction instr, int index, int value, String indent) {
+            switch (instr.getOpcode()) {
+                case INVOKEDYNAMIC: {
+                    String operand = krakatau ? dynamic("InvokeDynamic", index) : null;
+                    if (operand == null) {
+                        unsupportedOperand = "#" + index + " (" + constantWriter.stringValue(index) + ")";
+                        return null;
+                    }
+                    return mnemonic(instr) + " " + operand;
+                }
+                case INVOKEINTERFACE:
+                    return mnemonic(instr) + " " + memberRef(index, true) + " " + value;
+                default:
+                    // multianewarray
+                    return mnemonic(instr) + " " + className(index) + " " + value;
+            }
+        }
+
+        public String visitLocal(Instruction instr, int index, String indent) {
+            return mnemonic(instr) + " " + index;
+        }
+
+        public String visitLocalAndValue(Instruction instr, int index, int value, String indent) {
+            return mnemonic(instr) + " " + index + " " + value;
+        }
+
+        public String visitLookupSwitch(Instruction instr,
+                int default_, int npairs, int[] matches, int[] offsets, String indent) {
+            int pc = instr.getPC();
+            String caseIndent = "\n" + indent + "    ";
+            StringBuilder sb = new StringBuilder(mnemonic(instr));
+            for (int i = 0; i < npairs; i++)
+                sb.append(caseIndent).append(matches[i]).append(" : ").append(label(pc + offsets[i]));
+            sb.append(caseIndent).append("default : ").append(label(pc + default_));
+            return sb.toString();
+        }
+
+        public String visitTableSwitch(Instruction instr,
+                int default_, int low, int high, int[] offsets, String indent) {
+            int pc = instr.getPC();
+            String caseIndent = "\n" + indent + "    ";
+            StringBuilder sb = new StringBuilder(mnemonic(instr));
+            sb.append(" ").append(low);
+            if (!krakatau)
+                sb.append(" ").append(high);
+            for (int offset: offsets)
+                sb.append(caseIndent).append(label(pc + offset));
+            sb.append(caseIndent).append("default : ").append(label(pc + default_));
+            return sb.toString();
+        }
+
+        public String visitValue(Instruction instr, int value, String indent) {
+            return mnemonic(instr) + " " + value;
+        }
+
+        public String visitUnknown(Instruction instr, String indent) {
+            unsupportedOperand = "(opcode " + instr.getUnsignedByte(0) + ")";
+            return null;
+        }
+    };
+
+    /*
+     *  The mnemonic of an instruction. Instructions that are modified by wide
+     *  are written with the prefix in Krakatau syntax; Jasmin chooses the wide
+     *  form from the size of the operands.
+     */
+    private String mnemonic(Instruction instr) {
+        Opcode opcode = instr.getOpcode();
+        String mnemonic = instr.getMnemonic();
+        if (opcode != null && (opcode.opcode >> 8) == Opcode.WIDE) {
+            mnemonic = mnemonic.substring(0, mnemonic.length() - "_w".length());
+            if (krakatau)
+                mnemonic = "wide " + mnemonic;
+        }
+        return mnemonic;
+    }
+
+    /*
+     *  Writes the frames of a StackMapTable attribute as .stack directives,
+     *  keyed by the offset of the instruction to which each frame applies.
+     */
+    private class FrameWriter implements stack_map_frame.Visitor<Void, Void> {
+        FrameWriter(Method m, Map<Integer, List<String>> frames) {
+            this.frames = frames;
+            locals = initialLocals(m);
+        }
+
+        void write(StackMapTable_attribute attr) {
+            for (stack_map_frame frame: attr.entries) {
+                // the first frame is at offset_delta; each later frame is at offset_delta + 1
+                // from the frame before it
+                pc = (pc < 0) ? frame.getOffsetDelta() : pc + frame.getOffsetDelta() + 1;
+                labels.add(pc);
+                frame.accept(this, null);
+            }
+        }
+
+        public Void visit_same_frame(same_frame frame, Void p) {
+            add(".stack same", empty);
+            return null;
+        }
+
+        public Void visit_same_locals_1_stack_item_frame(same_locals_1_stack_item_frame frame, Void p) {
+            add(".stack stack_1 " + type(frame.stack[0]), frame.stack);
+            return null;
+        }
+
+        public Void visit_same_locals_1_stack_item_frame_extended(same_locals_1_stack_item_frame_extended frame, Void p) {
+            add(".stack stack_1_extended " + type(frame.stack[0]), frame.stack);
+            return null;
+        }
+
+        public Void visit_chop_frame(chop_frame frame, Void p) {
+            int k = 251 - frame.frame_type;
+            locals = locals.subList(0, Math.max(0, locals.size() - k));
+            add(".stack chop " + k, empty);
+            return null;
+        }
+
+        public Void visit_same_frame_extended(same_frame_extended frame, Void p) {
+            add(".stack same_extended", empty);
+            return null;
+        }
+
+        public Void visit_append_frame(append_frame frame, Void p) {
+            List<String> newLocals = new ArrayList<>(locals);
+            for (verification_type_info t: frame.locals)
+                newLocals.add(type(t));
+            locals = newLocals;
+            add(".stack append" + types(frame.locals), empty);
+            return null;
+        }
+
+        public Void visit_full_frame(full_frame frame, Void p) {
+            List<String> newLocals = new ArrayList<>();
+            for (verification_type_info t: frame.locals)
+                newLocals.add(type(t));
+            locals = newLocals;
+            if (krakatau) {
+                List<String> lines = frames.computeIfAbsent(pc, k -> new ArrayList<>());
+                lines.add(".stack full");
+                lines.add("    locals" + types(frame.locals));
+                lines.add("    stack" + types(frame.stack));
+                lines.add(".end stack");
+            } else {
+                add(null, frame.stack);
+            }
+            return null;
+        }
+
+        /*
+         *  Adds a frame: the given directive in Krakatau syntax, or else the full
+         *  frame given by the current locals and the given stack.
+         */
+        private void add(String directive, verification_type_info[] stack) {
+            List<String> lines = frames.computeIfAbsent(pc, k -> new ArrayList<>());
+            if (krakatau) {
+                lines.add(directive);
+                return;
+            }
+            lines.add(".stack");
+            lines.add("    offset " + label(pc));
+            for (String t: locals)
+                lines.add("    locals " + t);
+            for (verification_type_info t: stack)
+                lines.add("    stack " + type(t));
+            lines.add(".end stack");
+        }
+
+        private String types(verification_type_info[] types) {
+            StringBuilder sb = new StringBuilder();
+            for (verification_type_info t: types)
+                sb.append(" ").append(type(t));
+            return sb.toString();
+        }
+
+        private final Map<Integer, List<String>> frames;
+        private List<String> locals;
+        private int pc = -1;
+        private final verification_type_info[] empty = { };
+    }
+
+    /*
+     *  The verification types of the locals on entry to a method, as implied by
+     *  its descriptor (JVMS 4.10.1.6).
+     */
+    private List<String> initialLocals(Method m) {
+        List<String> locals = new ArrayList<>();
+        if (!m.access_flags.is(ACC_STATIC)) {
+            boolean isInit = "<init>".equals(utf8(m.name_index));
+            locals.add(isInit && cf.super_class != 0 ? "UninitializedThis" : "Object " + className(cf.this_class));
+        }
+        String d = utf8(m.descriptor.index);
+        int i = 1;
+        while (i < d.length() && d.charAt(i) != ')') {
+            int start = i;
+            while (d.charAt(i) == '[')
+                i++;
+            if (d.charAt(i) == 'L')
+                i = d.indexOf(';', i);
+            String t = d.substring(start, i + 1);
+            i++;
+            switch (t) {
+                case "B": case "C": case "I": case "S": case "Z":
+                    locals.add("Integer");
+                    break;
+                case "F":
+                    locals.add("Float");
+                    break;
+                case "J":
+                    locals.add("Long");
+                    break;
+                case "D":
+                    locals.add("Double");
+                    break;
+                default:
+                    locals.add("Object " + word(t.startsWith("L") ? t.substring(1, t.length() - 1) : t));
+            }
+        }
+        return locals;
+    }
+
+    private String type(verification_type_info t) {
+        switch (t.tag) {
+            case ITEM_Top:
+                return "Top";
+            case ITEM_Integer:
+                return "Integer";
+            case ITEM_Float:
+                return "Float";
+            case ITEM_Long:
+                return "Long";
+            case ITEM_Double:
+                return "Double";
+            case ITEM_Null:
+                return "Null";
+            case ITEM_UninitializedThis:
+                return "UninitializedThis";
+            case ITEM_Object:
+                return "Object " + className(((Object_variable_info) t).cpool_index);
+            case ITEM_Uninitialized:
+                return "Uninitialized " + label(((Uninitialized_variable_info) t).offset);
+            default:
+                return report("unknown verification type " + t.tag);
+        }
+    }
+
+    /*
+     *  Returns a loadable constant, as the operand of ldc or the value of a
+     *  ConstantValue attribute, or null if it cannot be written in Jasmin syntax.
+     */
+    private String constant(int index) {
+        CPInfo info;
+        try {
+            info = constant_pool.get(index);
+        } catch (ConstantPoolException e) {
+            return report(e);
+        }
+        switch (info.getTag()) {
+            case ConstantPool.CONSTANT_Integer:
+                return String.valueOf(((CONSTANT_Integer_info) info).value);
+            case ConstantPool.CONSTANT_Long:
+                return ((CONSTANT_Long_info) info).value + (krakatau ? "L" : "");
+            case ConstantPool.CONSTANT_Float:
+                return floatValue(((CONSTANT_Float_info) info).value);
+            case ConstantPool.CONSTANT_Double:
+                return doubleValue(((CONSTANT_Double_info) info).value);
+            case ConstantPool.CONSTANT_String:
+                return quote(utf8(((CONSTANT_String_info) info).string_index));
+            case ConstantPool.CONSTANT_Class:
+                return krakatau ? "Class " + className(index) : null;
+            case ConstantPool.CONSTANT_MethodType:
+                return krakatau ? "MethodType " + quote(utf8(((CONSTANT_MethodType_info) info).descriptor_index)) : null;
+            case ConstantPool.CONSTANT_MethodHandle:
+                return krakatau ? methodHandle((CONSTANT_MethodHandle_info) info) : null;
+            case ConstantPool.CONSTANT_Dynamic:
+                return krakatau ? dynamic("Dynamic", index) : null;
+            default:
+                return report("unexpected constant #" + index);
+        }
+    }
+
+    private String floatValue(float f) {
+        if (Float.isNaN(f))
+            return krakatau ? "+NaNf" : "+FloatNaN";
+        if (Float.isInfinite(f))
+            return (f > 0 ? "+" : "-") + (krakatau ? "Infinityf" : "FloatInfinity");
+        return Float.toString(f) + (krakatau ? "f" : "");
+    }
+
+    private String doubleValue(double d) {
+        if (Double.isNaN(d))
+            return krakatau ? "+NaN" : "+DoubleNaN";
+        if (Double.isInfinite(d))
+            return (d > 0 ? "+" : "-") + (krakatau ? "Infinity" : "DoubleInfinity");
+        return Double.toString(d);
+    }
+
+    private String methodHandle(CONSTANT_MethodHandle_info info) {
+        String kind = info.reference_kind.name().substring("REF_".length());
+        return "MethodHandle " + kind + " " + memberRef(info.reference_index, true);
+    }
+
+    /*
+     *  Returns a dynamically-computed constant or call site, in Krakatau syntax:
+     *  the kind, the bootstrap method handle and its static arguments, then the
+     *  name and type after a colon.
+     */
+    private String dynamic(String kind, int index) {
+        try {
+            CPInfo info = constant_pool.get(index);
+            int bsmIndex;
+            CONSTANT_NameAndType_info nat;
+            if (info instanceof CONSTANT_InvokeDynamic_info) {
+                CONSTANT_InvokeDynamic_info indy = (CONSTANT_InvokeDynamic_info) info;
+                bsmIndex = indy.bootstrap_method_attr_index;
+                nat = indy.getNameAndTypeInfo();
+            } else {
+                CONSTANT_Dynamic_info condy = (CONSTANT_Dynamic_info) info;
+                bsmIndex = condy.bootstrap_method_attr_index;
+                nat = condy.getNameAndTypeInfo();
+            }
+            if (bsmIndex >= bootstrapMethods.length)
+                return report("bad bootstrap method index " + bsmIndex);
+            BootstrapMethodSpecifier bsm = bootstrapMethods[bsmIndex];
+            CONSTANT_MethodHandle_info mh =
+                    (CONSTANT_MethodHandle_info) constant_pool.get(bsm.bootstrap_method_ref);
+            StringBuilder sb = new StringBuilder(kind);
+            sb.append(" ").append(methodHandle(mh).substring("MethodHandle ".length()));
+            for (int arg: bsm.bootstrap_arguments)
+                sb.append(" ").append(constant(arg));
+            sb.append(" : ").append(word(nat.getName())).append(" ").append(word(nat.getType()));
+            return sb.toString();
+        } catch (ConstantPoolException | ClassCastException e) {
+            return report("bad dynamic constant #" + index);
+        }
+    }
+
+    /*
+     *  Returns a reference to a field or method. In Jasmin syntax, a reference to
+     *  an interface method can only be given to invokeinterface, for which the
+     *  interface is implied; null is returned for any other reference to an
+     *  interface method, unless anyInterface is set.
+     */
+    private String memberRef(int index, boolean anyInterface) {
+        try {
+            CPRefInfo ref = (CPRefInfo) constant_pool.get(index);
+            String cls = className(ref.class_index);
+            CONSTANT_NameAndType_info nat = ref.getNameAndTypeInfo();
+            String name = nat.getName();
+            String type = nat.getType();
+            if (krakatau) {
+                String kind;
+                switch (ref.getTag()) {
+                    case ConstantPool.CONSTANT_Fieldref:
+                        kind = "Field";
+                        break;
+                    case ConstantPool.CONSTANT_InterfaceMethodref:
+                        kind = "InterfaceMethod";
+                        break;
+                    default:
+                        kind = "Method";
+                }
+                return kind + " " + cls + " " + word(name) + " " + word(type);
+            }
+            if (ref.getTag() == ConstantPool.CONSTANT_InterfaceMethodref && !anyInterface)
+                return null;
+            return (ref.getTag() == ConstantPool.CONSTANT_Fieldref)
+                    ? cls + "/" + name + " " + type
+                    : cls + "/" + name + type;
+        } catch (ConstantPoolException | ClassCastException e) {
+            return report("bad member reference #" + index);
+        }
+    }
+
+    private String className(int index) {
+        try {
+            return word(constant_pool.getClassInfo(index).getName());
+        } catch (ConstantPoolException e) {
+            return report(e);
+        }
+    }
+
+    private String optClassName(int index) {
+        return (index == 0) ? "[0]" : className(index);
+    }
+
+    private String classNames(int[] indexes) {
+        StringBuilder sb = new StringBuilder();
+        for (int i: indexes)
+            sb.append(" ").append(className(i));
+        return sb.toString();
+    }
+
+    private String utf8(int index) {
+        try {
+            return constant_pool.getUTF8Value(index);
+        } catch (ConstantPoolException e) {
+            return report(e);
+        }
+    }
+
+    private String attrName(Attribute attr) {
+        try {
+            return attr.getName(constant_pool);
+        } catch (ConstantPoolException e) {
+            return report(e);
+        }
+    }
+
+    private String label(int pc) {
+        return "L" + pc;
+    }
+
+    private String syntaxName() {
+        return krakatau ? "Krakatau" : "Jasmin";
+    }
+
+    private void writeUnsupported(String attrName) {
+        println(unsupported(attrName));
+    }
+
+    private String unsupported(String attrName) {
+        unsupported++;
+        return "; the " + attrName + " attribute cannot be written in " + syntaxName() + " syntax";
+    }
+
+    /*
+     *  Returns the access flags that have names in the given table, each followed
+     *  by a space, in the order of the table.
+     */
+    private String flags(int flags, Object[][] table) {
+        StringBuilder sb = new StringBuilder();
+        for (Object[] entry: table) {
+            if ((flags & (Integer) entry[0]) != 0)
+                sb.append(entry[1]).append(" ");
+        }
+        return sb.toString();
+    }
+
+    /*
+     *  Returns a name or descriptor as a single token, quoting it if it contains
+     *  characters that would end or confuse a token.
+     */
+    private static String word(String s) {
+        if (!s.isEmpty() && s.chars().allMatch(c -> c > ' ' && c < 0x7f && "\"':#=".indexOf(c) < 0))
+            return s;
+        return quote(s, '\'');
+    }
+
+    private static String quote(String s) {
+        return quote(s, '"');
+    }
+
+    private static String quote(String s, char q) {
+        StringBuilder sb = new StringBuilder();
+        sb.append(q);
+        for (int i = 0; i < s.length(); i++) {
+            char c = s.charAt(i);
+            switch (c) {
+                case '\\':
+                    sb.append("\\\\");
+                    break;
+                case '\n':
+                    sb.append("\\n");
+                    break;
+                case '\r':
+                    sb.append("\\r");
+                    break;
+                case '\t':
+                    sb.append("\\t");
+                    break;
+                default:
+                    if (c == q)
+                        sb.append('\\').append(c);
+                    else if (c >= ' ' && c < 0x7f)
+                        sb.append(c);
+                    else
+                        sb.append(String.format("\\u%04x", (int) c));
+            }
+        }
+        sb.append(q);
+        return sb.toString();
+    }
+
+    private static final Object[][] CLASS_FLAGS = {
+        { ACC_PUBLIC, "public" },
+        { ACC_FINAL, "final" },
+        { ACC_SUPER, "super" },
+        { ACC_INTERFACE, "interface" },
+        { ACC_ABSTRACT, "abstract" },
+        { ACC_SYNTHETIC, "synthetic" },
+        { ACC_ANNOTATION, "annotation" },
+        { ACC_ENUM, "enum" },
+        { ACC_MODULE, "module" }
+    };
+
+    private static final Object[][] INNER_CLASS_FLAGS = {
+        { ACC_PUBLIC, "public" },
+        { ACC_PRIVATE, "private" },
+        { ACC_PROTECTED, "protected" },
+        { ACC_STATIC, "static" },
+        { ACC_FINAL, "final" },
+        { ACC_INTERFACE, "interface" },
+        { ACC_ABSTRACT, "abstract" },
+        { ACC_SYNTHETIC, "synthetic" },
+        { ACC_ANNOTATION, "annotation" },
+        { ACC_ENUM, "enum" }
+    };
+
+    private static final Object[][] FIELD_FLAGS = {
+        { ACC_PUBLIC, "public" },
+        { ACC_PRIVATE, "private" },
+        { ACC_PROTECTED, "protected" },
+        { ACC_STATIC, "static" },
+        { ACC_FINAL, "final" },
+        { ACC_VOLATILE, "volatile" },
+        { ACC_TRANSIENT, "transient" },
+        { ACC_SYNTHETIC, "synthetic" },
+        { ACC_ENUM, "enum" }
+    };
+
+    private static final Object[][] PARAMETER_FLAGS = {
+        { ACC_FINAL, "final" },
+        { ACC_SYNTHETIC, "synthetic" },
+        { ACC_MANDATED, "mandated" }
+    };
+
+    private static final Object[][] MODULE_FLAGS = {
+        { Module_attribute.ACC_OPEN, "open" },
+        { ACC_SYNTHETIC, "synthetic" },
+        { ACC_MANDATED, "mandated" }
+    };
+
+    private static final Object[][] REQUIRES_FLAGS = {
+        { Module_attribute.ACC_TRANSITIVE, "transitive" },
+        { Module_attribute.ACC_STATIC_PHASE, "static_phase" },
+        { ACC_SYNTHETIC, "synthetic" },
+        { ACC_MANDATED, "mandated" }
+    };
+
+    private static final Object[][] EXPORTS_FLAGS = {
+        { ACC_SYNTHETIC, "synthetic" },
+        { ACC_MANDATED, "mandated" }
+    };
+
+    /*
+     *  The kinds of the element values of annotations that are constants,
+     *  by their tags, other than string.
+     */
+    private static final Map<Character, String> ELEMENT_VALUE_KINDS = Map.of(
+        'B', "byte",
+        'C', "char",
+        'D', "double",
+        'F', "float",
+        'I', "int",
+        'J', "long",
+        'S', "short",
+        'Z', "boolean");
+
+    /*
+     *  The classes of the attributes that are written by their own directives,
+     *  or that are used to write other directives, by their names.
+     */
+    private static final Map<String, Class<? extends Attribute>> ATTRIBUTE_CLASSES = Map.ofEntries(
+        Map.entry(Attribute.AnnotationDefault, AnnotationDefault_attribute.class),
+        Map.entry(Attribute.BootstrapMethods, BootstrapMethods_attribute.class),
+        Map.entry(Attribute.Code, Code_attribute.class),
+        Map.entry(Attribute.ConstantValue, ConstantValue_attribute.class),
+        Map.entry(Attribute.Deprecated, Deprecated_attribute.class),
+        Map.entry(Attribute.EnclosingMethod, EnclosingMethod_attribute.class),
+        Map.entry(Attribute.Exceptions, Exceptions_attribute.class),
+        Map.entry(Attribute.InnerClasses, InnerClasses_attribute.class),
+        Map.entry(Attribute.LineNumberTable, LineNumberTable_attribute.class),
+        Map.entry(Attribute.LocalVariableTable, LocalVariableTable_attribute.class),
+        Map.entry(Attribute.LocalVariableTypeTable, LocalVariableTypeTable_attribute.class),
+        Map.entry(Attribute.MethodParameters, MethodParameters_attribute.class),
+        Map.entry(Attribute.Module, Module_attribute.class),
+        Map.entry(Attribute.ModuleMainClass, ModuleMainClass_attribute.class),
+        Map.entry(Attribute.ModulePackages, ModulePackages_attribute.class),
+        Map.entry(Attribute.ModuleResolution, ModuleResolution_attribute.class),
+        Map.entry(Attribute.NestHost, NestHost_attribute.class),
+        Map.entry(Attribute.NestMembers, NestMembers_attribute.class),
+        Map.entry(Attribute.PermittedSubclasses, PermittedSubclasses_attribute.class),
+        Map.entry(Attribute.Record, Record_attribute.class),
+        Map.entry(Attribute.RuntimeVisibleAnnotations, RuntimeAnnotations_attribute.class),
+        Map.entry(Attribute.RuntimeInvisibleAnnotations, RuntimeAnnotations_attribute.class),
+        Map.entry(Attribute.RuntimeVisibleParameterAnnotations, RuntimeParameterAnnotations_attribute.class),
+        Map.entry(Attribute.RuntimeInvisibleParameterAnnotations, RuntimeParameterAnnotations_attribute.class),
+        Map.entry(Attribute.RuntimeVisibleTypeAnnotations, RuntimeTypeAnnotations_attribute.class),
+        Map.entry(Attribute.RuntimeInvisibleTypeAnnotations, RuntimeTypeAnnotations_attribute.class),
+        Map.entry(Attribute.Signature, Signature_attribute.class),
+        Map.entry(Attribute.SourceDebugExtension, SourceDebugExtension_attribute.class),
+        Map.entry(Attribute.SourceFile, SourceFile_attribute.class),
+        Map.entry(Attribute.StackMapTable, StackMapTable_attribute.class),
+        Map.entry(Attribute.Synthetic, Synthetic_attribute.class));
+
+    private static final Object[][] METHOD_FLAGS = {
+        { ACC_PUBLIC, "public" },
+        { ACC_PRIVATE, "private" },
+        { ACC_PROTECTED, "protected" },
+        { ACC_STATIC, "static" },
+        { ACC_FINAL, "final" },
+        { ACC_SYNCHRONIZED, "synchronized" },
+        { ACC_BRIDGE, "bridge" },
+        { ACC_VARARGS, "varargs" },
+        { ACC_NATIVE, "native" },
+        { ACC_ABSTRACT, "abstract" },
+        { ACC_STRICT, "strict" },
+        { ACC_SYNTHETIC, "synthetic" }
+    };
+
+    private final Options options;
+    private final ClassWriter classWriter;
+    private final ConstantWriter constantWriter;
+    private ClassFile cf;
+    private ConstantPool constant_pool;
+    private BootstrapMethodSpecifier[] bootstrapMethods;
+    private boolean krakatau;
+    private TreeSet<Integer> labels;
+    private int unsupported;
+    private String unsupportedOperand;
+}
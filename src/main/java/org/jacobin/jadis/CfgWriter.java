@@ -0,0 +1,224 @@
+/*
+ * Copyright (c) 2021 by Andrew Binstock.
+ *
+ * Portions of this file are copyright Oracle Corp.
+ * Those portions are licensed under GPL v. 2.0
+ * with the Oracle classpath exception. Due to the
+ * requirements of that license, the portions that
+ * are copyrighted by Andrew Binstock are licensed
+ * using the same terms and requirements.
+ *
+ */
+
+package org.jacobin.jadis;
+
+import java.util.Locale;
+import java.util.regex.Pattern;
+
+import org.jacobin.jadis.classfile.Attribute;
+import org.jacobin.jadis.classfile.ClassFile;
+import org.jacobin.jadis.classfile.Code_attribute;
+import org.jacobin.jadis.classfile.ConstantPoolException;
+import org.jacobin.jadis.classfile.Instruction;
+import org.jacobin.jadis.classfile.Instruction.TypeKind;
+import org.jacobin.jadis.classfile.Method;
+
+/*
+ *  Writes the control-flow graph of each method of a class, for --cfg=dot,
+ *  as a graph in the DOT language of Graphviz. Each node is a basic block,
+ *  labelled with its instructions, and each edge is labelled with the kind
+ *  of branch, the cases of a switch, or the type of exception caught.
+ *  The graphs may be limited to the methods given by --cfg-method.
+ *
+ *  <p><b>This is NOT part of any supported API.
+ *  If you write code that depends on this, you do so at your own risk.
+ *  This code and its internal interfaces are subject to change or
+ *  deletion without notice.</b>
+ */
+public class CfgWriter extends BasicWriter {
+    static CfgWriter instance(Context context) {
+        CfgWriter instance = context.get(CfgWriter.class);
+        if (instance == null)
+            instance = new CfgWriter(context);
+        return instance;
+    }
+
+    protected CfgWriter(Context context) {
+        super(context);
+        context.put(CfgWriter.class, this);
+        classWriter = ClassWriter.instance(context);
+        constantWriter = ConstantWriter.instance(context);
+        options = Options.instance(context);
+    }
+
+    /**
+     * Writes a graph for each method of a class that has code, and a name
+     * that matches the pattern given by --cfg-method, if any.
+     * @param cf the class file
+     */
+    public void write(ClassFile cf) {
+        classWriter.setClassFile(cf);   // for the values of constants
+        Pattern pattern = (options.cfgMethod == null) ? null : globPattern(options.cfgMethod);
+        for (Method m: cf.methods) {
+            Attribute a = m.attributes.get(Attribute.Code);
+            if (!(a instanceof Code_attribute))
+                continue;
+            String className, name, descriptor;
+            try {
+                className = cf.getName();
+                name = m.getName(cf.constant_pool);
+                descriptor = m.descriptor.getValue(cf.constant_pool);
+            } catch (ConstantPoolException e) {
+                report(e);
+                continue;
+            }
+            if (pattern == null || pattern.matcher(name).matches())
+                write(className + "." + name + descriptor, new ControlFlowGraph((Code_attribute) a));
+        }
+    }
+
+    private void write(String name, ControlFlowGraph graph) {
+        println("digraph " + quote(name) + " {");
+        indent(+1);
+        println("node [shape=box, fontname=\"monospace\"];");
+        for (ControlFlowGraph.Block b: graph.getBlocks()) {
+            StringBuilder label = new StringBuilder();
+            for (Instruction instr: b.instructions)
+                label.append(instr.getPC()).append(": ").append(toString(instr)).append("\n");
+            println(nodeName(b) + " [label=" + quote(label.toString()) + "];");
+        }
+        for (ControlFlowGraph.Block b: graph.getBlocks()) {
+            for (ControlFlowGraph.Edge e: b.successors)
+                println(nodeName(b) + " -> " + nodeName(e.target) + edgeAttributes(e) + ";");
+        }
+        indent(-1);
+        println("}");
+    }
+
+    private String edgeAttributes(ControlFlowGraph.Edge e) {
+        switch (e.kind) {
+            case FALLTHROUGH:
+                return "";
+            case SWITCH:
+                return " [label=" + quote(e.cases == null ? "default" : "case " + e.cases) + "]";
+            case JSR:
+            case RET:
+                return " [label=" + quote(e.kind.name().toLowerCase(Locale.US)) + ", style=dotted]";
+            case EXCEPTION:
+                String type = (e.catchType == 0) ? "any" : constantWriter.stringValue(e.catchType);
+                return " [label=" + quote(type) + ", style=dashed]";
+            default:
+                return " [label=" + quote(e.kind.name().toLowerCase(Locale.US)) + "]";
+        }
+    }
+
+    private static String nodeName(ControlFlowGraph.Block b) {
+        return "B" + b.start;
+    }
+
+    /*
+     * Returns a string in the DOT language, in which each newline ends
+     * a left-justified line.
+     */
+    private static String quote(String s) {
+        StringBuilder sb = new StringBuilder("\"");
+        for (int i = 0; i < s.length(); i++) {
+            char c = s.charAt(i);
+            switch (c) {
+                case '"':  sb.append("\\\""); break;
+                case '\\': sb.append("\\\\"); break;
+                case '\n': sb.append("\\l"); break;
+                default:   sb.append(c);
+            }
+        }
+        return sb.append("\"").toString();
+    }
+
+    /*
+     * Returns a pattern for a glob, in which * matches any characters,
+     * and ? matches any one character.
+     */
+    private static Pattern globPattern(String glob) {
+        StringBuilder sb = new StringBuilder();
+        for (String s: glob.split("((?<=[*?])|(?=[*?]))")) {
+            switch (s) {
+                case "*": sb.append(".*"); break;
+                case "?": sb.append("."); break;
+                default:  sb.append(Pattern.quote(s));
+            }
+        }
+        return Pattern.compile(sb.toString());
+    }
+
+    /*
+     * Returns an instruction with its operands, as CodeWriter writes them,
+     * but on one line.
+     */
+    String toString(Instruction instr) {
+        return instr.getMnemonic() + instr.accept(operandWriter, null);
+    }
+
+    /*
+     * Writes the operands of an instruction, as CodeWriter does, but on one line.
+     */
+    private final Instruction.KindVisitor<String, Void> operandWriter = new Instruction.KindVisitor<>() {
+        public String visitNoOperands(Instruction instr, Void p) {
+            return "";
+        }
+
+        public String visitArrayType(Instruction instr, TypeKind kind, Void p) {
+            return " " + kind.name;
+        }
+
+        public String visitBranch(Instruction instr, int offset, Void p) {
+            return " " + (instr.getPC() + offset);
+        }
+
+        public String visitConstantPoolRef(Instruction instr, int index, Void p) {
+            return " #" + index + "  // " + constant(index);
+        }
+
+        public String visitConstantPoolRefAndValue(Instruction instr, int index, int value, Void p) {
+            return " #" + index + ",  " + value + "  // " + constant(index);
+        }
+
+        public String visitLocal(Instruction instr, int index, Void p) {
+            return " " + index;
+        }
+
+        public String visitLocalAndValue(Instruction instr, int index, int value, Void p) {
+            return " " + index + ", " + value;
+        }
+
+        public String visitLookupSwitch(Instruction instr,
+                int default_, int npairs, int[] matches, int[] offsets, Void p) {
+            return " { // " + npairs + " }";
+        }
+
+        public String visitTableSwitch(Instruction instr,
+                int default_, int low, int high, int[] offsets, Void p) {
+            return " { // " + low + " to " + high + " }";
+        }
+
+        public String visitValue(Instruction instr, int value, Void p) {
+            return " " + value;
+        }
+
+        public String visitUnknown(Instruction instr, Void p) {
+            return "";
+        }
+    };
+
+    private String constant(int index) {
+        try {
+            return constantWriter.tagName(classWriter.getClassFile().constant_pool.get(index).getTag())
+                    + " " + constantWriter.stringValue(index);
+        } catch (ConstantPoolException e) {
+            return report(e);
+        }
+    }
+
+    private final ClassWriter classWriter;
+    private final ConstantWriter constantWriter;
+    private final Options options;
+}
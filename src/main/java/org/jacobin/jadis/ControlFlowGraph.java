@@ -0,0 +1,333 @@
+/*
+ * Copyright (c) 2021 by Andrew Binstock.
+ *
+ * Portions of this file are copyright Oracle Corp.
+ * Those portions are licensed under GPL v. 2.0
+ * with the Oracle classpath exception. Due to the
+ * requirements of that license, the portions that
+ * are copyrighted by Andrew Binstock are licensed
+ * using the same terms and requirements.
+ *
+ */
+
+package org.jacobin.jadis;
+
+import java.util.ArrayDeque;
+import java.util.ArrayList;
+import java.util.Deque;
+import java.util.HashMap;
+import java.util.HashSet;
+import java.util.LinkedHashMap;
+import java.util.LinkedHashSet;
+import java.util.List;
+import java.util.Map;
+import java.util.Objects;
+import java.util.Set;
+import java.util.TreeSet;
+
+import org.jacobin.jadis.classfile.Code_attribute;
+import org.jacobin.jadis.classfile.Instruction;
+import org.jacobin.jadis.classfile.Opcode;
+
+/*
+ *  The control-flow graph of a Code attribute, as basic blocks and the edges
+ *  between them, for --cfg.
+ *
+ *  A block begins at the start of the code, at the target of a branch, switch
+ *  or jsr, at the start, end and handler of each exception table entry, and
+ *  after an instruction that does not simply go on to the next one. Each block
+ *  within the range of an exception table entry has an edge to the handler.
+ *
+ *  A jsr has an edge to its subroutine, but not to the instruction after it;
+ *  instead, each ret in the subroutine has an edge to the instruction after
+ *  each jsr to the subroutine. The rets of a subroutine are those that can be
+ *  reached from its start without following a ret, taking each jsr within it
+ *  to return to the instruction after the jsr.
+ *
+ *  <p><b>This is NOT part of any supported API.
+ *  If you write code that depends on this, you do so at your own risk.
+ *  This code and its internal interfaces are subject to change or
+ *  deletion without notice.</b>
+ */
+public class ControlFlowGraph {
+    /**
+     * The kinds of edges between blocks.
+     */
+    public enum EdgeKind {
+        /** The next block, after an instruction that is not a branch. */
+        FALLTHROUGH,
+        /** The target of goto or goto_w. */
+        GOTO,
+        /** The target of a conditional branch, when it is taken. */
+        TRUE,
+        /** The next block after a conditional branch, when it is not taken. */
+        FALSE,
+        /** The target of a case, or the default, of a switch. */
+        SWITCH,
+        /** The start of a subroutine, from jsr or jsr_w. */
+        JSR,
+        /** The instruction after a jsr, from a ret in the subroutine. */
+        RET,
+        /** The handler of an exception table entry. */
+        EXCEPTION
+    }
+
+    public static class Block {
+        Block(int start) {
+            this.start = start;
+        }
+
+        /** The pc of the first instruction. */
+        public final int start;
+        /** The instructions, in order. */
+        public final List<Instruction> instructions = new ArrayList<>();
+        /** The edges to the blocks that may follow this one. */
+        public final List<Edge> successors = new ArrayList<>();
+
+        Instruction last() {
+            return instructions.get(instructions.size() - 1);
+        }
+
+        int next() {
+            Instruction last = last();
+            return last.getPC() + last.length();
+        }
+    }
+
+    public static class Edge {
+        Edge(Block target, EdgeKind kind, String cases, int catchType) {
+            this.target = target;
+            this.kind = kind;
+            this.cases = cases;
+            this.catchType = catchType;
+        }
+
+        public final Block target;
+        public final EdgeKind kind;
+        /** For SWITCH, the values of the cases, as "1, 2", or null for the default. */
+        public final String cases;
+        /** For EXCEPTION, the constant pool index of the type caught, or 0 for any. */
+        public final int catchType;
+    }
+
+    /**
+     * Creates the control-flow graph of a Code attribute. If the code ends
+     * with an incomplete instruction, the graph ends before it.
+     * @param code the Code attribute
+     */
+    public ControlFlowGraph(Code_attribute code) {
+        List<Instruction> instrs = new ArrayList<>();
+        TreeSet<Integer> leaders = new TreeSet<>();
+        leaders.add(0);
+        try {
+            for (Instruction instr: code.getInstructions()) {
+                List<Integer> targets = getTargets(instr);
+                leaders.addAll(targets);
+                if (!targets.isEmpty() || endsFlow(instr.getOpcode()))
+                    leaders.add(instr.getPC() + instr.length());
+                instrs.add(instr);
+            }
+        } catch (ArrayIndexOutOfBoundsException e) {
+            // an incomplete instruction, as reported by CodeWriter
+        }
+        for (Code_attribute.Exception_data handler: code.exception_table) {
+            leaders.add(handler.start_pc);
+            leaders.add(handler.end_pc);
+            leaders.add(handler.handler_pc);
+        }
+
+        Block block = null;
+        for (Instruction instr: instrs) {
+            if (block == null || leaders.contains(instr.getPC())) {
+                block = new Block(instr.getPC());
+                blocks.add(block);
+                blocksByPC.put(block.start, block);
+            }
+            block.instructions.add(instr);
+        }
+
+        for (Block b: blocks)
+            addEdges(b, code);
+        addReturnEdges();
+    }
+
+    /**
+     * Returns the blocks, in the order of their instructions.
+     * @return the blocks
+     */
+    public List<Block> getBlocks() {
+        return blocks;
+    }
+
+    private void addEdges(Block b, Code_attribute code) {
+        Instruction last = b.last();
+        Opcode opcode = last.getOpcode();
+        List<Integer> targets = getTargets(last);
+        if (opcode == Opcode.TABLESWITCH || opcode == Opcode.LOOKUPSWITCH) {
+            // one edge for each target, and one for the default, which is the first target
+            Map<Integer, List<Integer>> cases = new LinkedHashMap<>();
+            int[] values = getSwitchValues(last);
+            for (int i = 1; i < targets.size(); i++)
+                cases.computeIfAbsent(targets.get(i), t -> new ArrayList<>()).add(values[i - 1]);
+            for (Map.Entry<Integer, List<Integer>> e: cases.entrySet()) {
+                StringBuilder sb = new StringBuilder();
+                for (int v: e.getValue())
+                    sb.append(sb.length() == 0 ? "" : ", ").append(v);
+                addEdge(b, e.getKey(), EdgeKind.SWITCH, sb.toString(), 0);
+            }
+            addEdge(b, targets.get(0), EdgeKind.SWITCH, null, 0);
+        } else if (opcode == Opcode.GOTO || opcode == Opcode.GOTO_W) {
+            addEdge(b, targets.get(0), EdgeKind.GOTO, null, 0);
+        } else if (opcode == Opcode.JSR || opcode == Opcode.JSR_W) {
+            addEdge(b, targets.get(0), EdgeKind.JSR, null, 0);
+        } else if (!targets.isEmpty()) {
+            addEdge(b, targets.get(0), EdgeKind.TRUE, null, 0);
+            addEdge(b, b.next(), EdgeKind.FALSE, null, 0);
+        } else if (!endsFlow(opcode)) {
+            addEdge(b, b.next(), EdgeKind.FALLTHROUGH, null, 0);
+        }
+
+        for (Code_attribute.Exception_data handler: code.exception_table) {
+            if (b.start >= handler.start_pc && b.start < handler.end_pc)
+                addEdge(b, handler.handler_pc, EdgeKind.EXCEPTION, null, handler.catch_type);
+        }
+    }
+
+    private void addEdge(Block from, int pc, EdgeKind kind, String cases, int catchType) {
+        Block to = blocksByPC.get(pc);
+        if (to == null)
+            return;     // not the start of an instruction
+        for (Edge e: from.successors) {
+            if (e.target == to && e.kind == kind && Objects.equals(e.cases, cases) && e.catchType == catchType)
+                return;
+        }
+        from.successors.add(new Edge(to, kind, cases, catchType));
+    }
+
+    /*
+     * Adds the edges from each ret to the instructions after the jsrs
+     * to its subroutine.
+     */
+    private void addReturnEdges() {
+        Map<Block, Set<Block>> rets = new HashMap<>();
+        for (Block b: blocks) {
+            Opcode opcode = b.last().getOpcode();
+            if (opcode != Opcode.JSR && opcode != Opcode.JSR_W)
+                continue;
+            Block subroutine = b.successors.isEmpty() ? null : b.successors.get(0).target;
+            Block next = blocksByPC.get(b.next());
+            if (subroutine == null || next == null)
+                continue;
+            for (Block ret: rets.computeIfAbsent(subroutine, this::getRets))
+                addEdge(ret, next.start, EdgeKind.RET, null, 0);
+        }
+    }
+
+    /*
+     * Returns the blocks that end with a ret for a subroutine.
+     */
+    private Set<Block> getRets(Block subroutine) {
+        Set<Block> rets = new LinkedHashSet<>();
+        Set<Block> seen = new HashSet<>();
+        Deque<Block> work = new ArrayDeque<>();
+        work.add(subroutine);
+        while (!work.isEmpty()) {
+            Block b = work.remove();
+            if (!seen.add(b))
+                continue;
+            Opcode opcode = b.last().getOpcode();
+            if (opcode == Opcode.RET || opcode == Opcode.RET_W) {
+                rets.add(b);
+            } else if (opcode == Opcode.JSR || opcode == Opcode.JSR_W) {
+                Block next = blocksByPC.get(b.next());
+                if (next != null)
+                    work.add(next);
+            } else {
+                for (Edge e: b.successors) {
+                    if (e.kind != EdgeKind.EXCEPTION)
+                        work.add(e.target);
+                }
+            }
+        }
+        return rets;
+    }
+
+    /*
+     * Returns the pcs of the targets of a branch, jsr or switch, with the
+     * default first for a switch, or an empty list for other instructions.
+     */
+    static List<Integer> getTargets(Instruction instr) {
+        List<Integer> targets = new ArrayList<>();
+        int pc = instr.getPC();
+        Opcode opcode = instr.getOpcode();
+        if (opcode == null)
+            return targets;
+        switch (opcode.kind) {
+            case BRANCH:
+                targets.add(pc + instr.getShort(1));
+                break;
+            case BRANCH_W:
+                targets.add(pc + instr.getInt(1));
+                break;
+            case DYNAMIC:
+                if (opcode == Opcode.TABLESWITCH || opcode == Opcode.LOOKUPSWITCH) {
+                    int pad = ((pc + 4) & ~3) - pc;
+                    targets.add(pc + instr.getInt(pad));
+                    if (opcode == Opcode.TABLESWITCH) {
+                        int n = instr.getInt(pad + 8) - instr.getInt(pad + 4) + 1;
+                        for (int i = 0; i < n; i++)
+                            targets.add(pc + instr.getInt(pad + 12 + 4 * i));
+                    } else {
+                        int npairs = instr.getInt(pad + 4);
+                        for (int i = 0; i < npairs; i++)
+                            targets.add(pc + instr.getInt(pad + 12 + 8 * i));
+                    }
+                }
+                break;
+        }
+        return targets;
+    }
+
+    /*
+     * Returns the values of the cases of a switch, in the order of the targets.
+     */
+    private static int[] getSwitchValues(Instruction instr) {
+        int pc = instr.getPC();
+        int pad = ((pc + 4) & ~3) - pc;
+        if (instr.getOpcode() == Opcode.TABLESWITCH) {
+            int low = instr.getInt(pad + 4);
+            int[] values = new int[instr.getInt(pad + 8) - low + 1];
+            for (int i = 0; i < values.length; i++)
+                values[i] = low + i;
+            return values;
+        } else {
+            int[] values = new int[instr.getInt(pad + 4)];
+            for (int i = 0; i < values.length; i++)
+                values[i] = instr.getInt(pad + 8 + 8 * i);
+            return values;
+        }
+    }
+
+    /*
+     * Returns whether an instruction never goes on to the next one,
+     * apart from by a branch or switch.
+     */
+    static boolean endsFlow(Opcode opcode) {
+        if (opcode == null)
+            return false;
+        switch (opcode) {
+            case GOTO: case GOTO_W:
+            case JSR: case JSR_W:
+            case RET: case RET_W:
+            case TABLESWITCH: case LOOKUPSWITCH:
+            case IRETURN: case LRETURN: case FRETURN: case DRETURN: case ARETURN: case RETURN:
+            case ATHROW:
+                return true;
+            default:
+                return false;
+        }
+    }
+
+    private final List<Block> blocks = new ArrayList<>();
+    private final Map<Integer, Block> blocksByPC = new HashMap<>();
+}
@@ -0,0 +1,227 @@
+/*
+ * Copyright (c) 2021 by Andrew Binstock.
+ *
+ * Portions of this file are copyright Oracle Corp.
+ * Those portions are licensed under GPL v. 2.0
+ * with the Oracle classpath exception. Due to the
+ * requirements of that license, the portions that
+ * are copyrighted by Andrew Binstock are licensed
+ * using the same terms and requirements.
+ *
+ */
+
+package org.jacobin.jadis;
+
+import java.io.PrintWriter;
+import java.util.HashSet;
+import java.util.Set;
+import java.util.regex.Matcher;
+import java.util.regex.Pattern;
+
+import org.jacobin.jadis.classfile.ClassFile;
+import org.jacobin.jadis.classfile.ConstantPoolException;
+import org.jacobin.jadis.classfile.Method;
+
+/*
+ *  Writes classes as a standalone HTML page, for --format=html. Each class is
+ *  written by ClassWriter, as for text, into a section of the page; the other
+ *  writers call the methods here to add the anchors and links, which are only
+ *  written for this format. References to the constant pool, such as #12, link
+ *  to the entry, branch offsets link to the target instruction, and invoked
+ *  methods link to their definitions, if they are written on the same page.
+ *
+ *  Since a method may be invoked before the class that defines it is written,
+ *  the sections are written to a buffer, and the page is written by writePage
+ *  once all the classes have been seen.
+ *
+ *  <p><b>This is NOT part of any supported API.
+ *  If you write code that depends on this, you do so at your own risk.
+ *  This code and its internal interfaces are subject to change or
+ *  deletion without notice.</b>
+ */
+public class HtmlWriter extends BasicWriter {
+    static HtmlWriter instance(Context context) {
+        HtmlWriter instance = context.get(HtmlWriter.class);
+        if (instance == null)
+            instance = new HtmlWriter(context);
+        return instance;
+    }
+
+    protected HtmlWriter(Context context) {
+        super(context);
+        context.put(HtmlWriter.class, this);
+        classWriter = ClassWriter.instance(context);
+        options = Options.instance(context);
+    }
+
+    /**
+     * Writes a class as a section of the page, headed by the name of the
+     * class as in the text output.
+     * @param cf the class file
+     */
+    public void write(ClassFile cf) {
+        classCount++;
+        markup("<section id=\"" + classId() + "\"><h2>"
+                + escape(messages.getMessage("html.class.heading", classWriter.getJavaName(cf))) + "</h2><pre>");
+        classWriter.write(cf);
+        setPendingNewline(false);
+        markup("</pre></section>");
+        println();
+    }
+
+    /**
+     * Writes the page, given the sections written for the classes. Links
+     * to methods that are not defined on the page are removed.
+     * @param sections the sections, as written by {@link #write}
+     * @param out where to write the page
+     */
+    public void writePage(String sections, PrintWriter out) {
+        String title = escape(messages.getMessage("html.title"));
+        out.println("<!DOCTYPE html>");
+        out.println("<html>");
+        out.println("<head>");
+        out.println("<meta charset=\"utf-8\">");
+        out.println("<title>" + title + "</title>");
+        out.println("<style>");
+        out.println("a { color: inherit; text-decoration: underline dotted; }");
+        out.println("span[title] { border-bottom: 1px dotted gray; }");
+        out.println(":target { background-color: #ffe680; }");
+        out.println("</style>");
+        out.println("</head>");
+        out.println("<body>");
+        out.println("<h1>" + title + "</h1>");
+        Matcher m = METHOD_LINK.matcher(sections);
+        StringBuilder sb = new StringBuilder();
+        while (m.find())
+            m.appendReplacement(sb, Matcher.quoteReplacement(methods.contains(m.group(1)) ? m.group() : m.group(2)));
+        m.appendTail(sb);
+        out.print(sb);
+        out.println("</body>");
+        out.println("</html>");
+        out.flush();
+    }
+
+    /*
+     * The ids of the elements of the page. The ids of constant pool entries
+     * and instructions are given for the current class and method.
+     */
+
+    String classId() {
+        return "c" + classCount;
+    }
+
+    String cpId(int index) {
+        return classId() + ".cp" + index;
+    }
+
+    String pcId(int pc) {
+        Method m = classWriter.getMethod();
+        if (m != method) {
+            method = m;
+            ClassFile cf = classWriter.getClassFile();
+            for (methodIndex = 0; methodIndex < cf.methods.length; methodIndex++) {
+                if (cf.methods[methodIndex] == m)
+                    break;
+            }
+        }
+        return classId() + ".m" + methodIndex + "." + pc;
+    }
+
+    /*
+     * The id of a method is given by its class, name and descriptor, so that
+     * it can be found from a reference in any class.
+     */
+    static String methodId(String className, String name, String descriptor) {
+        String s = className + "." + name + ":" + descriptor;
+        StringBuilder sb = new StringBuilder("m:");
+        for (int i = 0; i < s.length(); i++) {
+            char c = s.charAt(i);
+            if (c < 128 && (Character.isLetterOrDigit(c) || "./:$-()".indexOf(c) >= 0))
+                sb.append(c);
+            else
+                sb.append('_').append(Integer.toHexString(c)).append('_');
+        }
+        return sb.toString();
+    }
+
+    /*
+     * Writes a reference to a constant pool entry, as #index. The reference
+     * only links to the entry if the constant pool is written, with -v.
+     */
+    void printRef(int index) {
+        if (options.verbose)
+            printLink("#" + index, cpId(index));
+        else
+            print("#" + index);
+    }
+
+    /*
+     * Writes text that links to an element. Any leading spaces, used to align
+     * the text, are not part of the link.
+     */
+    void printLink(String text, String id) {
+        String trimmed = text.stripLeading();
+        print(text.substring(0, text.length() - trimmed.length()));
+        beginLink(id);
+        print(trimmed);
+        endLink();
+    }
+
+    void beginLink(String id) {
+        markup("<a href=\"#" + id + "\">");
+    }
+
+    /*
+     * Begins a link to a method, which is removed if the method is not
+     * defined on the page.
+     */
+    void beginMethodLink(String id) {
+        markup("<a class=\"method\" href=\"#" + id + "\">");
+    }
+
+    void endLink() {
+        markup("</a>");
+    }
+
+    /*
+     * Writes text that is the target of links, and which shows a title,
+     * if one is given, when the pointer is over it. As for a link, any
+     * leading spaces are not part of the target.
+     */
+    void printTarget(String text, String id, String title) {
+        String trimmed = text.stripLeading();
+        print(text.substring(0, text.length() - trimmed.length()));
+        text = trimmed;
+        markup("<span id=\"" + id + "\"" + (title == null ? "" : " title=\"" + escape(title) + "\"") + ">");
+        print(text);
+        markup("</span>");
+    }
+
+    /*
+     * Writes the target of links to a method, before the method is written.
+     */
+    void printMethodTarget(ClassFile cf, Method m) {
+        if (options.format != Options.Format.HTML)
+            return;
+        try {
+            String id = methodId(cf.getName(), m.getName(cf.constant_pool), m.descriptor.getValue(cf.constant_pool));
+            methods.add(id);
+            markup("<span id=\"" + id + "\"></span>");
+        } catch (ConstantPoolException e) {
+            // the method cannot be the target of a link
+        }
+    }
+
+    private static String escape(String s) {
+        return s.replace("&", "&amp;").replace("<", "&lt;").replace(">", "&gt;").replace("\"", "&quot;");
+    }
+
+    private static final Pattern METHOD_LINK = Pattern.compile("<a class=\"method\" href=\"#([^\"]*)\">(.*?)</a>");
+
+    private final ClassWriter classWriter;
+    private final Options options;
+    private final Set<String> methods = new HashSet<>();
+    private int classCount;
+    private Method method;
+    private int methodIndex;
+}
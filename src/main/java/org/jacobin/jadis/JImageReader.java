@@ -0,0 +1,542 @@
+/*
+ * Copyright (c) 2021 by Andrew Binstock.
+ *
+ * Portions of this file are copyright Oracle Corp.
+ * Those portions are licensed under GPL v. 2.0
+ * with the Oracle classpath exception. Due to the
+ * requirements of that license, the portions that
+ * are copyrighted by Andrew Binstock are licensed
+ * using the same terms and requirements.
+ *
+ */
+
+package org.jacobin.jadis;
+
+import java.io.ByteArrayInputStream;
+import java.io.ByteArrayOutputStream;
+import java.io.Closeable;
+import java.io.DataInputStream;
+import java.io.DataOutputStream;
+import java.io.EOFException;
+import java.io.IOException;
+import java.nio.ByteBuffer;
+import java.nio.ByteOrder;
+import java.nio.IntBuffer;
+import java.nio.channels.FileChannel;
+import java.nio.file.Path;
+import java.nio.file.StandardOpenOption;
+import java.util.ArrayList;
+import java.util.Comparator;
+import java.util.List;
+import java.util.Map;
+import java.util.Set;
+import java.util.TreeMap;
+import java.util.zip.DataFormatException;
+import java.util.zip.Inflater;
+
+/*
+ *  A reader for the jimage file (lib/modules) in which a JDK run-time image,
+ *  from JDK 9 onwards, stores the classes and resources of its modules.
+ *  The file is read directly, rather than through the jrt file system, so
+ *  that the image of any local JDK can be read, whatever JDK we are running on.
+ *
+ *  <p>The file starts with a header, followed by the index: a redirect
+ *  table and an offsets table for a perfect hash of the resource names,
+ *  the location attributes for each resource, and a table of strings.
+ *  The contents of the resources follow the index.
+ *
+ *  <p><b>This is NOT part of any supported API.
+ *  If you write code that depends on this, you do so at your own risk.
+ *  This code and its internal interfaces are subject to change or
+ *  deletion without notice.</b>
+ */
+public class JImageReader implements Closeable {
+    private static final int MAGIC = 0xCAFEDADA;
+    private static final int MAJOR_VERSION = 1;
+    private static final int HEADER_SIZE = 7 * 4;
+
+    private static final int HASH_MULTIPLIER = 0x01000193;
+    private static final int POSITIVE_MASK = 0x7FFFFFFF;
+
+    // location attribute kinds
+    private static final int ATTRIBUTE_END = 0;
+    private static final int ATTRIBUTE_MODULE = 1;
+    private static final int ATTRIBUTE_PARENT = 2;
+    private static final int ATTRIBUTE_BASE = 3;
+    private static final int ATTRIBUTE_EXTENSION = 4;
+    private static final int ATTRIBUTE_OFFSET = 5;
+    private static final int ATTRIBUTE_COMPRESSED = 6;
+    private static final int ATTRIBUTE_UNCOMPRESSED = 7;
+    private static final int ATTRIBUTE_COUNT = 8;
+
+    private static final int COMPRESSED_MAGIC = 0xCAFEFAFA;
+    private static final int COMPRESSED_HEADER_SIZE = 4 + 8 + 8 + 4 + 4 + 1;
+
+    // constant pool tags, including those used by the "compact-cp" decompressor
+    private static final int CONSTANT_Utf8 = 1;
+    private static final int CONSTANT_Long = 5;
+    private static final int CONSTANT_Double = 6;
+    private static final int EXTERNALIZED_STRING = 23;
+    private static final int EXTERNALIZED_STRING_DESCRIPTOR = 25;
+
+    // the size of the info of each other kind of constant pool entry
+    private static final int[] CONSTANT_SIZES = {
+        0, 0, 0, 4, 4, 8, 8, 2, 2, 4, 4, 4, 4, 0, 0, 3, 2, 4, 4, 2, 2
+    };
+
+    public static JImageReader open(Path file) throws IOException {
+        FileChannel channel = FileChannel.open(file, StandardOpenOption.READ);
+        try {
+            return new JImageReader(file, channel);
+        } catch (IOException | RuntimeException e) {
+            channel.close();
+            throw e;
+        }
+    }
+
+    private JImageReader(Path file, FileChannel channel) throws IOException {
+        this.file = file;
+        this.channel = channel;
+
+        ByteBuffer header = read(0, HEADER_SIZE);
+        if (header.order(ByteOrder.LITTLE_ENDIAN).getInt(0) == MAGIC)
+            order = ByteOrder.LITTLE_ENDIAN;
+        else if (header.order(ByteOrder.BIG_ENDIAN).getInt(0) == MAGIC)
+            order = ByteOrder.BIG_ENDIAN;
+        else
+            throw new IOException("not a jimage file: " + file);
+        header.order(order);
+
+        int version = header.getInt(4);
+        if ((version >>> 16) != MAJOR_VERSION)
+            throw new IOException("unsupported jimage version "
+                    + (version >>> 16) + "." + (version & 0xFFFF) + ": " + file);
+        int tableLength = header.getInt(16);
+        int locationsSize = header.getInt(20);
+        int stringsSize = header.getInt(24);
+        if (tableLength < 0 || locationsSize < 0 || stringsSize < 0)
+            throw new IOException("bad jimage header: " + file);
+
+        int redirectPos = HEADER_SIZE;
+        int offsetsPos = redirectPos + tableLength * 4;
+        int locationsPos = offsetsPos + tableLength * 4;
+        int stringsPos = locationsPos + locationsSize;
+        indexSize = (long) stringsPos + stringsSize;
+        if (indexSize > channel.size())
+            throw new IOException("bad jimage header: " + file);
+
+        ByteBuffer index = channel.map(FileChannel.MapMode.READ_ONLY, 0, indexSize).order(order);
+        redirect = slice(index, redirectPos, tableLength * 4).asIntBuffer();
+        offsets = slice(index, offsetsPos, tableLength * 4).asIntBuffer();
+        locations = slice(index, locationsPos, locationsSize);
+        strings = slice(index, stringsPos, stringsSize);
+    }
+
+    private ByteBuffer slice(ByteBuffer buf, int pos, int size) {
+        return buf.duplicate().position(pos).limit(pos + size).slice().order(order);
+    }
+
+    /**
+     * Returns the file being read.
+     */
+    public Path getFile() {
+        return file;
+    }
+
+    /**
+     * Returns the names of the modules in the image, in name order.
+     */
+    public Set<String> getModuleNames() throws IOException {
+        return getModules().keySet();
+    }
+
+    /**
+     * Returns the resources in a module, in order of their path within the
+     * module, or an empty list if there is no such module.
+     */
+    public List<Resource> getResources(String module) throws IOException {
+        return getModules().getOrDefault(module, List.of());
+    }
+
+    /**
+     * Returns a resource, given the name of its module and its path within
+     * the module, such as "java.base" and "java/lang/Object.class",
+     * or null if there is no such resource.
+     */
+    public Resource findResource(String module, String path) {
+        String name = "/" + module + "/" + path;
+        int count = redirect.limit();
+        if (count == 0)
+            return null;
+        int index = redirect.get(hashCode(name, HASH_MULTIPLIER) % count);
+        if (index < 0)
+            index = -index - 1;
+        else if (index > 0)
+            index = hashCode(name, index) % count;
+        else
+            return null;
+        Resource r = getResource(offsets.get(index));
+        return (r != null && r.getName().equals(name)) ? r : null;
+    }
+
+    /**
+     * Returns a resource, given its path within whichever module contains
+     * its package, or null if there is no such resource.
+     */
+    public Resource findResource(String path) throws IOException {
+        int sep = path.lastIndexOf('/');
+        if (sep == -1)
+            return null;
+        for (String module: getPackageModules(path.substring(0, sep).replace('/', '.'))) {
+            Resource r = findResource(module, path);
+            if (r != null)
+                return r;
+        }
+        return null;
+    }
+
+    /*
+     * The content of the /packages/<package> entry for a package is a
+     * sequence of pairs of ints, one for each module containing the package:
+     * a flag that is set if the package is empty in that module, and the
+     * offset of the name of the module in the table of strings.
+     */
+    private List<String> getPackageModules(String packageName) throws IOException {
+        Resource r = findResource("packages", packageName);
+        if (r == null)
+            return List.of();
+        IntBuffer content = ByteBuffer.wrap(getContent(r)).order(order).asIntBuffer();
+        List<String> list = new ArrayList<>();
+        for (int i = 0; i + 1 < content.limit(); i += 2) {
+            if (content.get(i) == 0)
+                list.add(getString(content.get(i + 1)));
+        }
+        return list;
+    }
+
+    /**
+     * Returns the contents of a resource, decompressing it if necessary.
+     */
+    public byte[] getContent(Resource r) throws IOException {
+        if (r.compressedSize == 0)
+            return read(indexSize + r.offset, r.uncompressedSize).array();
+
+        byte[] bytes = read(indexSize + r.offset, r.compressedSize).array();
+        ByteBuffer buf;
+        while (bytes.length >= COMPRESSED_HEADER_SIZE
+                && (buf = ByteBuffer.wrap(bytes).order(order)).getInt(0) == COMPRESSED_MAGIC) {
+            long uncompressedSize = buf.getLong(12);
+            String decompressor = getString(buf.getInt(20));
+            switch (decompressor) {
+                case "zip":
+                    bytes = inflate(bytes, COMPRESSED_HEADER_SIZE, uncompressedSize);
+                    break;
+                case "compact-cp":
+                    bytes = expandStrings(bytes, COMPRESSED_HEADER_SIZE, uncompressedSize);
+                    break;
+                default:
+                    throw new IOException("unsupported compression in " + file + ": " + decompressor);
+            }
+        }
+        return bytes;
+    }
+
+    private byte[] inflate(byte[] bytes, int offset, long size) throws IOException {
+        Inflater inflater = new Inflater();
+        try {
+            inflater.setInput(bytes, offset, bytes.length - offset);
+            ByteArrayOutputStream out = new ByteArrayOutputStream((int) size);
+            byte[] buf = new byte[8192];
+            while (!inflater.finished()) {
+                int n = inflater.inflate(buf);
+                if (n == 0 && (inflater.needsInput() || inflater.needsDictionary()))
+                    throw new IOException("bad compressed resource in " + file);
+                out.write(buf, 0, n);
+            }
+            return out.toByteArray();
+        } catch (DataFormatException e) {
+            throw new IOException("bad compressed resource in " + file + ": " + e.getMessage(), e);
+        } finally {
+            inflater.end();
+        }
+    }
+
+    /*
+     * Reverses the string sharing done by "jlink --compress=1". The constant
+     * pool of a class file is rewritten so that some of its Utf8 entries
+     * refer to the table of strings in the image: an EXTERNALIZED_STRING
+     * entry gives the offset of the whole string, and an
+     * EXTERNALIZED_STRING_DESCRIPTOR entry gives a descriptor in which each
+     * "L...;" type has been reduced to "L;", followed by the length in bytes
+     * of the offsets that come next: for each such type, the offsets of its
+     * package and its simple name.
+     * The rest of the class file follows the constant pool unchanged.
+     */
+    private byte[] expandStrings(byte[] bytes, int offset, long size) throws IOException {
+        try {
+            DataInputStream in = new DataInputStream(
+                    new ByteArrayInputStream(bytes, offset, bytes.length - offset));
+            ByteArrayOutputStream buf = new ByteArrayOutputStream((int) size);
+            DataOutputStream out = new DataOutputStream(buf);
+            byte[] header = new byte[8];   // magic, minor_version, major_version
+            in.readFully(header);
+            out.write(header);
+            int count = in.readUnsignedShort();
+            out.writeShort(count);
+            for (int i = 1; i < count; i++) {
+                int tag = in.readUnsignedByte();
+                switch (tag) {
+                    case CONSTANT_Utf8:
+                        out.write(tag);
+                        out.writeUTF(in.readUTF());
+                        break;
+                    case EXTERNALIZED_STRING:
+                        out.write(CONSTANT_Utf8);
+                        out.writeUTF(getString(readCompressedInt(in)));
+                        break;
+                    case EXTERNALIZED_STRING_DESCRIPTOR:
+                        out.write(CONSTANT_Utf8);
+                        out.writeUTF(expandDescriptor(in));
+                        break;
+                    default:
+                        if (tag >= CONSTANT_SIZES.length || CONSTANT_SIZES[tag] == 0)
+                            throw new IOException("bad compressed resource in " + file
+                                    + ": constant pool tag " + tag);
+                        if (tag == CONSTANT_Long || tag == CONSTANT_Double)
+                            i++;
+                        byte[] info = new byte[CONSTANT_SIZES[tag]];
+                        in.readFully(info);
+                        out.write(tag);
+                        out.write(info);
+                }
+            }
+            out.write(bytes, bytes.length - in.available(), in.available());
+            out.flush();
+            return buf.toByteArray();
+        } catch (EOFException e) {
+            throw new IOException("bad compressed resource in " + file, e);
+        }
+    }
+
+    private String expandDescriptor(DataInputStream in) throws IOException {
+        String desc = getString(readCompressedInt(in));
+        byte[] types = new byte[readCompressedInt(in)];
+        in.readFully(types);
+        DataInputStream typesIn = new DataInputStream(new ByteArrayInputStream(types));
+        StringBuilder sb = new StringBuilder();
+        int pos = 0;
+        while (typesIn.available() > 0) {
+            String pkg = getString(readCompressedInt(typesIn));
+            String name = getString(readCompressedInt(typesIn));
+            int l = desc.indexOf('L', pos);
+            if (l == -1)
+                throw new IOException("bad compressed resource in " + file + ": " + desc);
+            sb.append(desc, pos, l + 1);
+            if (!pkg.isEmpty())
+                sb.append(pkg).append('/');
+            sb.append(name);
+            pos = l + 1;
+        }
+        return sb.append(desc, pos, desc.length()).toString();
+    }
+
+    /*
+     * An int in which the top bit of the first byte is set holds, in
+     * the next two bits, its length in bytes (1 to 3) and, in the other five,
+     * the most significant bits of its value; otherwise, it is 4 bytes long.
+     */
+    private static int readCompressedInt(DataInputStream in) throws IOException {
+        int b = in.readByte();
+        int length = ((b & 0x80) != 0) ? (b >> 5) & 0x3 : 4;
+        int value = ((b & 0x80) != 0) ? b & 0x1F : b;
+        for (int i = 1; i < length; i++)
+            value = (value << 8) | in.readUnsignedByte();
+        return value;
+    }
+
+    @Override
+    public void close() throws IOException {
+        channel.close();
+    }
+
+    /*
+     * Groups the resources of the image by module. The "modules" and
+     * "packages" entries that describe the layout of the image are skipped.
+     */
+    private Map<String, List<Resource>> getModules() throws IOException {
+        if (modules == null) {
+            Map<String, List<Resource>> map = new TreeMap<>();
+            for (int i = 0; i < offsets.limit(); i++) {
+                Resource r = getResource(offsets.get(i));
+                if (r == null || r.module.isEmpty()
+                        || r.module.equals("modules") || r.module.equals("packages"))
+                    continue;
+                map.computeIfAbsent(r.module, m -> new ArrayList<>()).add(r);
+            }
+            for (List<Resource> list: map.values())
+                list.sort(Comparator.comparing(Resource::getPath));
+            modules = map;
+        }
+        return modules;
+    }
+
+    private Resource getResource(int offset) {
+        if (offset < 0 || offset >= locations.limit())
+            return null;
+        long[] attrs = new long[ATTRIBUTE_COUNT];
+        int pos = offset;
+        while (pos < locations.limit()) {
+            int data = locations.get(pos++) & 0xFF;
+            int kind = data >>> 3;
+            if (kind == ATTRIBUTE_END)
+                break;
+            int length = (data & 0x7) + 1;
+            long value = 0;
+            for (int j = 0; j < length && pos < locations.limit(); j++)
+                value = (value << 8) | (locations.get(pos++) & 0xFF);
+            if (kind < ATTRIBUTE_COUNT)
+                attrs[kind] = value;
+        }
+        return new Resource(getString((int) attrs[ATTRIBUTE_MODULE]),
+                getString((int) attrs[ATTRIBUTE_PARENT]),
+                getString((int) attrs[ATTRIBUTE_BASE]),
+                getString((int) attrs[ATTRIBUTE_EXTENSION]),
+                attrs[ATTRIBUTE_OFFSET],
+                attrs[ATTRIBUTE_COMPRESSED],
+                attrs[ATTRIBUTE_UNCOMPRESSED]);
+    }
+
+    /*
+     * Strings are stored in modified UTF-8, terminated by a zero byte.
+     */
+    private String getString(int offset) {
+        if (offset <= 0 || offset >= strings.limit())
+            return "";
+        StringBuilder sb = new StringBuilder();
+        int pos = offset;
+        while (pos < strings.limit()) {
+            int b = strings.get(pos++) & 0xFF;
+            if (b == 0)
+                break;
+            if (b < 0x80) {
+                sb.append((char) b);
+            } else if ((b & 0xE0) == 0xC0 && pos < strings.limit()) {
+                sb.append((char) (((b & 0x1F) << 6) | (strings.get(pos++) & 0x3F)));
+            } else if ((b & 0xF0) == 0xE0 && pos + 1 < strings.limit()) {
+                int b2 = strings.get(pos++) & 0x3F;
+                int b3 = strings.get(pos++) & 0x3F;
+                sb.append((char) (((b & 0x0F) << 12) | (b2 << 6) | b3));
+            } else {
+                sb.append('?');
+            }
+        }
+        return sb.toString();
+    }
+
+    /*
+     * The hash function used for the perfect hash of the resource names:
+     * FNV-1 over the modified UTF-8 encoding of the name.
+     */
+    private static int hashCode(String s, int seed) {
+        for (int i = 0; i < s.length(); i++) {
+            char c = s.charAt(i);
+            if (c >= 0x01 && c <= 0x7F) {
+                seed = (seed * HASH_MULTIPLIER) ^ c;
+            } else if (c <= 0x7FF) {
+                seed = (seed * HASH_MULTIPLIER) ^ (0xC0 | (c >> 6));
+                seed = (seed * HASH_MULTIPLIER) ^ (0x80 | (c & 0x3F));
+            } else {
+                seed = (seed * HASH_MULTIPLIER) ^ (0xE0 | (c >> 12));
+                seed = (seed * HASH_MULTIPLIER) ^ (0x80 | ((c >> 6) & 0x3F));
+                seed = (seed * HASH_MULTIPLIER) ^ (0x80 | (c & 0x3F));
+            }
+        }
+        return seed & POSITIVE_MASK;
+    }
+
+    private ByteBuffer read(long position, long size) throws IOException {
+        if (size < 0 || size > Integer.MAX_VALUE)
+            throw new IOException("bad resource size in " + file + ": " + size);
+        ByteBuffer buf = ByteBuffer.allocate((int) size);
+        while (buf.hasRemaining()) {
+            if (channel.read(buf, position + buf.position()) < 0)
+                throw new EOFException(file.toString());
+        }
+        return buf;
+    }
+
+    /**
+     * A resource in the image, such as a class file.
+     */
+    public static class Resource {
+        Resource(String module, String parent, String base, String extension,
+                long offset, long compressedSize, long uncompressedSize) {
+            this.module = module;
+            this.parent = parent;
+            this.base = base;
+            this.extension = extension;
+            this.offset = offset;
+            this.compressedSize = compressedSize;
+            this.uncompressedSize = uncompressedSize;
+        }
+
+        /**
+         * Returns the name of the module containing this resource.
+         */
+        public String getModule() {
+            return module;
+        }
+
+        /**
+         * Returns the path of this resource within its module,
+         * such as "java/lang/Object.class".
+         */
+        public String getPath() {
+            StringBuilder sb = new StringBuilder();
+            if (!parent.isEmpty())
+                sb.append(parent).append('/');
+            sb.append(base);
+            if (!extension.isEmpty())
+                sb.append('.').append(extension);
+            return sb.toString();
+        }
+
+        /**
+         * Returns the full name of this resource within the image,
+         * such as "/java.base/java/lang/Object.class".
+         */
+        public String getName() {
+            return module.isEmpty() ? "/" + getPath() : "/" + module + "/" + getPath();
+        }
+
+        /**
+         * Returns the size of the contents of this resource, after any decompression.
+         */
+        public long getSize() {
+            return uncompressedSize;
+        }
+
+        @Override
+        public String toString() {
+            return getName();
+        }
+
+        private final String module;
+        private final String parent;
+        private final String base;
+        private final String extension;
+        private final long offset;
+        private final long compressedSize;
+        private final long uncompressedSize;
+    }
+
+    private final Path file;
+    private final FileChannel channel;
+    private final ByteOrder order;
+    private final long indexSize;
+    private final IntBuffer redirect;
+    private final IntBuffer offsets;
+    private final ByteBuffer locations;
+    private final ByteBuffer strings;
+    private Map<String, List<Resource>> modules;
+}
@@ -0,0 +1,1114 @@
+/*
+ * Copyright (c) 2021 by Andrew Binstock.
+ *
+ * Portions of this file are copyright Oracle Corp.
+ * Those portions are licensed under GPL v. 2.0
+ * with the Oracle classpath exception. Due to the
+ * requirements of that license, the portions that
+ * are copyrighted by Andrew Binstock are licensed
+ * using the same terms and requirements.
+ *
+ */
+
+package org.jacobin.jadis;
+
+import java.io.ByteArrayInputStream;
+import java.io.File;
+import java.io.IOException;
+import java.io.InputStream;
+import java.io.OutputStream;
+import java.io.Reader;
+import java.io.StringReader;
+import java.io.Writer;
+import java.lang.module.FindException;
+import java.lang.module.ModuleFinder;
+import java.lang.module.ModuleReference;
+import java.net.URI;
+import java.nio.charset.Charset;
+import java.nio.file.DirectoryStream;
+import java.nio.file.Files;
+import java.nio.file.InvalidPathException;
+import java.nio.file.Path;
+import java.nio.file.PathMatcher;
+import java.nio.file.Paths;
+import java.util.ArrayList;
+import java.util.Collections;
+import java.util.EnumSet;
+import java.util.Enumeration;
+import java.util.HashMap;
+import java.util.Iterator;
+import java.util.LinkedHashSet;
+import java.util.List;
+import java.util.Locale;
+import java.util.Map;
+import java.util.NavigableSet;
+import java.util.Optional;
+import java.util.Set;
+import java.util.TreeMap;
+import java.util.TreeSet;
+import java.util.jar.Attributes;
+import java.util.jar.JarFile;
+import java.util.jar.Manifest;
+import java.util.stream.Stream;
+import java.util.zip.ZipEntry;
+import java.util.zip.ZipFile;
+
+import javax.lang.model.element.Modifier;
+import javax.lang.model.element.NestingKind;
+import javax.tools.FileObject;
+import javax.tools.JavaFileManager;
+import javax.tools.JavaFileObject;
+import javax.tools.StandardLocation;
+
+/*
+ *  A standalone file manager for javap. It locates class files in
+ *  directories and JAR files on the class path and boot class path,
+ *  in the modules of a JDK run-time image, and on the module path,
+ *  without depending on the file manager provided by javac. The run-time
+ *  image is read with JImageReader, so that --system can name any local JDK.
+ *
+ *  <p><b>This is NOT part of any supported API.
+ *  If you write code that depends on this, you do so at your own risk.
+ *  This code and its internal interfaces are subject to change or
+ *  deletion without notice.</b>
+ */
+public class JavapFileManager implements JavaFileManager {
+    public JavapFileManager() {
+    }
+
+    /**
+     * Returns a file object for a class file named directly on the command line,
+     * or null if there is no such file.
+     */
+    JavaFileObject getJavaFileObject(String fileName) {
+        Path path = Paths.get(fileName);
+        if (!Files.isRegularFile(path))
+            return null;
+        return new PathFileObject(path, path.getFileName().toString(), JavaFileObject.Kind.CLASS);
+    }
+
+    /**
+     * Returns file objects for all the class files in a JAR file, JMOD file
+     * or directory named on the command line, in name order, or null if the
+     * name does not refer to one. The name of a directory may be followed by
+     * a glob pattern, as in {@code classes/com/acme/**}, to select just the
+     * class files whose path relative to the directory matches the pattern.
+     * For a multi-release JAR file, the class files are those for the release
+     * given with --multi-release, or all their versions if that is "all".
+     */
+    List<JavaFileObject> getClassFiles(String name) throws IOException {
+        int glob = indexOfGlob(name);
+        if (glob != -1) {
+            int sep = Math.max(name.lastIndexOf('/', glob), name.lastIndexOf(File.separatorChar, glob));
+            Path dir;
+            try {
+                dir = Paths.get(sep == -1 ? "." : sep == 0 ? name.substring(0, 1) : name.substring(0, sep));
+            } catch (InvalidPathException e) {
+                return null;
+            }
+            if (!Files.isDirectory(dir))
+                return null;
+            PathMatcher matcher = dir.getFileSystem().getPathMatcher("glob:" + name.substring(sep + 1));
+            List<JavaFileObject> files = listClassFiles(new DirContainer(dir), "");
+            files.removeIf(fo -> !matcher.matches(Paths.get(((BaseFileObject) fo).relativeName)));
+            return files;
+        }
+
+        Path path;
+        try {
+            path = Paths.get(name);
+        } catch (InvalidPathException e) {
+            return null;
+        }
+        if (Files.isDirectory(path))
+            return listClassFiles(new DirContainer(path), "");
+        if (!Files.isRegularFile(path))
+            return null;
+        String fileName = path.getFileName().toString().toLowerCase(Locale.ROOT);
+        if (fileName.endsWith(".jar")) {
+            List<JavaFileObject> files = listClassFiles(new JarContainer(path), "");
+            files.removeIf(fo -> ((BaseFileObject) fo).relativeName.startsWith("META-INF/"));
+            if (!allReleases)
+                return files;
+            List<JavaFileObject> allFiles = new ArrayList<>();
+            for (JavaFileObject fo: files) {
+                Map<Integer, JavaFileObject> variants = getVariants(fo);
+                allFiles.addAll(variants.isEmpty() ? List.of(fo) : variants.values());
+            }
+            return allFiles;
+        }
+        if (fileName.endsWith(".jmod"))
+            return listClassFiles(new JarContainer(path), "classes");
+        return null;
+    }
+
+    private List<JavaFileObject> listClassFiles(Container c, String packagePath) throws IOException {
+        archives.add(c);
+        List<JavaFileObject> files = new ArrayList<>();
+        c.list(packagePath, EnumSet.of(JavaFileObject.Kind.CLASS), true, files);
+        return files;
+    }
+
+    /**
+     * Returns a file object for a class in the same directory, JAR file or
+     * module of a run-time image as a given class file, such as a class
+     * nested in it, or null if there is no such class file.
+     */
+    JavaFileObject getSibling(JavaFileObject fo, String className) throws IOException {
+        String relativeName = className + JavaFileObject.Kind.CLASS.extension;
+        if (fo instanceof PathFileObject) {
+            PathFileObject pfo = (PathFileObject) fo;
+            String simpleName = relativeName.substring(relativeName.lastIndexOf('/') + 1);
+            Path p = pfo.path.resolveSibling(simpleName);
+            if (!Files.isRegularFile(p))
+                return null;
+            String dir = pfo.relativeName.substring(0, pfo.relativeName.lastIndexOf('/') + 1);
+            return new PathFileObject(p, dir + simpleName, JavaFileObject.Kind.CLASS);
+        }
+        if (fo instanceof ZipEntryFileObject)
+            return ((ZipEntryFileObject) fo).container.find(relativeName, JavaFileObject.Kind.CLASS);
+        if (fo instanceof ImageFileObject) {
+            ImageFileObject ifo = (ImageFileObject) fo;
+            JImageReader.Resource r = ifo.image.findResource(ifo.resource.getModule(), relativeName);
+            return (r == null) ? null : new ImageFileObject(ifo.image, r, JavaFileObject.Kind.CLASS);
+        }
+        return null;
+    }
+
+    /**
+     * Returns whether a release for multi-release JAR files was given with
+     * --multi-release.
+     */
+    boolean isMultiRelease() {
+        return release != 0;
+    }
+
+    /**
+     * Returns whether all the versions of the classes in multi-release JAR
+     * files should be shown, as requested by --multi-release all.
+     */
+    boolean isAllReleases() {
+        return allReleases;
+    }
+
+    /**
+     * Returns all the versions of a class file in a multi-release JAR file,
+     * in release order, keyed by release, with 0 for the unversioned class
+     * file; or an empty map if the class file is not in a multi-release JAR file.
+     */
+    Map<Integer, JavaFileObject> getVariants(JavaFileObject fo) throws IOException {
+        if (!(fo instanceof ZipEntryFileObject))
+            return Map.of();
+        ZipEntryFileObject zfo = (ZipEntryFileObject) fo;
+        return zfo.container.getVariants(zfo.relativeName, zfo.kind);
+    }
+
+    /**
+     * Returns the release of a class file in a multi-release JAR file,
+     * or 0 if it is not in the versioned part of a multi-release JAR file.
+     */
+    int getRelease(JavaFileObject fo) throws IOException {
+        if (!(fo instanceof ZipEntryFileObject))
+            return 0;
+        ZipEntryFileObject zfo = (ZipEntryFileObject) fo;
+        return zfo.container.getVersion(zfo.entry.getName());
+    }
+
+    private static int indexOfGlob(String name) {
+        for (int i = 0; i < name.length(); i++) {
+            if ("*?[{".indexOf(name.charAt(i)) != -1)
+                return i;
+        }
+        return -1;
+    }
+
+    private static final Set<String> classPathOptions = Set.of("--class-path", "-classpath", "-cp");
+    private static final Set<String> bootClassPathOptions = Set.of("--boot-class-path", "-bootclasspath");
+
+    @Override
+    public int isSupportedOption(String option) {
+        switch (option) {
+            case "--class-path": case "-classpath": case "-cp":
+            case "--boot-class-path": case "-bootclasspath":
+            case "--module-path":
+            case "--upgrade-module-path":
+            case "--system":
+            case "--multi-release":
+                return 1;
+            default:
+                return -1;
+        }
+    }
+
+    @Override
+    public boolean handleOption(String current, Iterator<String> remaining) {
+        if (isSupportedOption(current) == -1)
+            return false;
+        if (!remaining.hasNext())
+            throw new IllegalArgumentException(current);
+        String value = remaining.next();
+
+        if (classPathOptions.contains(current)) {
+            classPath = setContainers(classPath, parsePath(value));
+        } else if (bootClassPathOptions.contains(current)) {
+            bootClassPath = setContainers(bootClassPath, parsePath(value));
+        } else if (current.equals("--module-path")) {
+            modulePath = parseModulePath(value);
+            modulePathModules = null;
+        } else if (current.equals("--upgrade-module-path")) {
+            upgradeModulePath = parseModulePath(value);
+            upgradeModulePathModules = null;
+        } else if (current.equals("--system")) {
+            setSystem(value);
+        } else if (current.equals("--multi-release")) {
+            setRelease(value);
+        }
+        return true;
+    }
+
+    private List<Container> setContainers(List<Container> old, List<Path> paths) {
+        if (old != null)
+            closeAll(old);
+        List<Container> containers = new ArrayList<>();
+        for (Path p: paths) {
+            if (Files.isDirectory(p))
+                containers.add(new DirContainer(p));
+            else if (Files.isRegularFile(p))
+                containers.add(new JarContainer(p));
+            // else silently ignore, as javac does for missing path elements
+        }
+        return containers;
+    }
+
+    private void setSystem(String value) {
+        closeSystem();
+        if (value.equals("none")) {
+            noSystem = true;
+            return;
+        }
+        Path home = Paths.get(value);
+        if (!Files.isRegularFile(home.resolve("lib").resolve("modules"))
+                && getLegacyBootClassPath(home).isEmpty())
+            throw new IllegalArgumentException(value);
+        systemHome = home;
+        noSystem = false;
+    }
+
+    /**
+     * Sets the release used for multi-release JAR files: a feature release
+     * number, such as 11, or "all", which finds the latest version of each
+     * class and shows all of them when a whole JAR file is given.
+     */
+    private void setRelease(String value) {
+        if (value.equals("all")) {
+            release = Integer.MAX_VALUE;
+            allReleases = true;
+            return;
+        }
+        try {
+            release = Runtime.Version.parse(value).feature();
+            allReleases = false;
+        } catch (IllegalArgumentException e) {
+            throw new IllegalArgumentException(value, e);
+        }
+    }
+
+    /**
+     * Splits a path option into its elements. An empty element stands for the
+     * current directory, and an element whose last name is {@code *} stands for
+     * all the JAR files in that directory, in name order.
+     */
+    private static List<Path> parsePath(String value) {
+        List<Path> paths = new ArrayList<>();
+        for (String s: value.split(File.pathSeparator, -1)) {
+            if (s.isEmpty()) {
+                paths.add(Paths.get("."));
+            } else if (s.equals("*") || s.endsWith(File.separator + "*")
+                    || (File.separatorChar != '/' && s.endsWith("/*"))) {
+                Path dir = Paths.get(s.length() == 1 ? "." : s.substring(0, s.length() - 2));
+                paths.addAll(listJars(dir));
+            } else {
+                paths.add(Paths.get(s));
+            }
+        }
+        return paths;
+    }
+
+    private static List<Path> listJars(Path dir) {
+        List<Path> jars = new ArrayList<>();
+        if (!Files.isDirectory(dir))
+            return jars;
+        try (DirectoryStream<Path> ds = Files.newDirectoryStream(dir)) {
+            for (Path p: ds) {
+                String name = p.getFileName().toString();
+                if ((name.endsWith(".jar") || name.endsWith(".JAR")) && Files.isRegularFile(p))
+                    jars.add(p);
+            }
+        } catch (IOException e) {
+            // treat an unreadable directory as empty
+        }
+        Collections.sort(jars);
+        return jars;
+    }
+
+    private static List<Path> parseModulePath(String value) {
+        List<Path> paths = new ArrayList<>();
+        for (String s: value.split(File.pathSeparator)) {
+            if (!s.isEmpty())
+                paths.add(Paths.get(s));
+        }
+        return paths;
+    }
+
+    @Override
+    public boolean hasLocation(Location location) {
+        if (location == StandardLocation.CLASS_PATH
+                || location == StandardLocation.PLATFORM_CLASS_PATH)
+            return true;
+        if (location == StandardLocation.SYSTEM_MODULES)
+            return !noSystem;
+        if (location == StandardLocation.MODULE_PATH)
+            return !modulePath.isEmpty();
+        if (location == StandardLocation.UPGRADE_MODULE_PATH)
+            return !upgradeModulePath.isEmpty();
+        return location instanceof ModuleLocation;
+    }
+
+    private List<Container> getContainers(Location location) throws IOException {
+        if (location == StandardLocation.CLASS_PATH) {
+            if (classPath == null)
+                classPath = setContainers(null, parsePath(defaultClassPath()));
+            return classPath;
+        }
+        if (location == StandardLocation.PLATFORM_CLASS_PATH) {
+            return (bootClassPath != null) ? bootClassPath : getSystemClassPath();
+        }
+        if (location instanceof ModuleLocation)
+            return List.of(((ModuleLocation) location).container);
+        return List.of();
+    }
+
+    /**
+     * The user class path when none is given: the CLASSPATH environment
+     * variable if it is set, and otherwise the current directory.
+     */
+    private static String defaultClassPath() {
+        String cp = System.getenv("CLASSPATH");
+        return (cp == null || cp.isEmpty()) ? "." : cp;
+    }
+
+    private Map<String, ModuleLocation> getModules(Location location) throws IOException {
+        if (location == StandardLocation.SYSTEM_MODULES)
+            return getSystemModules();
+        if (location == StandardLocation.MODULE_PATH) {
+            if (modulePathModules == null)
+                modulePathModules = findModules(modulePath);
+            return modulePathModules;
+        }
+        if (location == StandardLocation.UPGRADE_MODULE_PATH) {
+            if (upgradeModulePathModules == null)
+                upgradeModulePathModules = findModules(upgradeModulePath);
+            return upgradeModulePathModules;
+        }
+        return Map.of();
+    }
+
+    private Map<String, ModuleLocation> getSystemModules() throws IOException {
+        if (systemModules == null) {
+            Map<String, ModuleLocation> map = new TreeMap<>();
+            JImageReader image = getSystemImage();
+            if (image != null) {
+                for (String name: image.getModuleNames())
+                    map.put(name, new ModuleLocation(name, new ImageContainer(image, name)));
+            }
+            systemModules = map;
+        }
+        return systemModules;
+    }
+
+    /**
+     * The platform classes when no boot class path is given: those in all the
+     * modules of the system image, or for JDK 8 and earlier, which do not have
+     * a run-time image, those in the JAR files on the default boot class path.
+     */
+    private List<Container> getSystemClassPath() throws IOException {
+        if (systemClassPath == null) {
+            JImageReader image = getSystemImage();
+            if (image != null)
+                systemClassPath = List.of(new ImageContainer(image, null));
+            else if (!noSystem)
+                systemClassPath = setContainers(null, getLegacyBootClassPath(systemHome));
+            else
+                systemClassPath = List.of();
+        }
+        return systemClassPath;
+    }
+
+    private JImageReader getSystemImage() throws IOException {
+        if (systemImage == null && !noSystem) {
+            Path modules = systemHome.resolve("lib").resolve("modules");
+            if (Files.isRegularFile(modules))
+                systemImage = JImageReader.open(modules);
+        }
+        return systemImage;
+    }
+
+    private static final List<String> legacyBootJars = List.of(
+            "resources.jar", "rt.jar", "sunrsasign.jar", "jsse.jar", "jce.jar", "charsets.jar", "jfr.jar");
+
+    /**
+     * Returns the default boot class path of a JDK or JRE for JDK 8 or earlier,
+     * or an empty list if the given directory does not contain one.
+     */
+    private static List<Path> getLegacyBootClassPath(Path home) {
+        Path lib = home.resolve("jre").resolve("lib");
+        if (!Files.isRegularFile(lib.resolve("rt.jar")))
+            lib = home.resolve("lib");
+        if (!Files.isRegularFile(lib.resolve("rt.jar")))
+            return List.of();
+        List<Path> paths = new ArrayList<>();
+        for (String name: legacyBootJars) {
+            Path p = lib.resolve(name);
+            if (Files.isRegularFile(p))
+                paths.add(p);
+        }
+        return paths;
+    }
+
+    private Map<String, ModuleLocation> findModules(List<Path> paths) throws IOException {
+        Map<String, ModuleLocation> map = new TreeMap<>();
+        try {
+            for (ModuleReference ref: ModuleFinder.of(paths.toArray(new Path[0])).findAll()) {
+                Optional<URI> uri = ref.location();
+                if (uri.isEmpty() || !uri.get().getScheme().equals("file"))
+                    continue;
+                Path p = Paths.get(uri.get());
+                Container c = Files.isDirectory(p) ? new DirContainer(p) : new JarContainer(p);
+                String name = ref.descriptor().name();
+                map.put(name, new ModuleLocation(name, c));
+            }
+        } catch (FindException e) {
+            throw new IOException(e.getMessage(), e);
+        }
+        return map;
+    }
+
+    @Override
+    public Location getLocationForModule(Location location, String moduleName) throws IOException {
+        return getModules(location).get(moduleName);
+    }
+
+    @Override
+    public Location getLocationForModule(Location location, JavaFileObject fo) throws IOException {
+        for (ModuleLocation l: getModules(location).values()) {
+            if (l.container.contains(fo))
+                return l;
+        }
+        return null;
+    }
+
+    @Override
+    public Iterable<Set<Location>> listLocationsForModules(Location location) throws IOException {
+        Map<String, ModuleLocation> modules = getModules(location);
+        if (modules.isEmpty())
+            return List.of();
+        return List.of(new LinkedHashSet<>(modules.values()));
+    }
+
+    @Override
+    public String inferModuleName(Location location) {
+        if (location instanceof ModuleLocation)
+            return ((ModuleLocation) location).name;
+        throw new IllegalArgumentException(location.getName());
+    }
+
+    @Override
+    public boolean contains(Location location, FileObject fo) throws IOException {
+        for (Container c: getContainers(location)) {
+            if (c.contains(fo))
+                return true;
+        }
+        return false;
+    }
+
+    @Override
+    public JavaFileObject getJavaFileForInput(Location location, String className, JavaFileObject.Kind kind)
+            throws IOException {
+        String relativeName = className.replace('.', '/') + kind.extension;
+        for (Container c: getContainers(location)) {
+            JavaFileObject fo = c.find(relativeName, kind);
+            if (fo != null)
+                return fo;
+        }
+        return null;
+    }
+
+    @Override
+    public FileObject getFileForInput(Location location, String packageName, String relativeName)
+            throws IOException {
+        String name = packageName.isEmpty()
+                ? relativeName
+                : packageName.replace('.', '/') + "/" + relativeName;
+        for (Container c: getContainers(location)) {
+            JavaFileObject fo = c.find(name, getKind(name));
+            if (fo != null)
+                return fo;
+        }
+        return null;
+    }
+
+    @Override
+    public Iterable<JavaFileObject> list(Location location, String packageName,
+            Set<JavaFileObject.Kind> kinds, boolean recurse) throws IOException {
+        String packagePath = packageName.replace('.', '/');
+        List<JavaFileObject> result = new ArrayList<>();
+        for (Container c: getContainers(location))
+            c.list(packagePath, kinds, recurse, result);
+        return result;
+    }
+
+    @Override
+    public String inferBinaryName(Location location, JavaFileObject file) {
+        if (!(file instanceof BaseFileObject))
+            return null;
+        String name = ((BaseFileObject) file).relativeName;
+        int dot = name.lastIndexOf('.');
+        return (dot == -1 ? name : name.substring(0, dot)).replace('/', '.');
+    }
+
+    @Override
+    public boolean isSameFile(FileObject a, FileObject b) {
+        return a.toUri().equals(b.toUri());
+    }
+
+    @Override
+    public JavaFileObject getJavaFileForOutput(Location location, String className,
+            JavaFileObject.Kind kind, FileObject sibling) {
+        throw new UnsupportedOperationException();
+    }
+
+    @Override
+    public FileObject getFileForOutput(Location location, String packageName,
+            String relativeName, FileObject sibling) {
+        throw new UnsupportedOperationException();
+    }
+
+    @Override
+    public ClassLoader getClassLoader(Location location) {
+        return null;
+    }
+
+    @Override
+    public void flush() {
+    }
+
+    @Override
+    public void close() throws IOException {
+        if (classPath != null)
+            closeAll(classPath);
+        if (bootClassPath != null)
+            closeAll(bootClassPath);
+        if (modulePathModules != null)
+            closeAll(modulePathModules.values());
+        if (upgradeModulePathModules != null)
+            closeAll(upgradeModulePathModules.values());
+        closeAll(archives);
+        archives.clear();
+        closeSystem();
+    }
+
+    private void closeSystem() {
+        systemModules = null;
+        if (systemClassPath != null) {
+            closeAll(systemClassPath);
+            systemClassPath = null;
+        }
+        if (systemImage != null) {
+            try {
+                systemImage.close();
+            } catch (IOException e) {
+                // ignore
+            }
+            systemImage = null;
+        }
+    }
+
+    private static void closeAll(Iterable<?> items) {
+        for (Object item: items) {
+            Container c = (item instanceof ModuleLocation)
+                    ? ((ModuleLocation) item).container
+                    : (Container) item;
+            try {
+                c.close();
+            } catch (IOException e) {
+                // ignore
+            }
+        }
+    }
+
+    private static JavaFileObject.Kind getKind(String name) {
+        for (JavaFileObject.Kind k: JavaFileObject.Kind.values()) {
+            if (k != JavaFileObject.Kind.OTHER && name.endsWith(k.extension))
+                return k;
+        }
+        return JavaFileObject.Kind.OTHER;
+    }
+
+    private static boolean inPackage(String relativeName, String packagePath, boolean recurse) {
+        if (packagePath.isEmpty())
+            return recurse || relativeName.indexOf('/') == -1;
+        if (!relativeName.startsWith(packagePath + "/"))
+            return false;
+        return recurse || relativeName.indexOf('/', packagePath.length() + 1) == -1;
+    }
+
+    /**
+     * A directory or JAR file that may contain class files.
+     */
+    private interface Container {
+        JavaFileObject find(String relativeName, JavaFileObject.Kind kind) throws IOException;
+        void list(String packagePath, Set<JavaFileObject.Kind> kinds, boolean recurse,
+                List<JavaFileObject> result) throws IOException;
+        boolean contains(FileObject fo);
+        void close() throws IOException;
+    }
+
+    private static class DirContainer implements Container {
+        DirContainer(Path dir) {
+            this.dir = dir;
+        }
+
+        @Override
+        public JavaFileObject find(String relativeName, JavaFileObject.Kind kind) {
+            Path p = dir.resolve(relativeName);
+            return Files.isRegularFile(p) ? new PathFileObject(p, relativeName, kind) : null;
+        }
+
+        @Override
+        public void list(String packagePath, Set<JavaFileObject.Kind> kinds, boolean recurse,
+                List<JavaFileObject> result) throws IOException {
+            Path start = packagePath.isEmpty() ? dir : dir.resolve(packagePath);
+            if (!Files.isDirectory(start))
+                return;
+            try (Stream<Path> s = recurse ? Files.walk(start) : Files.list(start)) {
+                s.filter(Files::isRegularFile)
+                        .map(p -> dir.relativize(p).toString().replace(p.getFileSystem().getSeparator(), "/"))
+                        .filter(n -> kinds.contains(getKind(n)))
+                        .sorted()
+                        .forEach(n -> result.add(new PathFileObject(dir.resolve(n), n, getKind(n))));
+            }
+        }
+
+        @Override
+        public boolean contains(FileObject fo) {
+            return (fo instanceof PathFileObject) && ((PathFileObject) fo).path.startsWith(dir);
+        }
+
+        @Override
+        public void close() {
+        }
+
+        private final Path dir;
+    }
+
+    private static final String VERSIONS_DIR = "META-INF/versions/";
+
+    /**
+     * A JAR or JMOD file. In a multi-release JAR file, a class is found in
+     * the directory META-INF/versions/N with the highest N that is not
+     * greater than the release given with --multi-release, if there is one,
+     * and otherwise in the unversioned part of the file.
+     */
+    private class JarContainer implements Container {
+        JarContainer(Path jar) {
+            this.jar = jar;
+        }
+
+        private ZipFile getZipFile() throws IOException {
+            if (zipFile == null)
+                zipFile = new ZipFile(jar.toFile());
+            return zipFile;
+        }
+
+        /*
+         * Returns the releases for which there are versioned entries,
+         * or an empty set if this is not a multi-release JAR file.
+         */
+        private NavigableSet<Integer> getVersions() throws IOException {
+            if (versions == null) {
+                ZipFile zf = getZipFile();
+                NavigableSet<Integer> set = new TreeSet<>();
+                if (isMultiRelease(zf)) {
+                    for (Enumeration<? extends ZipEntry> e = zf.entries(); e.hasMoreElements(); ) {
+                        int v = parseVersion(e.nextElement().getName());
+                        if (v != 0)
+                            set.add(v);
+                    }
+                }
+                versions = set;
+            }
+            return versions;
+        }
+
+        private boolean isMultiRelease(ZipFile zf) throws IOException {
+            ZipEntry e = zf.getEntry(JarFile.MANIFEST_NAME);
+            if (e == null)
+                return false;
+            try (InputStream in = zf.getInputStream(e)) {
+                String value = new Manifest(in).getMainAttributes().getValue(Attributes.Name.MULTI_RELEASE);
+                return "true".equalsIgnoreCase(value);
+            }
+        }
+
+        private int parseVersion(String entryName) {
+            if (!entryName.startsWith(VERSIONS_DIR))
+                return 0;
+            int slash = entryName.indexOf('/', VERSIONS_DIR.length());
+            if (slash == -1)
+                return 0;
+            try {
+                int v = Integer.parseInt(entryName.substring(VERSIONS_DIR.length(), slash));
+                return (v >= 9) ? v : 0;
+            } catch (NumberFormatException e) {
+                return 0;
+            }
+        }
+
+        /*
+         * Returns the release of a versioned entry in a multi-release JAR file,
+         * or 0 for any other entry.
+         */
+        int getVersion(String entryName) throws IOException {
+            int v = parseVersion(entryName);
+            return getVersions().contains(v) ? v : 0;
+        }
+
+        @Override
+        public JavaFileObject find(String relativeName, JavaFileObject.Kind kind) throws IOException {
+            ZipFile zf = getZipFile();
+            for (int v: getVersions().descendingSet()) {
+                if (v > release)
+                    continue;
+                ZipEntry e = zf.getEntry(VERSIONS_DIR + v + "/" + relativeName);
+                if (e != null && !e.isDirectory())
+                    return new ZipEntryFileObject(this, e, relativeName, kind);
+            }
+            ZipEntry e = zf.getEntry(relativeName);
+            return (e == null || e.isDirectory()) ? null : new ZipEntryFileObject(this, e, relativeName, kind);
+        }
+
+        @Override
+        public void list(String packagePath, Set<JavaFileObject.Kind> kinds, boolean recurse,
+                List<JavaFileObject> result) throws IOException {
+            ZipFile zf = getZipFile();
+            Map<String, ZipEntry> entries = new TreeMap<>();
+            Map<String, Integer> entryVersions = new HashMap<>();
+            for (Enumeration<? extends ZipEntry> e = zf.entries(); e.hasMoreElements(); ) {
+                ZipEntry ze = e.nextElement();
+                if (ze.isDirectory())
+                    continue;
+                String n = ze.getName();
+                int v = getVersion(n);
+                if (v > release)
+                    continue;
+                if (v != 0)
+                    n = n.substring(n.indexOf('/', VERSIONS_DIR.length()) + 1);
+                if (inPackage(n, packagePath, recurse) && kinds.contains(getKind(n))
+                        && entryVersions.getOrDefault(n, -1) < v) {
+                    entries.put(n, ze);
+                    entryVersions.put(n, v);
+                }
+            }
+            for (Map.Entry<String, ZipEntry> e: entries.entrySet())
+                result.add(new ZipEntryFileObject(this, e.getValue(), e.getKey(), getKind(e.getKey())));
+        }
+
+        /*
+         * Returns the unversioned entry and all the versioned entries for a file,
+         * if this is a multi-release JAR file.
+         */
+        Map<Integer, JavaFileObject> getVariants(String relativeName, JavaFileObject.Kind kind)
+                throws IOException {
+            if (getVersions().isEmpty())
+                return Map.of();
+            ZipFile zf = getZipFile();
+            Map<Integer, JavaFileObject> map = new TreeMap<>();
+            ZipEntry base = zf.getEntry(relativeName);
+            if (base != null && !base.isDirectory())
+                map.put(0, new ZipEntryFileObject(this, base, relativeName, kind));
+            for (int v: getVersions()) {
+                ZipEntry e = zf.getEntry(VERSIONS_DIR + v + "/" + relativeName);
+                if (e != null && !e.isDirectory())
+                    map.put(v, new ZipEntryFileObject(this, e, relativeName, kind));
+            }
+            return map;
+        }
+
+        @Override
+        public boolean contains(FileObject fo) {
+            return (fo instanceof ZipEntryFileObject) && ((ZipEntryFileObject) fo).container == this;
+        }
+
+        @Override
+        public void close() throws IOException {
+            if (zipFile != null) {
+                zipFile.close();
+                zipFile = null;
+            }
+            versions = null;
+        }
+
+        private final Path jar;
+        private ZipFile zipFile;
+        private NavigableSet<Integer> versions;
+    }
+
+    /**
+     * The resources in a module of a run-time image, or if no module is
+     * given, in all the modules of the image.
+     */
+    private static class ImageContainer implements Container {
+        ImageContainer(JImageReader image, String module) {
+            this.image = image;
+            this.module = module;
+        }
+
+        @Override
+        public JavaFileObject find(String relativeName, JavaFileObject.Kind kind) throws IOException {
+            JImageReader.Resource r = (module == null)
+                    ? image.findResource(relativeName)
+                    : image.findResource(module, relativeName);
+            return (r == null) ? null : new ImageFileObject(image, r, kind);
+        }
+
+        @Override
+        public void list(String packagePath, Set<JavaFileObject.Kind> kinds, boolean recurse,
+                List<JavaFileObject> result) throws IOException {
+            Iterable<String> modules = (module == null) ? image.getModuleNames() : List.of(module);
+            for (String m: modules) {
+                for (JImageReader.Resource r: image.getResources(m)) {
+                    String n = r.getPath();
+                    if (inPackage(n, packagePath, recurse) && kinds.contains(getKind(n)))
+                        result.add(new ImageFileObject(image, r, getKind(n)));
+                }
+            }
+        }
+
+        @Override
+        public boolean contains(FileObject fo) {
+            if (!(fo instanceof ImageFileObject))
+                return false;
+            ImageFileObject ifo = (ImageFileObject) fo;
+            return ifo.image == image && (module == null || module.equals(ifo.resource.getModule()));
+        }
+
+        @Override
+        public void close() {
+            // the image is closed by the file manager
+        }
+
+        private final JImageReader image;
+        private final String module;
+    }
+
+    private static class ModuleLocation implements Location {
+        ModuleLocation(String name, Container container) {
+            this.name = name;
+            this.container = container;
+        }
+
+        @Override
+        public String getName() {
+            return name;
+        }
+
+        @Override
+        public boolean isOutputLocation() {
+            return false;
+        }
+
+        @Override
+        public String toString() {
+            return name;
+        }
+
+        final String name;
+        final Container container;
+    }
+
+    /**
+     * The common parts of the file objects for files in a directory, for
+     * resources in a run-time image and for entries in a JAR file. This
+     * does not extend SimpleJavaFileObject, which does not allow the opaque
+     * URIs used for JAR file entries.
+     */
+    private abstract static class BaseFileObject implements JavaFileObject {
+        BaseFileObject(URI uri, String relativeName, Kind kind) {
+            this.uri = uri;
+            this.relativeName = relativeName;
+            this.kind = kind;
+        }
+
+        @Override
+        public URI toUri() {
+            return uri;
+        }
+
+        @Override
+        public Kind getKind() {
+            return kind;
+        }
+
+        @Override
+        public boolean isNameCompatible(String simpleName, Kind kind) {
+            String baseName = simpleName + kind.extension;
+            return kind == this.kind
+                    && (relativeName.equals(baseName) || relativeName.endsWith("/" + baseName));
+        }
+
+        @Override
+        public NestingKind getNestingKind() {
+            return null;
+        }
+
+        @Override
+        public Modifier getAccessLevel() {
+            return null;
+        }
+
+        @Override
+        public OutputStream openOutputStream() {
+            throw new UnsupportedOperationException();
+        }
+
+        @Override
+        public Reader openReader(boolean ignoreEncodingErrors) throws IOException {
+            return new StringReader(getCharContent(ignoreEncodingErrors).toString());
+        }
+
+        @Override
+        public CharSequence getCharContent(boolean ignoreEncodingErrors) throws IOException {
+            try (InputStream in = openInputStream()) {
+                return new String(in.readAllBytes(), Charset.defaultCharset());
+            }
+        }
+
+        @Override
+        public Writer openWriter() {
+            throw new UnsupportedOperationException();
+        }
+
+        @Override
+        public boolean delete() {
+            return false;
+        }
+
+        @Override
+        public String toString() {
+            return getName();
+        }
+
+        final URI uri;
+        final String relativeName;
+        final Kind kind;
+    }
+
+    private static class PathFileObject extends BaseFileObject {
+        PathFileObject(Path path, String relativeName, Kind kind) {
+            super(path.toUri().normalize(), relativeName, kind);
+            this.path = path;
+        }
+
+        @Override
+        public String getName() {
+            return path.toString();
+        }
+
+        @Override
+        public InputStream openInputStream() throws IOException {
+            return Files.newInputStream(path);
+        }
+
+        @Override
+        public long getLastModified() {
+            try {
+                return Files.getLastModifiedTime(path).toMillis();
+            } catch (IOException e) {
+                return 0;
+            }
+        }
+
+        final Path path;
+    }
+
+    private static class ZipEntryFileObject extends BaseFileObject {
+        ZipEntryFileObject(JarContainer container, ZipEntry entry, String relativeName, Kind kind) {
+            super(URI.create("jar:" + container.jar.toUri() + "!/" + entry.getName()), relativeName, kind);
+            this.container = container;
+            this.entry = entry;
+        }
+
+        @Override
+        public String getName() {
+            return container.jar + "(" + entry.getName() + ")";
+        }
+
+        @Override
+        public InputStream openInputStream() throws IOException {
+            return container.getZipFile().getInputStream(entry);
+        }
+
+        @Override
+        public long getLastModified() {
+            return entry.getTime();
+        }
+
+        final JarContainer container;
+        final ZipEntry entry;
+    }
+
+    private static class ImageFileObject extends BaseFileObject {
+        ImageFileObject(JImageReader image, JImageReader.Resource resource, Kind kind) {
+            super(URI.create("jrt:" + resource.getName()), resource.getPath(), kind);
+            this.image = image;
+            this.resource = resource;
+        }
+
+        @Override
+        public String getName() {
+            return "/modules" + resource.getName();
+        }
+
+        @Override
+        public InputStream openInputStream() throws IOException {
+            return new ByteArrayInputStream(image.getContent(resource));
+        }
+
+        @Override
+        public long getLastModified() {
+            try {
+                return Files.getLastModifiedTime(image.getFile()).toMillis();
+            } catch (IOException e) {
+                return 0;
+            }
+        }
+
+        final JImageReader image;
+        final JImageReader.Resource resource;
+    }
+
+    private final List<Container> archives = new ArrayList<>();   // named on the command line
+    private List<Container> classPath;          // null until set or first used
+    private List<Container> bootClassPath;      // null means use the system modules
+    private List<Path> modulePath = List.of();
+    private List<Path> upgradeModulePath = List.of();
+    private Map<String, ModuleLocation> modulePathModules;
+    private Map<String, ModuleLocation> upgradeModulePathModules;
+    private Map<String, ModuleLocation> systemModules;
+    private List<Container> systemClassPath;
+    private Path systemHome = Paths.get(System.getProperty("java.home"));
+    private boolean noSystem;
+    private JImageReader systemImage;
+    private int release;                        // 0 means ignore the versions in multi-release JAR files
+    private boolean allReleases;
+}
@@ -11,6 +11,7 @@
 
 package org.jacobin.jadis;
 
+import java.io.ByteArrayInputStream;
 import java.io.EOFException;
 import java.io.FileNotFoundException;
 import java.io.FilterInputStream;
@@ -21,11 +22,15 @@ import java.io.PrintWriter;
 import java.io.Reader;
 import java.io.StringWriter;
 import java.io.Writer;
+import java.net.MalformedURLException;
 import java.net.URI;
 import java.net.URISyntaxException;
-import java.net.URL;
 import java.net.URLConnection;
+import java.nio.file.Files;
+import java.nio.file.InvalidPathException;
 import java.nio.file.NoSuchFileException;
+import java.nio.file.Path;
+import java.nio.file.Paths;
 import java.security.DigestInputStream;
 import java.security.MessageDigest;
 import java.security.NoSuchAlgorithmException;
@@ -34,7 +39,9 @@ import java.util.ArrayList;
 import java.util.Arrays;
 import java.util.EnumSet;
 import java.util.HashMap;
+import java.util.HashSet;
 import java.util.Iterator;
+import java.util.LinkedHashMap;
 import java.util.List;
 import java.util.Locale;
 import java.util.Map;
@@ -42,6 +49,9 @@ import java.util.MissingResourceException;
 import java.util.Objects;
 import java.util.ResourceBundle;
 import java.util.Set;
+import java.util.zip.ZipEntry;
+import java.util.zip.ZipFile;
+import java.util.zip.ZipInputStream;
 
 import javax.lang.model.element.Modifier;
 import javax.lang.model.element.NestingKind;
@@ -53,6 +63,7 @@ import javax.tools.JavaFileObject;
 import javax.tools.StandardJavaFileManager;
 import javax.tools.StandardLocation;
 
+import org.jacobin.jadis.asm.ClassHierarchy;
 import org.jacobin.jadis.classfile.*;
 import org.jacobin.jadis.exceptions.InternalError;
 
@@ -70,6 +81,7 @@ public class JavapTask implements Messages {
         context.put(Messages.class, this);
         options = Options.instance(context);
         attributeFactory = new Attribute.Factory();
+        context.put(Attribute.Factory.class, attributeFactory);
     }
 
     public void setLog( Writer log ) {
@@ -161,15 +173,15 @@ public class JavapTask implements Messages {
             if (diagnosticListener == null)
               diagnosticListener = getDiagnosticListenerForWriter( log );
 
-//        if (fileManager == null)
-//            fileManager = getDefaultFileManager(diagnosticListener, log);
+        if (fileManager == null)
+            fileManager = getDefaultFileManager();
 
         Iterator<String> iter = args.iterator();
         boolean noArgs = !iter.hasNext();
 
         while (iter.hasNext()) {
             String arg = iter.next();
-            if (arg.startsWith("-"))
+            if (arg.startsWith("-") && !arg.equals("-"))    // "-" is the standard input
                 handleOption(arg, iter);
             else if (allowClasses) {
                 if (classes == null)
@@ -191,6 +203,15 @@ public class JavapTask implements Messages {
             throw new BadArgs("err.incompatible.options", sb);
         }
 
+        if (options.cfg != null && options.format != Options.Format.TEXT)
+            throw new BadArgs("err.options.conflict", "--cfg", "--format");
+        if (options.cfgMethod != null && options.cfg == null)
+            throw new BadArgs("err.option.requires", "--cfg-method", "--cfg");
+        if (options.verify && options.cfg != null)
+            throw new BadArgs("err.options.conflict", "--verify", "--cfg");
+        if (options.verify && options.format != Options.Format.TEXT)
+            throw new BadArgs("err.options.conflict", "--verify", "--format");
+
         if ((classes == null || classes.size() == 0) &&
                 !(noArgs || options.help || options.version || options.fullVersion)) {
             throw new BadArgs("err.no.classes.specified");
@@ -229,18 +250,60 @@ public class JavapTask implements Messages {
             throw new BadArgs("err.invalid.use.of.option", name).showUsage(true);
         }
 
+        // GNU-style options may use '=' instead of whitespace to separate
+        // the name of an option from its value, as in --module=java.base
+        int sep = name.indexOf('=');
+        if (sep > 0 && handleOption(name, name.substring(0, sep), name.substring(sep + 1)))
+            return;
+
         throw new BadArgs("err.unknown.option", name).showUsage(true);
     }
 
+    private boolean handleOption(String opt, String name, String value) throws BadArgs {
+        for (Option o: recognizedOptions) {
+            if (o.matches(name)) {
+                if (!o.hasArg)
+                    throw new BadArgs("err.no.value.allowed", name).showUsage(true);
+                if (value.isEmpty())
+                    throw new BadArgs("err.missing.arg", name).showUsage(true);
+                o.process(this, name, value);
+                return true;
+            }
+        }
+
+        int argCount = fileManager.isSupportedOption(name);
+        if (argCount == -1)
+            return false;
+        if (argCount == 0)
+            throw new BadArgs("err.no.value.allowed", name).showUsage(true);
+        if (value.isEmpty())
+            throw new BadArgs("err.missing.arg", name).showUsage(true);
+
+        try {
+            if (fileManager.handleOption(name, List.of(value).iterator()))
+                return true;
+        } catch (IllegalArgumentException e) {
+            throw new BadArgs("err.invalid.use.of.option", opt).showUsage(true);
+        }
+        return false;
+    }
+
     public int run() {
         if (classes == null || classes.isEmpty()) {
             return EXIT_ERROR;
         }
 
-        context.put(PrintWriter.class, log);
-//        ClassWriter classWriter = ClassWriter.instance(context);
-//        SourceWriter sourceWriter = SourceWriter.instance(context);
-//        sourceWriter.setFileManager(fileManager);
+        // an HTML page or a JSON document is written once all the classes have been seen
+        StringWriter sections = null;
+        if (options.format == Options.Format.HTML || options.format == Options.Format.JSON) {
+            sections = new StringWriter();
+            context.put(PrintWriter.class, new PrintWriter(sections));
+        } else {
+            context.put(PrintWriter.class, log);
+        }
+        ClassWriter classWriter = ClassWriter.instance(context);
+        SourceWriter sourceWriter = SourceWriter.instance(context);
+        sourceWriter.setFileManager(fileManager);
 
         if (options.moduleName != null) {
             try {
@@ -258,136 +321,521 @@ public class JavapTask implements Messages {
         int result = EXIT_OK;
 
         for (String className: classes) {
+            if (className.equals("-")) {
+                result = Math.max(result, writeStandardInput(classWriter));
+                continue;
+            }
+
+            List<JavaFileObject> classFiles;
             try {
-                result = writeClass(); //ALB in lieu of immediately below code--to shut up the compiler.
-//                result = writeClass(classWriter, className);
-//            } catch (ConstantPoolException e) {
-//                reportError("err.bad.constant.pool", className, e.getLocalizedMessage());
-//                result = EXIT_ERROR;
-            } catch (EOFException e) {
-                reportError("err.end.of.file", className);
-                result = EXIT_ERROR;
-            } catch (FileNotFoundException | NoSuchFileException e) {
-                reportError("err.file.not.found", e.getLocalizedMessage());
-                result = EXIT_ERROR;
+                classFiles = getClassFiles(className);
             } catch (IOException e) {
-                //e.printStackTrace();
-                Object msg = e.getLocalizedMessage();
-                if (msg == null) {
-                    msg = e;
+                reportError("err.ioerror", className, e.getLocalizedMessage());
+                result = Math.max(result, EXIT_ERROR);
+                continue;
+            }
+
+            if (classFiles == null)
+                result = Math.max(result, writeClass(classWriter, className, null));
+            else
+                result = Math.max(result, writeClassFiles(classWriter, className, classFiles));
+        }
+
+        if (options.format == Options.Format.HTML)
+            HtmlWriter.instance(context).writePage(sections.toString(), log);
+        else if (options.format == Options.Format.JSON)
+            JsonWriter.instance(context).writeDocument(sections, log);
+
+        if (verifier != null) {
+            for (String name: hierarchy.getMissingClasses())
+                reportWarning("warn.verify.class.not.found", name);
+            if (verifyErrors > 0)
+                result = Math.max(result, EXIT_ERROR);
+        }
+
+        return result;
+    }
+
+    /*
+     * Writes the class files found in a JAR file, JMOD file or directory,
+     * each preceded by its name.
+     */
+    private int writeClassFiles(ClassWriter classWriter, String name, List<JavaFileObject> classFiles) {
+        if (classFiles.isEmpty()) {
+            reportError("err.no.class.files", name);
+            return EXIT_ERROR;
+        }
+        int result = EXIT_OK;
+        for (JavaFileObject fo: classFiles) {
+            writeHeader(classWriter, fo.getName());
+            result = Math.max(result, writeClass(classWriter, fo.getName(), fo));
+        }
+        return result;
+    }
+
+    /*
+     * Writes the class read from the standard input, given as "-" on the
+     * command line. If the input is a JAR file, as shown by the "PK" magic
+     * number of a zip file, all the classes it contains are written instead.
+     */
+    private int writeStandardInput(ClassWriter classWriter) {
+        try {
+            byte[] content = System.in.readAllBytes();
+            if (content.length < 2 || content[0] != 'P' || content[1] != 'K')
+                return writeClass(classWriter, STDIN, new StdinFileObject(STDIN, content, 0));
+
+            List<JavaFileObject> classFiles = new ArrayList<>();
+            try (ZipInputStream zin = new ZipInputStream(new ByteArrayInputStream(content))) {
+                ZipEntry entry;
+                while ((entry = zin.getNextEntry()) != null) {
+                    String name = entry.getName();
+                    if (!entry.isDirectory() && name.endsWith(".class") && !name.startsWith("META-INF/")) {
+                        String fileName = STDIN + "(" + name + ")";
+                        long lastModified = (entry.getTime() == -1) ? 0 : entry.getTime();
+                        classFiles.add(new StdinFileObject(fileName, zin.readAllBytes(), lastModified));
+                    }
                 }
-                reportError("err.ioerror", className, msg);
-                result = EXIT_ERROR;
-            } catch (OutOfMemoryError e) {
-                reportError("err.nomem");
-                result = EXIT_ERROR;
-            } catch (FatalError e) {
-                Object msg = e.getLocalizedMessage();
-                if (msg == null) {
-                    msg = e;
+            }
+            return writeClassFiles(classWriter, STDIN, classFiles);
+        } catch (IOException e) {
+            reportError("err.ioerror", STDIN, e.getLocalizedMessage());
+            return EXIT_ERROR;
+        }
+    }
+
+    private static final String STDIN = "<stdin>";
+
+    /*
+     * If the argument names a JAR file, JMOD file or directory, rather than a
+     * class, returns the class files it contains; otherwise returns null.
+     */
+    private List<JavaFileObject> getClassFiles(String className) throws IOException {
+        if (!(fileManager instanceof JavapFileManager))
+            return null;
+        List<JavaFileObject> classFiles = ((JavapFileManager) fileManager).getClassFiles(className);
+        if (classFiles != null && !className.endsWith(".class") && getClassFileObject(className) != null)
+            return null;
+        return classFiles;
+    }
+
+    /*
+     * Writes a class, either found by name or given by a file object, and
+     * reports any errors that occur.
+     */
+    private int writeClass(ClassWriter classWriter, String className, JavaFileObject fo) {
+        try {
+            if (fo == null)
+                return writeClass(classWriter, className);
+            write(read(fo));
+            return EXIT_OK;
+        } catch (ConstantPoolException e) {
+            reportError("err.bad.constant.pool", className, e.getLocalizedMessage());
+            return EXIT_ERROR;
+        } catch (EOFException e) {
+            reportError("err.end.of.file", className);
+            return EXIT_ERROR;
+        } catch (BadURL e) {
+            reportError(e.key, e.args);
+            return EXIT_ERROR;
+        } catch (FileNotFoundException | NoSuchFileException e) {
+            reportError("err.file.not.found", e.getLocalizedMessage());
+            return EXIT_ERROR;
+        } catch (IOException e) {
+            //e.printStackTrace();
+            Object msg = e.getLocalizedMessage();
+            if (msg == null) {
+                msg = e;
+            }
+            reportError("err.ioerror", className, msg);
+            return EXIT_ERROR;
+        } catch (OutOfMemoryError e) {
+            reportError("err.nomem");
+            return EXIT_ERROR;
+        } catch (FatalError e) {
+            Object msg = e.getLocalizedMessage();
+            if (msg == null) {
+                msg = e;
+            }
+            reportError("err.fatal.err", msg);
+            return EXIT_ERROR;
+        } catch (Throwable t) {
+            StringWriter sw = new StringWriter();
+            PrintWriter pw = new PrintWriter(sw);
+            t.printStackTrace(pw);
+            pw.close();
+            reportError("err.crash", t.toString(), sw.toString());
+            return EXIT_ABNORMAL;
+        }
+    }
+
+    protected int writeClass(ClassWriter classWriter, String className)
+            throws IOException, ConstantPoolException {
+        JavaFileObject fo = open(className);
+        if (fo == null) {
+            reportError("err.class.not.found", className);
+            return EXIT_ERROR;
+        }
+
+        if (fileManager instanceof JavapFileManager) {
+            JavapFileManager fm = (JavapFileManager) fileManager;
+            Map<Integer, JavaFileObject> variants = fm.getVariants(fo);
+            if (variants.size() > 1 && fm.isAllReleases()) {
+                int result = EXIT_OK;
+                for (JavaFileObject v: variants.values()) {
+                    writeHeader(classWriter, v.getName());
+                    result = Math.max(result, writeInnerClasses(classWriter, writeClassFile(className, v), v));
                 }
-                reportError("err.fatal.err", msg);
-                result = EXIT_ERROR;
-            } catch (Throwable t) {
-                StringWriter sw = new StringWriter();
-                PrintWriter pw = new PrintWriter(sw);
-                t.printStackTrace(pw);
-                pw.close();
-                reportError("err.crash", t.toString(), sw.toString());
-                result = EXIT_ABNORMAL;
+                return result;
             }
+            if (variants.size() > 1 && fm.isMultiRelease()) {
+                StringBuilder releases = new StringBuilder();
+                for (int r: variants.keySet()) {
+                    if (releases.length() > 0)
+                        releases.append(", ");
+                    releases.append(r == 0 ? "base" : String.valueOf(r));
+                }
+                int r = fm.getRelease(fo);
+                reportNote("note.multi.release.variants", className, releases,
+                        r == 0 ? "base" : String.valueOf(r));
+            }
+        }
+
+        return writeInnerClasses(classWriter, writeClassFile(className, fo), fo);
+    }
+
+    /*
+     * Writes the classes nested in a class that has just been written,
+     * if they have been requested with -XDinner.
+     */
+    private int writeInnerClasses(ClassWriter classWriter, ClassFile cf, JavaFileObject fo)
+            throws IOException, ConstantPoolException {
+        if (!options.showInnerClasses || cf.this_class == 0)
+            return EXIT_OK;
+        Set<String> written = new HashSet<>();
+        written.add(cf.getName());
+        return writeInnerClasses(classWriter, cf, fo, written);
+    }
+
+    /*
+     * Writes the classes nested in a class, and those nested in them in turn.
+     * Member classes are found from the InnerClasses attribute. Local and
+     * anonymous classes, which have no outer class there, are written if their
+     * EnclosingMethod attribute names the class. Then any other members of a
+     * nest of which the class is the host, from its NestMembers attribute,
+     * are written.
+     */
+    private int writeInnerClasses(ClassWriter classWriter, ClassFile cf, JavaFileObject fo, Set<String> written)
+            throws IOException, ConstantPoolException {
+        String className = cf.getName();
+        ConstantPool cp = cf.constant_pool;
+        List<String> memberClasses = new ArrayList<>();
+        List<String> localClasses = new ArrayList<>();
+        List<String> nestMembers = new ArrayList<>();
+        try {
+            Attribute a = cf.getAttribute(Attribute.InnerClasses);
+            if (a instanceof InnerClasses_attribute) {
+                for (InnerClasses_attribute.Info info: ((InnerClasses_attribute) a).classes) {
+                    if (info.inner_class_info_index == 0) {
+                        reportError("err.bad.innerclasses.attribute", className);
+                        return EXIT_ERROR;
+                    }
+                    String innerClassName = info.getInnerClassInfo(cp).getName();
+                    if (info.outer_class_info_index != 0) {
+                        if (info.getOuterClassInfo(cp).getName().equals(className))
+                            memberClasses.add(innerClassName);
+                    } else if (!innerClassName.equals(className)) {
+                        localClasses.add(innerClassName);
+                    }
+                }
+            } else if (a != null) {
+                reportError("err.bad.innerclasses.attribute", className);
+                return EXIT_ERROR;
+            }
+
+            Attribute n = cf.getAttribute(Attribute.NestMembers);
+            if (n instanceof NestMembers_attribute) {
+                for (ConstantPool.CONSTANT_Class_info info: ((NestMembers_attribute) n).getChildren(cp))
+                    nestMembers.add(info.getName());
+            }
+        } catch (ConstantPoolException e) {
+            reportError("err.bad.innerclasses.attribute", className);
+            return EXIT_ERROR;
         }
 
+        int result = EXIT_OK;
+        for (String c: memberClasses)
+            result = Math.max(result, writeInnerClass(classWriter, cf, fo, c, false, true, written));
+        for (String c: localClasses)
+            result = Math.max(result, writeInnerClass(classWriter, cf, fo, c, true, false, written));
+        for (String c: nestMembers)
+            result = Math.max(result, writeInnerClass(classWriter, cf, fo, c, false, false, written));
         return result;
     }
 
-    int writeClass() throws IOException, EOFException { ///invented by ALB to silence compiler
-        int i = 42;
-        if( i > 10 )
-            throw new EOFException();
-        else if ( i > 20 )
-            throw new FileNotFoundException();
-        return i;
-    }
-
-//    protected int writeClass(ClassWriter classWriter, String className)
-//            throws IOException, ConstantPoolException {
-//        JavaFileObject fo = open(className);
-//        if (fo == null) {
-//            reportError("err.class.not.found", className);
-//            return EXIT_ERROR;
-//        }
-//
-//        ClassFileInfo cfInfo = read(fo);
-//        if (!className.endsWith(".class")) {
-//            if (cfInfo.cf.this_class == 0) {
-//                if (!className.equals("module-info")) {
-//                    reportWarning("warn.unexpected.class", fo.getName(), className);
-//                }
-//            } else {
-//                String cfName = cfInfo.cf.getName();
-//                if (!cfName.replaceAll("[/$]", ".").equals(className.replaceAll("[/$]", "."))) {
-//                    reportWarning("warn.unexpected.class", fo.getName(), className);
-//                }
-//            }
-//        }
-//        write(cfInfo);
-
-//        if (options.showInnerClasses) {
-//            ClassFile cf = cfInfo.cf;
-//            Attribute a = cf.getAttribute(Attribute.InnerClasses);
-//            if (a instanceof InnerClasses_attribute) {
-//                InnerClasses_attribute inners = (InnerClasses_attribute) a;
-//                try {
-//                    int result = EXIT_OK;
-//                    for (int i = 0; i < inners.classes.length; i++) {
-//                        int outerIndex = inners.classes[i].outer_class_info_index;
-//                        ConstantPool.CONSTANT_Class_info outerClassInfo = cf.constant_pool.getClassInfo(outerIndex);
-//                        String outerClassName = outerClassInfo.getName();
-//                        if (outerClassName.equals(cf.getName())) {
-//                            int innerIndex = inners.classes[i].inner_class_info_index;
-//                            ConstantPool.CONSTANT_Class_info innerClassInfo = cf.constant_pool.getClassInfo(innerIndex);
-//                            String innerClassName = innerClassInfo.getName();
-//                            classWriter.println("// inner class " + innerClassName.replaceAll("[/$]", "."));
-//                            classWriter.println();
-//                            result = writeClass(classWriter, innerClassName);
-//                            if (result != EXIT_OK) return result;
-//                        }
-//                    }
-//                    return result;
-//                } catch (ConstantPoolException e) {
-//                    reportError("err.bad.innerclasses.attribute", className);
-//                    return EXIT_ERROR;
-//                }
-//            } else if (a != null) {
-//                reportError("err.bad.innerclasses.attribute", className);
-//                return EXIT_ERROR;
-//            }
-//        }
-//
-//        return EXIT_OK;
-//    }
+    /*
+     * Writes one nested class. Only a member class of the outer class must
+     * be found; the local, anonymous and nest member classes named by the
+     * outer class may belong to other classes, and are skipped if missing.
+     */
+    private int writeInnerClass(ClassWriter classWriter, ClassFile outer, JavaFileObject outerFile,
+            String innerClassName, boolean local, boolean member, Set<String> written)
+            throws IOException, ConstantPoolException {
+        if (written.contains(innerClassName))
+            return EXIT_OK;
+
+        JavaFileObject fo = null;
+        if (fileManager instanceof JavapFileManager)
+            fo = ((JavapFileManager) fileManager).getSibling(outerFile, innerClassName);
+        if (fo == null)
+            fo = open(innerClassName);
+        if (fo == null) {
+            if (!member)
+                return EXIT_OK;
+            reportError("err.class.not.found", innerClassName);
+            return EXIT_ERROR;
+        }
+
+        ClassFileInfo cfInfo = read(fo);
+        ClassFile cf = cfInfo.cf;
+        if (local) {
+            // a local or anonymous class of some other class
+            Attribute a = cf.getAttribute(Attribute.EnclosingMethod);
+            if (!(a instanceof EnclosingMethod_attribute)
+                    || !((EnclosingMethod_attribute) a).getClassName(cf.constant_pool).equals(outer.getName()))
+                return EXIT_OK;
+        }
+        if (cf.this_class == 0 || !cf.getName().equals(innerClassName)) {
+            reportWarning("warn.unexpected.class", fo.getName(), innerClassName.replaceAll("[/$]", "."));
+            return EXIT_OK;
+        }
+
+        written.add(innerClassName);
+        writeHeader(classWriter, "inner class " + innerClassName.replaceAll("[/$]", "."));
+        write(cfInfo);
+        return writeInnerClasses(classWriter, cf, fo, written);
+    }
+
+    /*
+     * Writes a comment to introduce one of several classes written for a
+     * single argument. Other formats identify the file for each class
+     * in their own way.
+     */
+    private void writeHeader(ClassWriter classWriter, String header) {
+        if (options.format == Options.Format.TEXT && options.cfg == null && !options.verify) {
+            classWriter.println("// " + header);
+            classWriter.println();
+        }
+    }
+
+    private ClassFile writeClassFile(String className, JavaFileObject fo)
+            throws IOException, ConstantPoolException {
+        ClassFileInfo cfInfo = read(fo);
+        if (!className.endsWith(".class")) {
+            if (cfInfo.cf.this_class == 0) {
+                if (!className.equals("module-info")) {
+                    reportWarning("warn.unexpected.class", fo.getName(), className);
+                }
+            } else {
+                String cfName = cfInfo.cf.getName();
+                if (!getCandidateNames(className).contains(cfName)) {
+                    reportWarning("warn.unexpected.class", fo.getName(), className);
+                }
+            }
+        }
+        write(cfInfo);
+        return cfInfo.cf;
+    }
+
+    public static class ClassFileInfo {
+        ClassFileInfo(JavaFileObject fo, ClassFile cf, Map<String, byte[]> digests, int size) {
+            this.fo = fo;
+            this.cf = cf;
+            this.digests = digests;
+            this.size = size;
+        }
+
+        public final JavaFileObject fo;
+        public final ClassFile cf;
+        public final Map<String, byte[]> digests;
+        public final int size;
+    }
+
+    public ClassFileInfo read(JavaFileObject fo) throws IOException, ConstantPoolException {
+        InputStream in = fo.openInputStream();
+        try {
+            SizeInputStream sizeIn = null;
+            Map<String, MessageDigest> mds = new LinkedHashMap<>();
+            if (options.sysInfo || options.verbose) {
+                for (String algorithm: options.digestAlgorithms) {
+                    try {
+                        MessageDigest md = MessageDigest.getInstance(algorithm);
+                        in = new DigestInputStream(in, md);
+                        mds.put(algorithm, md);
+                    } catch (NoSuchAlgorithmException ignore) {
+                    }
+                }
+                in = sizeIn = new SizeInputStream(in);
+            }
+
+            ClassFile cf = ClassFile.read(in, attributeFactory);
+            Map<String, byte[]> digests = new LinkedHashMap<>();
+            for (Map.Entry<String, MessageDigest> e: mds.entrySet())
+                digests.put(e.getKey(), e.getValue().digest());
+            int size = (sizeIn == null) ? -1 : sizeIn.size();
+            return new ClassFileInfo(fo, cf, digests, size);
+        } finally {
+            in.close();
+        }
+    }
+
+    public void write(ClassFileInfo info) {
+        if (options.verify) {
+            verify(info);
+            return;
+        }
+        if (options.cfg != null) {
+            CfgWriter.instance(context).write(info.cf);
+            return;
+        }
+        switch (options.format) {
+            case JSON:
+                JsonWriter.instance(context).write(info.cf, info.fo.getName());
+                return;
+            case JASMIN:
+            case KRAKATAU: {
+                String name = getFileName(info.fo);
+                AssemblyWriter.instance(context).write(info.cf, (name != null) ? name : info.fo.getName());
+                return;
+            }
+        }
+        ClassWriter classWriter = ClassWriter.instance(context);
+        if (options.sysInfo || options.verbose) {
+            classWriter.setFile(getFileName(info.fo));
+            classWriter.setLastModified(info.fo.getLastModified());
+            classWriter.setDigests(info.digests);
+            classWriter.setFileSize(info.size);
+        }
+        if (options.format == Options.Format.HTML)
+            HtmlWriter.instance(context).write(info.cf);
+        else
+            classWriter.write(info.cf);
+    }
+
+    /*
+     * Returns the name of a class file as shown by -sysinfo, and in the warning
+     * at the end of an assembly listing: the path of a local file, <stdin>
+     * for the standard input, or else the URI of the file.
+     */
+    private static String getFileName(JavaFileObject fo) {
+        if (fo instanceof StdinFileObject)
+            return fo.getName();
+        URI uri = fo.toUri();
+        if (uri == null)
+            return null;
+        return "file".equals(uri.getScheme()) ? uri.getPath() : uri.toString();
+    }
+
+    protected void setClassFile(ClassFile classFile) {
+        ClassWriter classWriter = ClassWriter.instance(context);
+        classWriter.setClassFile(classFile);
+    }
+
+    protected void setMethod(Method enclosingMethod) {
+        ClassWriter classWriter = ClassWriter.instance(context);
+        classWriter.setMethod(enclosingMethod);
+    }
+
+    /*
+     * Verifies the code of the methods of a class, for --verify, and reports
+     * the first error in each method.
+     */
+    private void verify(ClassFileInfo info) {
+        ClassFile cf = info.cf;
+        if (cf.major_version < 50) {
+            reportWarning("warn.verify.old.version", info.fo.getName(), cf.major_version);
+            return;
+        }
+        if (verifier == null) {
+            hierarchy = new ClassHierarchy(this::openClassFile);
+            verifier = new Verifier(hierarchy);
+        }
+        List<Verifier.Problem> problems;
+        try {
+            problems = verifier.verify(cf);
+        } catch (ConstantPoolException e) {
+            reportError("err.bad.constant.pool", info.fo.getName(), e.getLocalizedMessage());
+            verifyErrors++;
+            return;
+        }
+        ClassWriter.instance(context).setClassFile(cf);     // for the operands of instructions
+        CfgWriter cfgWriter = CfgWriter.instance(context);
+        for (Verifier.Problem p: problems) {
+            String reason = getMessage(p.key, p.args);
+            if (p.instruction == null) {
+                reportError("err.verify.method", p.method, reason);
+                verifyErrors++;
+                continue;
+            }
+            String instr = cfgWriter.toString(p.instruction);
+            if (p.expected != null)
+                reportError("err.verify.frames", p.method, p.offset, instr, reason, p.expected, p.actual);
+            else if (p.actual != null)
+                reportError("err.verify.frame", p.method, p.offset, instr, reason, p.actual);
+            else
+                reportError("err.verify", p.method, p.offset, instr, reason);
+            verifyErrors++;
+        }
+    }
+
+    /*
+     * Opens the class file of a class used by the classes being verified,
+     * to find its superclasses.
+     */
+    private InputStream openClassFile(String name) throws IOException {
+        JavaFileObject fo = getClassFileObject(name.replace('/', '.'));
+        return (fo == null) ? null : fo.openInputStream();
+    }
+
+    protected void write(Field f) {
+        ClassWriter classWriter = ClassWriter.instance(context);
+        classWriter.writeField(f);
+    }
+
+    protected void write(Method m) {
+        ClassWriter classWriter = ClassWriter.instance(context);
+        classWriter.writeMethod(m);
+    }
 
     protected JavaFileObject open(String className) throws IOException {
-        // for compatibility, first see if it is a class name
-        JavaFileObject fo = getClassFileObject(className);
+        // see if it is a class name, or the name of a nested class with dots in place of $;
+        // for compatibility, prefer the class with the most package names if there are several
+        JavaFileObject fo = null;
+        List<String> found = new ArrayList<>();
+        for (String cn: getCandidateNames(className)) {
+            JavaFileObject f = getClassFileObject(cn);
+            if (f != null) {
+                if (fo == null)
+                    fo = f;
+                found.add(cn.replace('/', '.'));
+            }
+        }
+        if (found.size() > 1)
+            reportWarning("warn.ambiguous.class", className, String.join(", ", found), found.get(0));
         if (fo != null)
             return fo;
 
-        // see if it is an inner class, by replacing dots to $, starting from the right
-        String cn = className;
-        int lastDot;
-        while ((lastDot = cn.lastIndexOf(".")) != -1) {
-            cn = cn.substring(0, lastDot) + "$" + cn.substring(lastDot + 1);
-            fo = getClassFileObject(cn);
-            if (fo != null)
-                return fo;
-        }
-
         if (!className.endsWith(".class"))
             return null;
 
-        if (fileManager instanceof StandardJavaFileManager) {
+        if (fileManager instanceof JavapFileManager) {
+            try {
+                fo = ((JavapFileManager) fileManager).getJavaFileObject(className);
+                if (fo != null) {
+                    return fo;
+                }
+            } catch (IllegalArgumentException ignore) {
+            }
+        } else if (fileManager instanceof StandardJavaFileManager) {
             StandardJavaFileManager sfm = (StandardJavaFileManager) fileManager;
             try {
                 fo = sfm.getJavaFileObjects(className).iterator().next();
@@ -399,73 +847,272 @@ public class JavapTask implements Messages {
         }
 
         // see if it is a URL, and if so, wrap it in just enough of a JavaFileObject
-        // to suit javap's needs
-        if (className.matches("^[A-Za-z]+:.*")) {
+        // to suit javap's needs; the scheme must have at least two characters, so that
+        // a Windows drive letter is not taken for one
+        if (className.matches("^[A-Za-z][A-Za-z0-9+.-]+:.*")) {
+            final URI uri;
             try {
-                final URI uri = new URI(className);
-                final URL url = uri.toURL();
-                final URLConnection conn = url.openConnection();
+                uri = new URI(className);
+            } catch (URISyntaxException e) {
+                throw new BadURL("err.invalid.url", className, e.getReason());
+            }
+            if (isLocalFile(uri))
+                return openFile(uri);
+            if (isLocalJar(uri))
+                return openJarEntry(uri);
+            if (!options.allowRemoteURLs)
+                throw new BadURL("err.remote.url", className);
+            try {
+                final URLConnection conn = uri.toURL().openConnection();
                 conn.setUseCaches(false);
-                return new JavaFileObject() {
-                    public Kind getKind() {
-                        return JavaFileObject.Kind.CLASS;
+                return new SimpleFileObject(uri) {
+                    public InputStream openInputStream() throws IOException {
+                        return conn.getInputStream();
                     }
 
-                    public boolean isNameCompatible(String simpleName, Kind kind) {
-                        throw new UnsupportedOperationException();
+                    public long getLastModified() {
+                        return conn.getLastModified();
                     }
+                };
+            } catch (MalformedURLException | IllegalArgumentException e) {
+                throw new BadURL("err.invalid.url", className, e.getLocalizedMessage());
+            } catch (IOException ignore) {
+            }
+        }
 
-                    public NestingKind getNestingKind() {
-                        throw new UnsupportedOperationException();
-                    }
+        return null;
+    }
 
-                    public Modifier getAccessLevel() {
-                        throw new UnsupportedOperationException();
-                    }
+    /*
+     * Returns whether a URI is a file: URI for a file on this machine,
+     * such as file:///path/to/MyClass.class or file:path/to/MyClass.class.
+     */
+    private static boolean isLocalFile(URI uri) {
+        if (!"file".equalsIgnoreCase(uri.getScheme()))
+            return false;
+        String host = uri.getHost();
+        return uri.getRawAuthority() == null || host == null || host.isEmpty() || host.equals("localhost");
+    }
 
-                    public URI toUri() {
-                        return uri;
-                    }
+    /*
+     * Returns whether a URI is a jar: URI for an entry in a JAR file on this
+     * machine, such as jar:file:///path/to/MyJar.jar!/mypkg/MyClass.class.
+     * The entry may itself be in a JAR file nested in the first, as in
+     * jar:file:///path/to/MyApp.jar!/lib/MyJar.jar!/mypkg/MyClass.class.
+     */
+    private static boolean isLocalJar(URI uri) throws BadURL {
+        if (!"jar".equalsIgnoreCase(uri.getScheme()))
+            return false;
+        String[] parts = uri.getRawSchemeSpecificPart().split("!/");
+        return parts.length > 1 && isLocalFile(toURI(parts[0], uri));
+    }
 
-                    public String getName() {
-                        return uri.toString();
-                    }
+    /*
+     * Returns the path of a local file given by a file: URI. An opaque URI,
+     * such as file:MyClass.class, is taken to be relative to the current directory.
+     */
+    private static Path toPath(URI uri) throws BadURL {
+        try {
+            return uri.isOpaque() ? Paths.get(uri.getSchemeSpecificPart()) : Paths.get(uri.getPath());
+        } catch (InvalidPathException e) {
+            throw new BadURL("err.invalid.url", uri, e.getReason());
+        }
+    }
 
-                    public InputStream openInputStream() throws IOException {
-                        return conn.getInputStream();
-                    }
+    /*
+     * Parses part of a jar: URI, reporting an error in terms of the whole URI.
+     */
+    private static URI toURI(String part, URI uri) throws BadURL {
+        try {
+            return new URI(part);
+        } catch (URISyntaxException e) {
+            throw new BadURL("err.invalid.url", uri, e.getReason());
+        }
+    }
 
-                    public OutputStream openOutputStream() throws IOException {
-                        throw new UnsupportedOperationException();
-                    }
+    private static JavaFileObject openFile(URI uri) throws BadURL {
+        final Path file = toPath(uri);
+        return new SimpleFileObject(file.toUri().normalize()) {
+            public InputStream openInputStream() throws IOException {
+                return Files.newInputStream(file);
+            }
 
-                    public Reader openReader(boolean ignoreEncodingErrors) throws IOException {
-                        throw new UnsupportedOperationException();
-                    }
+            public long getLastModified() {
+                try {
+                    return Files.getLastModifiedTime(file).toMillis();
+                } catch (IOException e) {
+                    return 0;
+                }
+            }
+        };
+    }
 
-                    public CharSequence getCharContent(boolean ignoreEncodingErrors) throws IOException {
-                        throw new UnsupportedOperationException();
-                    }
+    /*
+     * Opens an entry given by a jar: URI. The outermost JAR file is read as a
+     * zip file; any nested JAR files are read from the stream for their entry
+     * in the enclosing JAR file, since they cannot be opened in place. The
+     * content of the entry is read when the file object is opened.
+     */
+    private static JavaFileObject openJarEntry(URI uri) throws BadURL {
+        String[] parts = uri.getRawSchemeSpecificPart().split("!/");
+        final Path jar = toPath(toURI(parts[0], uri));
+        final List<String> entryNames = new ArrayList<>();
+        for (int i = 1; i < parts.length; i++)
+            entryNames.add(toURI(parts[i], uri).getPath());
+        return new SimpleFileObject(uri) {
+            public InputStream openInputStream() throws IOException {
+                read();
+                return new ByteArrayInputStream(content);
+            }
 
-                    public Writer openWriter() throws IOException {
-                        throw new UnsupportedOperationException();
-                    }
+            public long getLastModified() {
+                try {
+                    read();
+                    return lastModified;
+                } catch (IOException e) {
+                    return 0;
+                }
+            }
 
-                    public long getLastModified() {
-                        return conn.getLastModified();
+            private void read() throws IOException {
+                if (content != null)
+                    return;
+                try (ZipFile zf = new ZipFile(jar.toFile())) {
+                    ZipEntry entry = zf.getEntry(entryNames.get(0));
+                    if (entry == null || entry.isDirectory())
+                        throw new FileNotFoundException(uri.toString());
+                    InputStream in = zf.getInputStream(entry);
+                    for (String name: entryNames.subList(1, entryNames.size())) {
+                        ZipInputStream zin = new ZipInputStream(in);
+                        while ((entry = zin.getNextEntry()) != null && !entry.getName().equals(name))
+                            continue;
+                        if (entry == null || entry.isDirectory())
+                            throw new FileNotFoundException(uri.toString());
+                        in = zin;
                     }
+                    content = in.readAllBytes();
+                    lastModified = entry.getTime() == -1 ? 0 : entry.getTime();
+                } catch (NoSuchFileException e) {
+                    throw new FileNotFoundException(uri.toString());
+                }
+            }
 
-                    public boolean delete() {
-                        throw new UnsupportedOperationException();
-                    }
+            private byte[] content;
+            private long lastModified;
+        };
+    }
 
-                };
-            } catch (URISyntaxException | IOException ignore) {
-            }
+    /*
+     * Just enough of a JavaFileObject, for a class file given by a URL or read
+     * from the standard input, to suit javap's needs.
+     */
+    private abstract static class SimpleFileObject implements JavaFileObject {
+        private final URI uri;
+
+        SimpleFileObject(URI uri) {
+            this.uri = uri;
         }
 
-        return null;
+        public Kind getKind() {
+            return JavaFileObject.Kind.CLASS;
+        }
+
+        public boolean isNameCompatible(String simpleName, Kind kind) {
+            throw new UnsupportedOperationException();
+        }
+
+        public NestingKind getNestingKind() {
+            throw new UnsupportedOperationException();
+        }
+
+        public Modifier getAccessLevel() {
+            throw new UnsupportedOperationException();
+        }
+
+        public URI toUri() {
+            return uri;
+        }
+
+        public String getName() {
+            return uri.toString();
+        }
+
+        public OutputStream openOutputStream() throws IOException {
+            throw new UnsupportedOperationException();
+        }
+
+        public Reader openReader(boolean ignoreEncodingErrors) throws IOException {
+            throw new UnsupportedOperationException();
+        }
+
+        public CharSequence getCharContent(boolean ignoreEncodingErrors) throws IOException {
+            throw new UnsupportedOperationException();
+        }
+
+        public Writer openWriter() throws IOException {
+            throw new UnsupportedOperationException();
+        }
+
+        public boolean delete() {
+            throw new UnsupportedOperationException();
+        }
+    }
+
+    /*
+     * A class file read from the standard input, either by itself or as an
+     * entry in a JAR file.
+     */
+    private static class StdinFileObject extends SimpleFileObject {
+        private final String name;
+        private final byte[] content;
+        private final long lastModified;
+
+        StdinFileObject(String name, byte[] content, long lastModified) {
+            super(null);
+            this.name = name;
+            this.content = content;
+            this.lastModified = lastModified;
+        }
+
+        @Override
+        public String getName() {
+            return name;
+        }
+
+        public InputStream openInputStream() {
+            return new ByteArrayInputStream(content);
+        }
+
+        public long getLastModified() {
+            return lastModified;
+        }
+    }
+
+    /*
+     * Returns the binary names, in internal form, to which a class name given
+     * on the command line may refer, since each dot in it may separate the
+     * names of packages or the names of a class and a class nested in it:
+     * first the name with every dot taken to separate packages, and then
+     * with each dot in turn, from the right, taken to separate nested classes.
+     */
+    private static List<String> getCandidateNames(String className) {
+        List<String> names = new ArrayList<>();
+        String cn = className;
+        names.add(cn.replace('.', '/'));
+        int lastDot;
+        while ((lastDot = cn.lastIndexOf(".")) != -1) {
+            cn = cn.substring(0, lastDot) + "$" + cn.substring(lastDot + 1);
+            names.add(cn.replace('.', '/'));
+        }
+        return names;
     }
+
+    private JavaFileManager getDefaultFileManager() {
+        if (defaultFileManager == null)
+            defaultFileManager = new JavapFileManager();
+        return defaultFileManager;
+    }
+
     private JavaFileObject getClassFileObject(String className) throws IOException {
         try {
             JavaFileObject fo;
@@ -664,6 +1311,9 @@ public class JavapTask implements Messages {
     List<String> classes;
     Location moduleLocation;
     Options options;
+    ClassHierarchy hierarchy;       // for --verify
+    Verifier verifier;
+    int verifyErrors;
     //ResourceBundle bundle;
     Locale task_locale;
     Map<Locale, ResourceBundle> bundles;
@@ -671,7 +1321,7 @@ public class JavapTask implements Messages {
 
     private static final String progname = "javap";
 
-    private static class SizeInputStream extends FilterInputStream { //note: used by presenty ALB-commented out code
+    private static class SizeInputStream extends FilterInputStream {
         SizeInputStream(InputStream in) {
             super(in);
         }
@@ -698,6 +1348,22 @@ public class JavapTask implements Messages {
         private int size;
     }
 
+    /*
+     * A URL for a class that cannot be used, reported with a specific message.
+     */
+    private static class BadURL extends IOException {
+        static final long serialVersionUID = 3062481905718453716L;
+
+        final String key;
+        final Object[] args;
+
+        BadURL( String key, Object... args ) {
+            super( key );
+            this.key = key;
+            this.args = args;
+        }
+    }
+
     public class BadArgs extends Exception {
         static final long serialVersionUID = 8765093759964640721L;
 
@@ -0,0 +1,1245 @@
+/*
+ * Copyright (c) 2021 by Andrew Binstock.
+ *
+ * Portions of this file are copyright Oracle Corp.
+ * Those portions are licensed under GPL v. 2.0
+ * with the Oracle classpath exception. Due to the
+ * requirements of that license, the portions that
+ * are copyrighted by Andrew Binstock are licensed
+ * using the same terms and requirements.
+ *
+ */
+
+package org.jacobin.jadis;
+
+import java.io.PrintWriter;
+import java.io.StringWriter;
+import java.util.ArrayList;
+import java.util.Collection;
+import java.util.Iterator;
+import java.util.LinkedHashMap;
+import java.util.List;
+import java.util.Map;
+
+import org.jacobin.jadis.classfile.*;
+import org.jacobin.jadis.classfile.ConstantPool.CPInfo;
+import org.jacobin.jadis.classfile.ConstantPool.CPRefInfo;
+import org.jacobin.jadis.classfile.StackMapTable_attribute.stack_map_frame;
+import org.jacobin.jadis.classfile.StackMapTable_attribute.verification_type_info;
+
+/*
+ *  Writes the full model of each class file as a JSON object, for --format=json.
+ *  A run writes a single document, which follows the schema in
+ *  resources/jadis-class.schema.json and gives the classes in the order in which
+ *  they were read. Each class includes every entry in the constant pool, and
+ *  every field, method and attribute, regardless of the options that select
+ *  what is shown as text. References to the constant pool are given by index,
+ *  as in the class file. As for HTML, the classes are written to a buffer, and
+ *  the document is written by writeDocument once all the classes have been seen.
+ *
+ *  <p><b>This is NOT part of any supported API.
+ *  If you write code that depends on this, you do so at your own risk.
+ *  This code and its internal interfaces are subject to change or
+ *  deletion without notice.</b>
+ */
+public class JsonWriter extends BasicWriter {
+    /**
+     * The name of the schema, given as "schema" in the document.
+     */
+    public static final String SCHEMA = "jadis-class";
+
+    /**
+     * The version of the schema, given as "schemaVersion" in the document.
+     * It is incremented whenever a change is made that could break a consumer,
+     * such as removing or renaming a member, or changing the type of its value.
+     */
+    public static final int SCHEMA_VERSION = 1;
+
+    static JsonWriter instance(Context context) {
+        JsonWriter instance = context.get(JsonWriter.class);
+        if (instance == null)
+            instance = new JsonWriter(context);
+        return instance;
+    }
+
+    protected JsonWriter(Context context) {
+        super(context);
+        context.put(JsonWriter.class, this);
+        attributeFactory = context.get(Attribute.Factory.class);
+        options = Options.instance(context);
+    }
+
+    /**
+     * Writes a class file as an element of the "classes" array of the document.
+     * @param cf the class file
+     * @param file the name of the file from which the class file was read
+     */
+    public void write(ClassFile cf, String file) {
+        constant_pool = cf.constant_pool;
+
+        Map<String, Object> doc = new LinkedHashMap<>();
+        doc.put("file", file);
+        doc.put("magic", Integer.toUnsignedLong(cf.magic));
+        doc.put("minorVersion", cf.minor_version);
+        doc.put("majorVersion", cf.major_version);
+        doc.put("constantPool", constantPool());
+        doc.put("accessFlags", flags(cf.access_flags.flags, cf.access_flags.getClassFlags()));
+        doc.put("thisClass", cf.this_class);
+        doc.put("className", className(cf.this_class));
+        doc.put("superClass", cf.super_class);
+        doc.put("superClassName", cf.super_class == 0 ? null : className(cf.super_class));
+        doc.put("interfaces", indexes(cf.interfaces));
+
+        List<Object> fields = new ArrayList<>();
+        for (Field f: cf.fields) {
+            Map<String, Object> field = new LinkedHashMap<>();
+            field.put("accessFlags", flags(f.access_flags.flags, f.access_flags.getFieldFlags()));
+            field.put("nameIndex", f.name_index);
+            field.put("name", utf8(f.name_index));
+            field.put("descriptorIndex", f.descriptor.index);
+            field.put("descriptor", utf8(f.descriptor.index));
+            field.put("attributes", attributes(f.attributes));
+            fields.add(field);
+        }
+        doc.put("fields", fields);
+
+        List<Object> methods = new ArrayList<>();
+        for (Method m: cf.methods) {
+            Map<String, Object> method = new LinkedHashMap<>();
+            method.put("accessFlags", flags(m.access_flags.flags, m.access_flags.getMethodFlags()));
+            method.put("nameIndex", m.name_index);
+            method.put("name", utf8(m.name_index));
+            method.put("descriptorIndex", m.descriptor.index);
+            method.put("descriptor", utf8(m.descriptor.index));
+            method.put("attributes", attributes(m.attributes));
+            methods.add(method);
+        }
+        doc.put("methods", methods);
+        doc.put("attributes", attributes(cf.attributes));
+
+        if (classCount++ > 0)
+            println(",");
+        indent(+2);
+        writeValue(doc);
+        indent(-2);
+    }
+
+    /**
+     * Writes the document for a run, given the buffer to which the classes
+     * have been written by write.
+     * @param classes the buffer for the classes
+     * @param out the writer for the document
+     */
+    public void writeDocument(StringWriter classes, PrintWriter out) {
+        String tab = " ".repeat(options.indentWidth);
+        out.println("{");
+        out.println(tab + quote("schema") + ": " + quote(SCHEMA) + ",");
+        out.println(tab + quote("schemaVersion") + ": " + SCHEMA_VERSION + ",");
+        if (classCount == 0) {
+            out.println(tab + quote("classes") + ": []");
+        } else {
+            println();  // end the last class
+            out.println(tab + quote("classes") + ": [");
+            out.print(classes);
+            out.println(tab + "]");
+        }
+        out.println("}");
+        out.flush();
+    }
+
+    private List<Object> constantPool() {
+        List<Object> entries = new ArrayList<>();
+        for (int i = 1; i < constant_pool.size(); i++) {
+            CPInfo info;
+            try {
+                info = constant_pool.get(i);
+            } catch (ConstantPool.InvalidIndex e) {
+                continue;   // the second slot of a long or double
+            }
+            Map<String, Object> entry = new LinkedHashMap<>();
+            entry.put("index", i);
+            info.accept(constantPoolVisitor, entry);
+            entry.put("value", info.accept(constantValueVisitor, null));
+            entries.add(entry);
+        }
+        return entries;
+    }
+
+    private final ConstantPool.Visitor<Void, Map<String, Object>> constantPoolVisitor =
+            new ConstantPool.Visitor<>() {
+        public Void visitClass(ConstantPool.CONSTANT_Class_info info, Map<String, Object> entry) {
+            entry.put("tag", "Class");
+            entry.put("nameIndex", info.name_index);
+            return null;
+        }
+
+        public Void visitDouble(ConstantPool.CONSTANT_Double_info info, Map<String, Object> entry) {
+            entry.put("tag", "Double");
+            entry.put("number", number(info.value));
+            return null;
+        }
+
+        public Void visitFieldref(ConstantPool.CONSTANT_Fieldref_info info, Map<String, Object> entry) {
+            return visitRef("Fieldref", info, entry);
+        }
+
+        public Void visitFloat(ConstantPool.CONSTANT_Float_info info, Map<String, Object> entry) {
+            entry.put("tag", "Float");
+            entry.put("number", number(info.value));
+            return null;
+        }
+
+        public Void visitInteger(ConstantPool.CONSTANT_Integer_info info, Map<String, Object> entry) {
+            entry.put("tag", "Integer");
+            entry.put("number", info.value);
+            return null;
+        }
+
+        public Void visitInterfaceMethodref(ConstantPool.CONSTANT_InterfaceMethodref_info info,
+                                            Map<String, Object> entry) {
+            return visitRef("InterfaceMethodref", info, entry);
+        }
+
+        public Void visitInvokeDynamic(ConstantPool.CONSTANT_InvokeDynamic_info info, Map<String, Object> entry) {
+            entry.put("tag", "InvokeDynamic");
+            entry.put("bootstrapMethodAttrIndex", info.bootstrap_method_attr_index);
+            entry.put("nameAndTypeIndex", info.name_and_type_index);
+            return null;
+        }
+
+        public Void visitDynamicConstant(ConstantPool.CONSTANT_Dynamic_info info, Map<String, Object> entry) {
+            entry.put("tag", "Dynamic");
+            entry.put("bootstrapMethodAttrIndex", info.bootstrap_method_attr_index);
+            entry.put("nameAndTypeIndex", info.name_and_type_index);
+            return null;
+        }
+
+        public Void visitLong(ConstantPool.CONSTANT_Long_info info, Map<String, Object> entry) {
+            entry.put("tag", "Long");
+            entry.put("number", info.value);
+            return null;
+        }
+
+        public Void visitMethodref(ConstantPool.CONSTANT_Methodref_info info, Map<String, Object> entry) {
+            return visitRef("Methodref", info, entry);
+        }
+
+        public Void visitMethodHandle(ConstantPool.CONSTANT_MethodHandle_info info, Map<String, Object> entry) {
+            entry.put("tag", "MethodHandle");
+            entry.put("referenceKind", info.reference_kind.tag);
+            entry.put("referenceKindName", info.reference_kind.name());
+            entry.put("referenceIndex", info.reference_index);
+            return null;
+        }
+
+        public Void visitMethodType(ConstantPool.CONSTANT_MethodType_info info, Map<String, Object> entry) {
+            entry.put("tag", "MethodType");
+            entry.put("descriptorIndex", info.descriptor_index);
+            return null;
+        }
+
+        public Void visitModule(ConstantPool.CONSTANT_Module_info info, Map<String, Object> entry) {
+            entry.put("tag", "Module");
+            entry.put("nameIndex", info.name_index);
+            return null;
+        }
+
+        public Void visitNameAndType(ConstantPool.CONSTANT_NameAndType_info info, Map<String, Object> entry) {
+            entry.put("tag", "NameAndType");
+            entry.put("nameIndex", info.name_index);
+            entry.put("descriptorIndex", info.type_index);
+            return null;
+        }
+
+        public Void visitPackage(ConstantPool.CONSTANT_Package_info info, Map<String, Object> entry) {
+            entry.put("tag", "Package");
+            entry.put("nameIndex", info.name_index);
+            return null;
+        }
+
+        public Void visitString(ConstantPool.CONSTANT_String_info info, Map<String, Object> entry) {
+            entry.put("tag", "String");
+            entry.put("stringIndex", info.string_index);
+            return null;
+        }
+
+        public Void visitUtf8(ConstantPool.CONSTANT_Utf8_info info, Map<String, Object> entry) {
+            entry.put("tag", "Utf8");
+            entry.put("string", info.value);
+            return null;
+        }
+
+        private Void visitRef(String tag, CPRefInfo info, Map<String, Object> entry) {
+            entry.put("tag", tag);
+            entry.put("classIndex", info.class_index);
+            entry.put("nameAndTypeIndex", info.name_and_type_index);
+            return null;
+        }
+    };
+
+    /*
+     * Returns the value of an entry in the constant pool: the number for an
+     * Integer, Long, Float or Double, or else a string such as the string of
+     * a String or the name of a Class, with nothing quoted or escaped.
+     */
+    private final ConstantPool.Visitor<Object, Void> constantValueVisitor = new ConstantPool.Visitor<>() {
+        public Object visitClass(ConstantPool.CONSTANT_Class_info info, Void p) {
+            try {
+                return info.getName();
+            } catch (ConstantPoolException e) {
+                report(e);
+                return null;
+            }
+        }
+
+        public Object visitDouble(ConstantPool.CONSTANT_Double_info info, Void p) {
+            return number(info.value);
+        }
+
+        public Object visitFieldref(ConstantPool.CONSTANT_Fieldref_info info, Void p) {
+            return visitRef(info);
+        }
+
+        public Object visitFloat(ConstantPool.CONSTANT_Float_info info, Void p) {
+            return number(info.value);
+        }
+
+        public Object visitInteger(ConstantPool.CONSTANT_Integer_info info, Void p) {
+            return info.value;
+        }
+
+        public Object visitInterfaceMethodref(ConstantPool.CONSTANT_InterfaceMethodref_info info, Void p) {
+            return visitRef(info);
+        }
+
+        public Object visitInvokeDynamic(ConstantPool.CONSTANT_InvokeDynamic_info info, Void p) {
+            try {
+                return "#" + info.bootstrap_method_attr_index + ":" + visitNameAndType(info.getNameAndTypeInfo(), p);
+            } catch (ConstantPoolException e) {
+                report(e);
+                return null;
+            }
+        }
+
+        public Object visitDynamicConstant(ConstantPool.CONSTANT_Dynamic_info info, Void p) {
+            try {
+                return "#" + info.bootstrap_method_attr_index + ":" + visitNameAndType(info.getNameAndTypeInfo(), p);
+            } catch (ConstantPoolException e) {
+                report(e);
+                return null;
+            }
+        }
+
+        public Object visitLong(ConstantPool.CONSTANT_Long_info info, Void p) {
+            return info.value;
+        }
+
+        public Object visitMethodref(ConstantPool.CONSTANT_Methodref_info info, Void p) {
+            return visitRef(info);
+        }
+
+        public Object visitMethodHandle(ConstantPool.CONSTANT_MethodHandle_info info, Void p) {
+            try {
+                return info.reference_kind.name() + " " + visitRef(info.getCPRefInfo());
+            } catch (ConstantPoolException e) {
+                report(e);
+                return null;
+            }
+        }
+
+        public Object visitMethodType(ConstantPool.CONSTANT_MethodType_info info, Void p) {
+            try {
+                return info.getType();
+            } catch (ConstantPoolException e) {
+                report(e);
+                return null;
+            }
+        }
+
+        public Object visitModule(ConstantPool.CONSTANT_Module_info info, Void p) {
+            try {
+                return info.getName();
+            } catch (ConstantPoolException e) {
+                report(e);
+                return null;
+            }
+        }
+
+        public Object visitNameAndType(ConstantPool.CONSTANT_NameAndType_info info, Void p) {
+            try {
+                return info.getName() + ":" + info.getType();
+            } catch (ConstantPoolException e) {
+                report(e);
+                return null;
+            }
+        }
+
+        public Object visitPackage(ConstantPool.CONSTANT_Package_info info, Void p) {
+            try {
+                return info.getName();
+            } catch (ConstantPoolException e) {
+                report(e);
+                return null;
+            }
+        }
+
+        public Object visitString(ConstantPool.CONSTANT_String_info info, Void p) {
+            try {
+                return info.getString();
+            } catch (ConstantPoolException e) {
+                report(e);
+                return null;
+            }
+        }
+
+        public Object visitUtf8(ConstantPool.CONSTANT_Utf8_info info, Void p) {
+            return info.value;
+        }
+
+        private Object visitRef(CPRefInfo info) {
+            try {
+                return info.getClassName() + "." + visitNameAndType(info.getNameAndTypeInfo(), null);
+            } catch (ConstantPoolException e) {
+                report(e);
+                return null;
+            }
+        }
+    };
+
+    private List<Object> attributes(Attributes attrs) {
+        List<Object> list = new ArrayList<>();
+        for (Attribute attr: attrs) {
+            Map<String, Object> a = new LinkedHashMap<>();
+            a.put("name", utf8(attr.attribute_name_index));
+            a.put("nameIndex", attr.attribute_name_index);
+            a.put("length", attr.attribute_length);
+            attr.accept(attributeVisitor, a);
+            list.add(a);
+        }
+        return list;
+    }
+
+    private final Attribute.Visitor<Void, Map<String, Object>> attributeVisitor = new Attribute.Visitor<>() {
+        public Void visitBootstrapMethods(BootstrapMethods_attribute attr, Map<String, Object> a) {
+            List<Object> methods = new ArrayList<>();
+            for (BootstrapMethods_attribute.BootstrapMethodSpecifier bsm: attr.bootstrap_method_specifiers) {
+                Map<String, Object> m = new LinkedHashMap<>();
+                m.put("bootstrapMethodRef", bsm.bootstrap_method_ref);
+                m.put("bootstrapArguments", indexes(bsm.bootstrap_arguments));
+                methods.add(m);
+            }
+            a.put("bootstrapMethods", methods);
+            return null;
+        }
+
+        public Void visitDefault(DefaultAttribute attr, Map<String, Object> a) {
+            a.put("info", hex(attr.info));
+            if (attr.reason != null)
+                a.put("reason", attr.reason);
+            return null;
+        }
+
+        public Void visitAnnotationDefault(AnnotationDefault_attribute attr, Map<String, Object> a) {
+            a.put("defaultValue", elementValue(attr.default_value));
+            return null;
+        }
+
+        public Void visitCharacterRangeTable(CharacterRangeTable_attribute attr, Map<String, Object> a) {
+            List<Object> entries = new ArrayList<>();
+            for (CharacterRangeTable_attribute.Entry e: attr.character_range_table) {
+                Map<String, Object> entry = new LinkedHashMap<>();
+                entry.put("startPc", e.start_pc);
+                entry.put("endPc", e.end_pc);
+                entry.put("characterRangeStart", e.character_range_start);
+                entry.put("characterRangeEnd", e.character_range_end);
+                entry.put("flags", e.flags);
+                entries.add(entry);
+            }
+            a.put("characterRangeTable", entries);
+            return null;
+        }
+
+        public Void visitCode(Code_attribute attr, Map<String, Object> a) {
+            a.put("maxStack", attr.max_stack);
+            a.put("maxLocals", attr.max_locals);
+            a.put("codeLength", attr.code_length);
+            List<Object> instructions = new ArrayList<>();
+            for (Instruction instr: attr.getInstructions()) {
+                Map<String, Object> i = new LinkedHashMap<>();
+                i.put("pc", instr.getPC());
+                i.put("opcode", instr.getMnemonic());
+                instr.accept(instructionVisitor, i);
+                instructions.add(i);
+            }
+            a.put("instructions", instructions);
+            List<Object> handlers = new ArrayList<>();
+            for (Code_attribute.Exception_data e: attr.exception_table) {
+                Map<String, Object> handler = new LinkedHashMap<>();
+                handler.put("startPc", e.start_pc);
+                handler.put("endPc", e.end_pc);
+                handler.put("handlerPc", e.handler_pc);
+                handler.put("catchType", e.catch_type);
+                handlers.add(handler);
+            }
+            a.put("exceptionTable", handlers);
+            a.put("attributes", attributes(attr.attributes));
+            return null;
+        }
+
+        public Void visitCompilationID(CompilationID_attribute attr, Map<String, Object> a) {
+            a.put("compilationIdIndex", attr.compilationID_index);
+            return null;
+        }
+
+        public Void visitConstantValue(ConstantValue_attribute attr, Map<String, Object> a) {
+            a.put("constantValueIndex", attr.constantvalue_index);
+            return null;
+        }
+
+        public Void visitDeprecated(Deprecated_attribute attr, Map<String, Object> a) {
+            return null;
+        }
+
+        public Void visitEnclosingMethod(EnclosingMethod_attribute attr, Map<String, Object> a) {
+            a.put("classIndex", attr.class_index);
+            a.put("methodIndex", attr.method_index);
+            return null;
+        }
+
+        public Void visitExceptions(Exceptions_attribute attr, Map<String, Object> a) {
+            a.put("exceptionIndexTable", indexes(attr.exception_index_table));
+            return null;
+        }
+
+        public Void visitInnerClasses(InnerClasses_attribute attr, Map<String, Object> a) {
+            List<Object> classes = new ArrayList<>();
+            for (InnerClasses_attribute.Info info: attr.classes) {
+                Map<String, Object> c = new LinkedHashMap<>();
+                c.put("innerClassInfoIndex", info.inner_class_info_index);
+                c.put("outerClassInfoIndex", info.outer_class_info_index);
+                c.put("innerNameIndex", info.inner_name_index);
+                AccessFlags flags = info.inner_class_access_flags;
+                c.put("innerClassAccessFlags", flags(flags.flags, flags.getInnerClassFlags()));
+                classes.add(c);
+            }
+            a.put("classes", classes);
+            return null;
+        }
+
+        public Void visitLineNumberTable(LineNumberTable_attribute attr, Map<String, Object> a) {
+            List<Object> entries = new ArrayList<>();
+            for (LineNumberTable_attribute.Entry e: attr.line_number_table) {
+                Map<String, Object> entry = new LinkedHashMap<>();
+                entry.put("startPc", e.start_pc);
+                entry.put("lineNumber", e.line_number);
+                entries.add(entry);
+            }
+            a.put("lineNumberTable", entries);
+            return null;
+        }
+
+        public Void visitLocalVariableTable(LocalVariableTable_attribute attr, Map<String, Object> a) {
+            List<Object> entries = new ArrayList<>();
+            for (LocalVariableTable_attribute.Entry e: attr.local_variable_table) {
+                Map<String, Object> entry = new LinkedHashMap<>();
+                entry.put("startPc", e.start_pc);
+                entry.put("length", e.length);
+                entry.put("nameIndex", e.name_index);
+                entry.put("descriptorIndex", e.descriptor_index);
+                entry.put("index", e.index);
+                entries.add(entry);
+            }
+            a.put("localVariableTable", entries);
+            return null;
+        }
+
+        public Void visitLocalVariableTypeTable(LocalVariableTypeTable_attribute attr, Map<String, Object> a) {
+            List<Object> entries = new ArrayList<>();
+            for (LocalVariableTypeTable_attribute.Entry e: attr.local_variable_table) {
+                Map<String, Object> entry = new LinkedHashMap<>();
+                entry.put("startPc", e.start_pc);
+                entry.put("length", e.length);
+                entry.put("nameIndex", e.name_index);
+                entry.put("signatureIndex", e.signature_index);
+                entry.put("index", e.index);
+                entries.add(entry);
+            }
+            a.put("localVariableTypeTable", entries);
+            return null;
+        }
+
+        public Void visitMethodParameters(MethodParameters_attribute attr, Map<String, Object> a) {
+            List<Object> parameters = new ArrayList<>();
+            for (MethodParameters_attribute.Entry e: attr.method_parameter_table) {
+                Map<String, Object> p = new LinkedHashMap<>();
+                p.put("nameIndex", e.name_index);
+                List<String> names = new ArrayList<>();
+                if ((e.flags & AccessFlags.ACC_FINAL) != 0)
+                    names.add("ACC_FINAL");
+                if ((e.flags & AccessFlags.ACC_SYNTHETIC) != 0)
+                    names.add("ACC_SYNTHETIC");
+                if ((e.flags & AccessFlags.ACC_MANDATED) != 0)
+                    names.add("ACC_MANDATED");
+                p.put("accessFlags", flags(e.flags, names));
+                parameters.add(p);
+            }
+            a.put("parameters", parameters);
+            return null;
+        }
+
+        public Void visitModule(Module_attribute attr, Map<String, Object> a) {
+            a.put("moduleNameIndex", attr.module_name);
+            a.put("moduleFlags", attr.module_flags);
+            a.put("moduleVersionIndex", attr.module_version_index);
+            List<Object> requires = new ArrayList<>();
+            for (Module_attribute.RequiresEntry e: attr.requires) {
+                Map<String, Object> entry = new LinkedHashMap<>();
+                entry.put("requiresIndex", e.requires_index);
+                entry.put("requiresFlags", e.requires_flags);
+                entry.put("requiresVersionIndex", e.requires_version_index);
+                requires.add(entry);
+            }
+            a.put("requires", requires);
+            List<Object> exports = new ArrayList<>();
+            for (Module_attribute.ExportsEntry e: attr.exports) {
+                Map<String, Object> entry = new LinkedHashMap<>();
+                entry.put("exportsIndex", e.exports_index);
+                entry.put("exportsFlags", e.exports_flags);
+                entry.put("exportsToIndex", indexes(e.exports_to_index));
+                exports.add(entry);
+            }
+            a.put("exports", exports);
+            List<Object> opens = new ArrayList<>();
+            for (Module_attribute.OpensEntry e: attr.opens) {
+                Map<String, Object> entry = new LinkedHashMap<>();
+                entry.put("opensIndex", e.opens_index);
+                entry.put("opensFlags", e.opens_flags);
+                entry.put("opensToIndex", indexes(e.opens_to_index));
+                opens.add(entry);
+            }
+            a.put("opens", opens);
+            a.put("usesIndex", indexes(attr.uses_index));
+            List<Object> provides = new ArrayList<>();
+            for (Module_attribute.ProvidesEntry e: attr.provides) {
+                Map<String, Object> entry = new LinkedHashMap<>();
+                entry.put("providesIndex", e.provides_index);
+                entry.put("providesWithIndex", indexes(e.with_index));
+                provides.add(entry);
+            }
+            a.put("provides", provides);
+            return null;
+        }
+
+        public Void visitModuleHashes(ModuleHashes_attribute attr, Map<String, Object> a) {
+            a.put("algorithmIndex", attr.algorithm_index);
+            List<Object> hashes = new ArrayList<>();
+            for (ModuleHashes_attribute.Entry e: attr.hashes_table) {
+                Map<String, Object> entry = new LinkedHashMap<>();
+                entry.put("moduleNameIndex", e.module_name_index);
+                entry.put("hash", hex(e.hash));
+                hashes.add(entry);
+            }
+            a.put("hashes", hashes);
+            return null;
+        }
+
+        public Void visitModuleMainClass(ModuleMainClass_attribute attr, Map<String, Object> a) {
+            a.put("mainClassIndex", attr.main_class_index);
+            return null;
+        }
+
+        public Void visitModulePackages(ModulePackages_attribute attr, Map<String, Object> a) {
+            a.put("packageIndex", indexes(attr.packages_index));
+            return null;
+        }
+
+        public Void visitModuleResolution(ModuleResolution_attribute attr, Map<String, Object> a) {
+            a.put("resolutionFlags", attr.resolution_flags);
+            return null;
+        }
+
+        public Void visitModuleTarget(ModuleTarget_attribute attr, Map<String, Object> a) {
+            a.put("targetPlatformIndex", attr.target_platform_index);
+            return null;
+        }
+
+        public Void visitNestHost(NestHost_attribute attr, Map<String, Object> a) {
+            a.put("hostClassIndex", attr.top_index);
+            return null;
+        }
+
+        public Void visitNestMembers(NestMembers_attribute attr, Map<String, Object> a) {
+            a.put("classes", indexes(attr.members_indexes));
+            return null;
+        }
+
+        public Void visitRecord(Record_attribute attr, Map<String, Object> a) {
+            List<Object> components = new ArrayList<>();
+            for (Record_attribute.ComponentInfo c: attr.component_info_arr) {
+                Map<String, Object> component = new LinkedHashMap<>();
+                component.put("nameIndex", c.name_index);
+                component.put("descriptorIndex", c.descriptor.index);
+                component.put("attributes", attributes(c.attributes));
+                components.add(component);
+            }
+            a.put("components", components);
+            return null;
+        }
+
+        public Void visitRuntimeVisibleAnnotations(RuntimeVisibleAnnotations_attribute attr,
+                                                   Map<String, Object> a) {
+            a.put("annotations", annotations(attr.annotations));
+            return null;
+        }
+
+        public Void visitRuntimeInvisibleAnnotations(RuntimeInvisibleAnnotations_attribute attr,
+                                                     Map<String, Object> a) {
+            a.put("annotations", annotations(attr.annotations));
+            return null;
+        }
+
+        public Void visitRuntimeVisibleParameterAnnotations(RuntimeVisibleParameterAnnotations_attribute attr,
+                                                            Map<String, Object> a) {
+            a.put("parameterAnnotations", parameterAnnotations(attr.parameter_annotations));
+            return null;
+        }
+
+        public Void visitRuntimeInvisibleParameterAnnotations(RuntimeInvisibleParameterAnnotations_attribute attr,
+                                                              Map<String, Object> a) {
+            a.put("parameterAnnotations", parameterAnnotations(attr.parameter_annotations));
+            return null;
+        }
+
+        public Void visitRuntimeVisibleTypeAnnotations(RuntimeVisibleTypeAnnotations_attribute attr,
+                                                       Map<String, Object> a) {
+            a.put("annotations", typeAnnotations(attr.annotations));
+            return null;
+        }
+
+        public Void visitRuntimeInvisibleTypeAnnotations(RuntimeInvisibleTypeAnnotations_attribute attr,
+                                                         Map<String, Object> a) {
+            a.put("annotations", typeAnnotations(attr.annotations));
+            return null;
+        }
+
+        public Void visitPermittedSubclasses(PermittedSubclasses_attribute attr, Map<String, Object> a) {
+            a.put("classes", indexes(attr.subtypes));
+            return null;
+        }
+
+        public Void visitSignature(Signature_attribute attr, Map<String, Object> a) {
+            a.put("signatureIndex", attr.signature_index);
+            return null;
+        }
+
+        public Void visitSourceDebugExtension(SourceDebugExtension_attribute attr, Map<String, Object> a) {
+            a.put("debugExtension", attr.getValue());
+            return null;
+        }
+
+        public Void visitSourceFile(SourceFile_attribute attr, Map<String, Object> a) {
+            a.put("sourceFileIndex", attr.sourcefile_index);
+            return null;
+        }
+
+        public Void visitSourceID(SourceID_attribute attr, Map<String, Object> a) {
+            a.put("sourceIdIndex", attr.sourceID_index);
+            return null;
+        }
+
+        public Void visitStackMap(StackMap_attribute attr, Map<String, Object> a) {
+            List<Object> entries = new ArrayList<>();
+            for (StackMap_attribute.stack_map_frame frame: attr.entries) {
+                Map<String, Object> entry = new LinkedHashMap<>();
+                entry.put("offset", frame.offset_delta);
+                entry.put("locals", verificationTypes(frame.locals));
+                entry.put("stack", verificationTypes(frame.stack));
+                entries.add(entry);
+            }
+            a.put("entries", entries);
+            return null;
+        }
+
+        public Void visitStackMapTable(StackMapTable_attribute attr, Map<String, Object> a) {
+            List<Object> entries = new ArrayList<>();
+            for (stack_map_frame frame: attr.entries) {
+                Map<String, Object> entry = new LinkedHashMap<>();
+                entry.put("frameType", frame.frame_type);
+                frame.accept(frameVisitor, entry);
+                entries.add(entry);
+            }
+            a.put("entries", entries);
+            return null;
+        }
+
+        public Void visitSynthetic(Synthetic_attribute attr, Map<String, Object> a) {
+            return null;
+        }
+
+        public Void visitCustom(CustomAttribute attr, Map<String, Object> a) {
+            // the content of a custom attribute is known only to its printer, if any
+            Attribute.Printer printer = null;
+            try {
+                if (attributeFactory != null)
+                    printer = attributeFactory.getPrinter(attr.getName(constant_pool));
+            } catch (ConstantPoolException e) {
+                // no name, and so no printer
+            }
+            if (printer != null) {
+                StringWriter sw = new StringWriter();
+                try (PrintWriter pw = new PrintWriter(sw)) {
+                    printer.print(attr, constant_pool, pw);
+                } catch (ConstantPoolException e) {
+                    report(e);
+                }
+                a.put("text", sw.toString().replace("\r", ""));
+            }
+            return null;
+        }
+    };
+
+    private final stack_map_frame.Visitor<Void, Map<String, Object>> frameVisitor =
+            new stack_map_frame.Visitor<>() {
+        public Void visit_same_frame(StackMapTable_attribute.same_frame frame, Map<String, Object> entry) {
+            entry.put("kind", "same_frame");
+            entry.put("offsetDelta", frame.getOffsetDelta());
+            return null;
+        }
+
+        public Void visit_same_locals_1_stack_item_frame(
+                StackMapTable_attribute.same_locals_1_stack_item_frame frame, Map<String, Object> entry) {
+            entry.put("kind", "same_locals_1_stack_item_frame");
+            entry.put("offsetDelta", frame.getOffsetDelta());
+            entry.put("stack", verificationTypes(frame.stack));
+            return null;
+        }
+
+        public Void visit_same_locals_1_stack_item_frame_extended(
+                StackMapTable_attribute.same_locals_1_stack_item_frame_extended frame, Map<String, Object> entry) {
+            entry.put("kind", "same_locals_1_stack_item_frame_extended");
+            entry.put("offsetDelta", frame.getOffsetDelta());
+            entry.put("stack", verificationTypes(frame.stack));
+            return null;
+        }
+
+        public Void visit_chop_frame(StackMapTable_attribute.chop_frame frame, Map<String, Object> entry) {
+            entry.put("kind", "chop_frame");
+            entry.put("offsetDelta", frame.getOffsetDelta());
+            return null;
+        }
+
+        public Void visit_same_frame_extended(StackMapTable_attribute.same_frame_extended frame,
+                                              Map<String, Object> entry) {
+            entry.put("kind", "same_frame_extended");
+            entry.put("offsetDelta", frame.getOffsetDelta());
+            return null;
+        }
+
+        public Void visit_append_frame(StackMapTable_attribute.append_frame frame, Map<String, Object> entry) {
+            entry.put("kind", "append_frame");
+            entry.put("offsetDelta", frame.getOffsetDelta());
+            entry.put("locals", verificationTypes(frame.locals));
+            return null;
+        }
+
+        public Void visit_full_frame(StackMapTable_attribute.full_frame frame, Map<String, Object> entry) {
+            entry.put("kind", "full_frame");
+            entry.put("offsetDelta", frame.getOffsetDelta());
+            entry.put("locals", verificationTypes(frame.locals));
+            entry.put("stack", verificationTypes(frame.stack));
+            return null;
+        }
+    };
+
+    private List<Object> verificationTypes(verification_type_info[] types) {
+        List<Object> list = new ArrayList<>();
+        for (verification_type_info t: types) {
+            Map<String, Object> type = new LinkedHashMap<>();
+            switch (t.tag) {
+                case verification_type_info.ITEM_Top:
+                    type.put("tag", "Top");
+                    break;
+                case verification_type_info.ITEM_Integer:
+                    type.put("tag", "Integer");
+                    break;
+                case verification_type_info.ITEM_Float:
+                    type.put("tag", "Float");
+                    break;
+                case verification_type_info.ITEM_Long:
+                    type.put("tag", "Long");
+                    break;
+                case verification_type_info.ITEM_Double:
+                    type.put("tag", "Double");
+                    break;
+                case verification_type_info.ITEM_Null:
+                    type.put("tag", "Null");
+                    break;
+                case verification_type_info.ITEM_UninitializedThis:
+                    type.put("tag", "UninitializedThis");
+                    break;
+                case verification_type_info.ITEM_Object:
+                    type.put("tag", "Object");
+                    type.put("cpoolIndex", ((StackMapTable_attribute.Object_variable_info) t).cpool_index);
+                    break;
+                case verification_type_info.ITEM_Uninitialized:
+                    type.put("tag", "Uninitialized");
+                    type.put("offset", ((StackMapTable_attribute.Uninitialized_variable_info) t).offset);
+                    break;
+            }
+            list.add(type);
+        }
+        return list;
+    }
+
+    private final Instruction.KindVisitor<Void, Map<String, Object>> instructionVisitor =
+            new Instruction.KindVisitor<>() {
+        public Void visitNoOperands(Instruction instr, Map<String, Object> i) {
+            return null;
+        }
+
+        public Void visitArrayType(Instruction instr, Instruction.TypeKind kind, Map<String, Object> i) {
+            i.put("type", kind.name);
+            return null;
+        }
+
+        public Void visitBranch(Instruction instr, int offset, Map<String, Object> i) {
+            i.put("target", instr.getPC() + offset);
+            return null;
+        }
+
+        public Void visitConstantPoolRef(Instruction instr, int index, Map<String, Object> i) {
+            i.put("index", index);
+            return null;
+        }
+
+        public Void visitConstantPoolRefAndValue(Instruction instr, int index, int value, Map<String, Object> i) {
+            i.put("index", index);
+            i.put("value", value);
+            return null;
+        }
+
+        public Void visitLocal(Instruction instr, int index, Map<String, Object> i) {
+            i.put("local", index);
+            return null;
+        }
+
+        public Void visitLocalAndValue(Instruction instr, int index, int value, Map<String, Object> i) {
+            i.put("local", index);
+            i.put("value", value);
+            return null;
+        }
+
+        public Void visitLookupSwitch(Instruction instr, int default_, int npairs, int[] matches, int[] offsets,
+                                      Map<String, Object> i) {
+            int pc = instr.getPC();
+            i.put("default", pc + default_);
+            List<Object> pairs = new ArrayList<>();
+            for (int n = 0; n < npairs; n++) {
+                Map<String, Object> pair = new LinkedHashMap<>();
+                pair.put("match", matches[n]);
+                pair.put("target", pc + offsets[n]);
+                pairs.add(pair);
+            }
+            i.put("pairs", pairs);
+            return null;
+        }
+
+        public Void visitTableSwitch(Instruction instr, int default_, int low, int high, int[] offsets,
+                                     Map<String, Object> i) {
+            int pc = instr.getPC();
+            i.put("default", pc + default_);
+            i.put("low", low);
+            i.put("high", high);
+            List<Object> targets = new ArrayList<>();
+            for (int offset: offsets)
+                targets.add(pc + offset);
+            i.put("targets", targets);
+            return null;
+        }
+
+        public Void visitValue(Instruction instr, int value, Map<String, Object> i) {
+            i.put("value", value);
+            return null;
+        }
+
+        public Void visitUnknown(Instruction instr, Map<String, Object> i) {
+            return null;
+        }
+    };
+
+    private List<Object> annotations(Annotation[] annos) {
+        List<Object> list = new ArrayList<>();
+        for (Annotation anno: annos)
+            list.add(annotation(anno, new LinkedHashMap<>()));
+        return list;
+    }
+
+    private List<Object> parameterAnnotations(Annotation[][] annos) {
+        List<Object> list = new ArrayList<>();
+        for (Annotation[] a: annos)
+            list.add(annotations(a));
+        return list;
+    }
+
+    private Map<String, Object> annotation(Annotation anno, Map<String, Object> a) {
+        a.put("typeIndex", anno.type_index);
+        List<Object> pairs = new ArrayList<>();
+        for (Annotation.element_value_pair p: anno.element_value_pairs) {
+            Map<String, Object> pair = new LinkedHashMap<>();
+            pair.put("elementNameIndex", p.element_name_index);
+            pair.put("value", elementValue(p.value));
+            pairs.add(pair);
+        }
+        a.put("elementValuePairs", pairs);
+        return a;
+    }
+
+    private List<Object> typeAnnotations(TypeAnnotation[] annos) {
+        List<Object> list = new ArrayList<>();
+        for (TypeAnnotation anno: annos) {
+            Map<String, Object> a = new LinkedHashMap<>();
+            TypeAnnotation.Position pos = anno.position;
+            a.put("targetType", pos.type.name());
+            Map<String, Object> target = new LinkedHashMap<>();
+            if (pos.isValidOffset)
+                target.put("offset", pos.offset);
+            if (pos.lvarOffset != null) {
+                List<Object> table = new ArrayList<>();
+                for (int i = 0; i < pos.lvarOffset.length; i++) {
+                    Map<String, Object> entry = new LinkedHashMap<>();
+                    entry.put("startPc", pos.lvarOffset[i]);
+                    entry.put("length", pos.lvarLength[i]);
+                    entry.put("index", pos.lvarIndex[i]);
+                    table.add(entry);
+                }
+                target.put("localVariableTable", table);
+            }
+            if (pos.parameter_index != Integer.MIN_VALUE)
+                target.put("parameterIndex", pos.parameter_index);
+            if (pos.bound_index != Integer.MIN_VALUE)
+                target.put("boundIndex", pos.bound_index);
+            if (pos.type_index != Integer.MIN_VALUE)
+                target.put("typeIndex", pos.type_index);
+            if (pos.exception_index != Integer.MIN_VALUE)
+                target.put("exceptionTableIndex", pos.exception_index);
+            a.put("targetInfo", target);
+            List<Object> path = new ArrayList<>();
+            for (TypeAnnotation.Position.TypePathEntry e: pos.location) {
+                Map<String, Object> entry = new LinkedHashMap<>();
+                entry.put("kind", e.tag.name());
+                entry.put("argument", e.arg);
+                path.add(entry);
+            }
+            a.put("typePath", path);
+            list.add(annotation(anno.annotation, a));
+        }
+        return list;
+    }
+
+    private Map<String, Object> elementValue(Annotation.element_value value) {
+        return value.accept(elementValueVisitor, new LinkedHashMap<>());
+    }
+
+    private final Annotation.element_value.Visitor<Map<String, Object>, Map<String, Object>> elementValueVisitor =
+            new Annotation.element_value.Visitor<>() {
+        public Map<String, Object> visitPrimitive(Annotation.Primitive_element_value ev, Map<String, Object> v) {
+            v.put("tag", String.valueOf((char) ev.tag));
+            v.put("constValueIndex", ev.const_value_index);
+            return v;
+        }
+
+        public Map<String, Object> visitEnum(Annotation.Enum_element_value ev, Map<String, Object> v) {
+            v.put("tag", String.valueOf((char) ev.tag));
+            v.put("typeNameIndex", ev.type_name_index);
+            v.put("constNameIndex", ev.const_name_index);
+            return v;
+        }
+
+        public Map<String, Object> visitClass(Annotation.Class_element_value ev, Map<String, Object> v) {
+            v.put("tag", String.valueOf((char) ev.tag));
+            v.put("classInfoIndex", ev.class_info_index);
+            return v;
+        }
+
+        public Map<String, Object> visitAnnotation(Annotation.Annotation_element_value ev, Map<String, Object> v) {
+            v.put("tag", String.valueOf((char) ev.tag));
+            v.put("annotation", annotation(ev.annotation_value, new LinkedHashMap<>()));
+            return v;
+        }
+
+        public Map<String, Object> visitArray(Annotation.Array_element_value ev, Map<String, Object> v) {
+            v.put("tag", String.valueOf((char) ev.tag));
+            List<Object> values = new ArrayList<>();
+            for (Annotation.element_value e: ev.values)
+                values.add(elementValue(e));
+            v.put("values", values);
+            return v;
+        }
+    };
+
+    private static Map<String, Object> flags(int value, Collection<String> names) {
+        Map<String, Object> flags = new LinkedHashMap<>();
+        flags.put("value", value);
+        flags.put("flags", new ArrayList<>(names));
+        return flags;
+    }
+
+    private static List<Object> indexes(int[] values) {
+        List<Object> list = new ArrayList<>();
+        for (int v: values)
+            list.add(v);
+        return list;
+    }
+
+    private String utf8(int index) {
+        try {
+            return constant_pool.getUTF8Value(index);
+        } catch (ConstantPoolException e) {
+            report(e);
+            return null;
+        }
+    }
+
+    private String className(int index) {
+        try {
+            return constant_pool.getClassInfo(index).getName();
+        } catch (ConstantPoolException e) {
+            report(e);
+            return null;
+        }
+    }
+
+    /*
+     * Returns a floating point value as a number, or as a string for the
+     * values that have no representation as a JSON number.
+     */
+    private static Object number(double value) {
+        if (Double.isNaN(value) || Double.isInfinite(value))
+            return String.valueOf(value);
+        return value;
+    }
+
+    private static Object number(float value) {
+        if (Float.isNaN(value) || Float.isInfinite(value))
+            return String.valueOf(value);
+        return value;
+    }
+
+    private static String hex(byte[] bytes) {
+        StringBuilder sb = new StringBuilder();
+        for (byte b: bytes)
+            sb.append(String.format("%02x", b & 0xff));
+        return sb.toString();
+    }
+
+    /*
+     * Writes a value built from maps, lists, strings, numbers, booleans and null.
+     * An object or array whose members are all simple, in that they are neither
+     * objects nor arrays of anything other than simple values, is written on a
+     * single line; any other object or array is written with one member per line.
+     */
+    private void writeValue(Object value) {
+        if (isSimple(value)) {
+            print(toInlineString(value));
+            return;
+        }
+        boolean isMap = (value instanceof Map);
+        Collection<?> members = isMap ? ((Map<?, ?>) value).entrySet() : (Collection<?>) value;
+        println(isMap ? "{" : "[");
+        indent(+1);
+        for (Iterator<?> iter = members.iterator(); iter.hasNext(); ) {
+            Object member = iter.next();
+            if (isMap) {
+                Map.Entry<?, ?> e = (Map.Entry<?, ?>) member;
+                print(quote(e.getKey().toString()) + ": ");
+                writeValue(e.getValue());
+            } else {
+                writeValue(member);
+            }
+            if (iter.hasNext())
+                print(",");
+            println();
+        }
+        indent(-1);
+        print(isMap ? "}" : "]");
+    }
+
+    private static boolean isSimple(Object value) {
+        Collection<?> members;
+        if (value instanceof Map)
+            members = ((Map<?, ?>) value).values();
+        else if (value instanceof Collection)
+            members = (Collection<?>) value;
+        else
+            return true;
+        for (Object m: members) {
+            if (m instanceof Map)
+                return false;
+            if (m instanceof Collection) {
+                for (Object o: (Collection<?>) m) {
+                    if (o instanceof Map || o instanceof Collection)
+                        return false;
+                }
+            }
+        }
+        return true;
+    }
+
+    private static String toInlineString(Object value) {
+        StringBuilder sb = new StringBuilder();
+        if (value instanceof Map) {
+            sb.append("{");
+            String sep = "";
+            for (Map.Entry<?, ?> e: ((Map<?, ?>) value).entrySet()) {
+                sb.append(sep).append(quote(e.getKey().toString())).append(": ").append(toInlineString(e.getValue()));
+                sep = ", ";
+            }
+            sb.append("}");
+        } else if (value instanceof Collection) {
+            sb.append("[");
+            String sep = "";
+            for (Object o: (Collection<?>) value) {
+                sb.append(sep).append(toInlineString(o));
+                sep = ", ";
+            }
+            sb.append("]");
+        } else if (value instanceof String) {
+            sb.append(quote((String) value));
+        } else {
+            sb.append(value);   // number, boolean or null
+        }
+        return sb.toString();
+    }
+
+    /*
+     * Returns a string as a JSON string literal. Any character that is not
+     * printable ASCII is escaped, so that the output does not depend on the
+     * encoding of the console.
+     */
+    private static String quote(String s) {
+        StringBuilder sb = new StringBuilder("\"");
+        for (int i = 0; i < s.length(); i++) {
+            char c = s.charAt(i);
+            switch (c) {
+                case '"':  sb.append("\\\""); break;
+                case '\\': sb.append("\\\\"); break;
+                case '\b': sb.append("\\b"); break;
+                case '\f': sb.append("\\f"); break;
+                case '\n': sb.append("\\n"); break;
+                case '\r': sb.append("\\r"); break;
+                case '\t': sb.append("\\t"); break;
+                default:
+                    if (c < 0x20 || c > 0x7e)
+                        sb.append(String.format("\\u%04x", (int) c));
+                    else
+                        sb.append(c);
+            }
+        }
+        return sb.append('"').toString();
+    }
+
+    private final Attribute.Factory attributeFactory;
+    private final Options options;
+    private ConstantPool constant_pool;
+    private int classCount;
+}
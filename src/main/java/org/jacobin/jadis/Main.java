@@ -27,6 +27,7 @@ public class Main {
         PrintWriter out = new PrintWriter( System.out );
         JavapTask t = new JavapTask();
         t.setLog( out );
-        t.run( args );
+        int rc = t.run( args );
+        System.exit( rc );
     }
 }
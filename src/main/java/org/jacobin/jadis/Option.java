@@ -12,6 +12,13 @@
 
 package org.jacobin.jadis;
 
+import java.util.ArrayList;
+import java.util.EnumSet;
+import java.util.List;
+import java.util.Locale;
+
+import org.jacobin.jadis.classfile.AccessFlags;
+
 /*
  * Class for each individual option that javap allows
  * Extracted from JavapTask.java and lightly modified
@@ -45,6 +52,310 @@ public abstract class Option {
 
     abstract void process(JavapTask task, String opt, String arg) throws JavapTask.BadArgs;
 
+    /**
+     * parses the value of an option such as -XDindent:4, which must be a positive integer
+     * @param task the task whose messages are used to report an invalid value
+     * @param opt the option, including its value
+     * @return the value of the option
+     * @throws JavapTask.BadArgs if the value is missing, not a number, or not positive
+     */
+    static int positiveIntValue(JavapTask task, String opt) throws JavapTask.BadArgs {
+        int sep = opt.indexOf(":");
+        try {
+            int i = Integer.parseInt(opt.substring(sep + 1));
+            if (i > 0)
+                return i;
+        } catch (NumberFormatException e) {
+            // fall through and report the option
+        }
+        throw task.new BadArgs("err.invalid.arg.for.option", opt);
+    }
+
+    static final Option[] recognizedOptions = {
+
+        new Option(false, "-help", "--help", "-?", "-h") {
+            @Override
+            void process(JavapTask task, String opt, String arg) {
+                task.options.help = true;
+            }
+        },
+
+        new Option(false, "-version", "--version") {
+            @Override
+            void process(JavapTask task, String opt, String arg) {
+                task.options.version = true;
+            }
+        },
+
+        new Option(false, "-fullversion") {
+            @Override
+            void process(JavapTask task, String opt, String arg) {
+                task.options.fullVersion = true;
+            }
+        },
+
+        new Option(false, "-v", "-verbose", "-all") {
+            @Override
+            void process(JavapTask task, String opt, String arg) {
+                task.options.verbose = true;
+                task.options.showDescriptors = true;
+                task.options.showFlags = true;
+                task.options.showAllAttrs = true;
+            }
+        },
+
+        new Option(false, "-l") {
+            @Override
+            void process(JavapTask task, String opt, String arg) {
+                task.options.showLineAndLocalVariableTables = true;
+            }
+        },
+
+        new Option(false, "-public") {
+            @Override
+            void process(JavapTask task, String opt, String arg) {
+                task.options.accessOptions.add(opt);
+                task.options.showAccess = AccessFlags.ACC_PUBLIC;
+            }
+        },
+
+        new Option(false, "-protected") {
+            @Override
+            void process(JavapTask task, String opt, String arg) {
+                task.options.accessOptions.add(opt);
+                task.options.showAccess = AccessFlags.ACC_PROTECTED;
+            }
+        },
+
+        new Option(false, "-package") {
+            @Override
+            void process(JavapTask task, String opt, String arg) {
+                task.options.accessOptions.add(opt);
+                task.options.showAccess = 0;
+            }
+        },
+
+        new Option(false, "-p", "-private") {
+            @Override
+            void process(JavapTask task, String opt, String arg) {
+                if (!task.options.accessOptions.contains("-p") &&
+                        !task.options.accessOptions.contains("-private")) {
+                    task.options.accessOptions.add(opt);
+                }
+                task.options.showAccess = AccessFlags.ACC_PRIVATE;
+            }
+        },
+
+        new Option(false, "-c") {
+            @Override
+            void process(JavapTask task, String opt, String arg) {
+                task.options.showDisassembled = true;
+            }
+        },
+
+        new Option(false, "-s") {
+            @Override
+            void process(JavapTask task, String opt, String arg) {
+                task.options.showDescriptors = true;
+            }
+        },
+
+        new Option(false, "-sysinfo") {
+            @Override
+            boolean matches(String opt) {
+                return super.matches(opt) || opt.startsWith("-sysinfo:");
+            }
+
+            @Override
+            void process(JavapTask task, String opt, String arg) throws JavapTask.BadArgs {
+                task.options.sysInfo = true;
+                int sep = opt.indexOf(":");
+                if (sep == -1)
+                    return;
+                List<String> algorithms = new ArrayList<>(List.of("SHA-256"));
+                for (String v: opt.substring(sep + 1).split("[,: ]+")) {
+                    String algorithm = digestAlgorithm(v);
+                    if (algorithm == null)
+                        throw task.new BadArgs("err.invalid.hash.algorithm", v, "SHA-256, SHA-1, MD5");
+                    if (!algorithms.contains(algorithm))
+                        algorithms.add(algorithm);
+                }
+                task.options.digestAlgorithms = algorithms;
+            }
+
+            String digestAlgorithm(String arg) {
+                switch (arg.toLowerCase(Locale.ROOT)) {
+                    case "sha-256": case "sha256":
+                        return "SHA-256";
+                    case "sha-1": case "sha1":
+                        return "SHA-1";
+                    case "md5":
+                        return "MD5";
+                    default:
+                        return null;
+                }
+            }
+        },
+
+        new Option(false, "-XDdetails") {
+            @Override
+            void process(JavapTask task, String opt, String arg) {
+                task.options.details = EnumSet.allOf(InstructionDetailWriter.Kind.class);
+            }
 
+        },
+
+        new Option(false, "-XDdetails:") {
+            @Override
+            boolean matches(String opt) {
+                int sep = opt.indexOf(":");
+                return sep != -1 && super.matches(opt.substring(0, sep + 1));
+            }
+
+            @Override
+            void process(JavapTask task, String opt, String arg) throws JavapTask.BadArgs {
+                int sep = opt.indexOf(":");
+                for (String v: opt.substring(sep + 1).split("[,: ]+")) {
+                    if (!handleArg(task, v))
+                        throw task.new BadArgs("err.invalid.arg.for.option", v);
+                }
+            }
+
+            boolean handleArg(JavapTask task, String arg) {
+                if (arg.length() == 0)
+                    return true;
+
+                if (arg.equals("all")) {
+                    task.options.details = EnumSet.allOf(InstructionDetailWriter.Kind.class);
+                    return true;
+                }
+
+                boolean on = true;
+                if (arg.startsWith("-")) {
+                    on = false;
+                    arg = arg.substring(1);
+                }
+
+                for (InstructionDetailWriter.Kind k: InstructionDetailWriter.Kind.values()) {
+                    if (arg.equalsIgnoreCase(k.option)) {
+                        if (on)
+                            task.options.details.add(k);
+                        else
+                            task.options.details.remove(k);
+                        return true;
+                    }
+                }
+                return false;
+            }
+        },
+
+        new Option(false, "-constants") {
+            @Override
+            void process(JavapTask task, String opt, String arg) {
+                task.options.showConstants = true;
+            }
+        },
+
+        new Option(false, "-XDinner") {
+            @Override
+            void process(JavapTask task, String opt, String arg) {
+                task.options.showInnerClasses = true;
+            }
+        },
+
+        new Option(false, "-XDindent:") {
+            @Override
+            boolean matches(String opt) {
+                int sep = opt.indexOf(":");
+                return sep != -1 && super.matches(opt.substring(0, sep + 1));
+            }
+
+            @Override
+            void process(JavapTask task, String opt, String arg) throws JavapTask.BadArgs {
+                task.options.indentWidth = positiveIntValue(task, opt);
+            }
+        },
+
+        new Option(false, "-XDtab:") {
+            @Override
+            boolean matches(String opt) {
+                int sep = opt.indexOf(":");
+                return sep != -1 && super.matches(opt.substring(0, sep + 1));
+            }
+
+            @Override
+            void process(JavapTask task, String opt, String arg) throws JavapTask.BadArgs {
+                task.options.tabColumn = positiveIntValue(task, opt);
+            }
+        },
+
+        new Option(true, "--format") {
+            @Override
+            void process(JavapTask task, String opt, String arg) throws JavapTask.BadArgs {
+                for (Options.Format f: Options.Format.values()) {
+                    if (f.name().equalsIgnoreCase(arg)) {
+                        task.options.format = f;
+                        return;
+                    }
+                }
+                throw task.new BadArgs("err.invalid.arg.for.option", opt + " " + arg);
+            }
+        },
+
+        new Option(true, "--cfg") {
+            @Override
+            void process(JavapTask task, String opt, String arg) throws JavapTask.BadArgs {
+                for (Options.CfgFormat f: Options.CfgFormat.values()) {
+                    if (f.name().equalsIgnoreCase(arg)) {
+                        task.options.cfg = f;
+                        return;
+                    }
+                }
+                throw task.new BadArgs("err.invalid.arg.for.option", opt + " " + arg);
+            }
+        },
+
+        new Option(true, "--cfg-method") {
+            @Override
+            void process(JavapTask task, String opt, String arg) {
+                task.options.cfgMethod = arg;
+            }
+        },
+
+        new Option(false, "--verify") {
+            @Override
+            void process(JavapTask task, String opt, String arg) {
+                task.options.verify = true;
+            }
+        },
+
+        new Option(true, "--module", "-m") {
+            @Override
+            void process(JavapTask task, String opt, String arg) {
+                task.options.moduleName = arg;
+            }
+        },
+
+        new Option(false, "--allow-remote-urls") {
+            @Override
+            void process(JavapTask task, String opt, String arg) {
+                task.options.allowRemoteURLs = true;
+            }
+        },
+
+        // this option is processed by the launcher, and cannot be used when invoked via
+        // an API like ToolProvider. It exists here to be documented in the command-line help.
+        new Option(false, "-J") {
+            @Override
+            boolean matches(String opt) {
+                return opt.startsWith("-J");
+            }
+
+            @Override
+            void process(JavapTask task, String opt, String arg) {
+                // no-op
+            }
+        }
+    };
 }
 
@@ -27,6 +27,7 @@ package org.jacobin.jadis;
 
 import java.util.EnumSet;
 import java.util.HashSet;
+import java.util.List;
 import java.util.Set;
 
 import org.jacobin.jadis.classfile.AccessFlags;
@@ -81,14 +82,38 @@ public class Options {
     public boolean showLineAndLocalVariableTables;
     public int showAccess;
     public Set<String> accessOptions = new HashSet<>();
-//    public Set<InstructionDetailWriter.Kind> details = EnumSet.noneOf(InstructionDetailWriter.Kind.class); //by ALB
+    public Set<InstructionDetailWriter.Kind> details = EnumSet.noneOf(InstructionDetailWriter.Kind.class);
     public boolean showDisassembled;
     public boolean showDescriptors;
     public boolean showAllAttrs;
     public boolean showConstants;
     public boolean sysInfo;
+    public List<String> digestAlgorithms = List.of("SHA-256");   // for -sysinfo and -verbose
     public boolean showInnerClasses;
     public int indentWidth = 2;   // #spaces per indentWidth level; must be > 0
     public int tabColumn = 40;    // column number for comments; must be > 0
     public String moduleName;
+    public boolean allowRemoteURLs;   // allow classes to be read from http:, https: and other URLs
+    public Format format = Format.TEXT;
+    public CfgFormat cfg;             // write control-flow graphs instead, as given by --cfg
+    public String cfgMethod;          // the glob pattern for the methods for which to write graphs
+    public boolean verify;            // verify the code of each method instead, as given by --verify
+
+    /**
+     * The formats in which classes may be written, as given by --format.
+     */
+    public enum Format {
+        TEXT,
+        JSON,
+        JASMIN,
+        KRAKATAU,
+        HTML
+    }
+
+    /**
+     * The formats in which control-flow graphs may be written, as given by --cfg.
+     */
+    public enum CfgFormat {
+        DOT
+    }
 }
@@ -0,0 +1,1307 @@
+/*
+ * Copyright (c) 2021 by Andrew Binstock.
+ *
+ * Portions of this file are copyright Oracle Corp.
+ * Those portions are licensed under GPL v. 2.0
+ * with the Oracle classpath exception. Due to the
+ * requirements of that license, the portions that
+ * are copyrighted by Andrew Binstock are licensed
+ * using the same terms and requirements.
+ *
+ */
+
+package org.jacobin.jadis;
+
+import java.util.ArrayList;
+import java.util.Arrays;
+import java.util.Collections;
+import java.util.List;
+import java.util.Map;
+import java.util.StringJoiner;
+import java.util.TreeMap;
+
+import org.jacobin.jadis.asm.ClassHierarchy;
+import org.jacobin.jadis.asm.VType;
+import org.jacobin.jadis.classfile.Attribute;
+import org.jacobin.jadis.classfile.ClassFile;
+import org.jacobin.jadis.classfile.Code_attribute;
+import org.jacobin.jadis.classfile.ConstantPool;
+import org.jacobin.jadis.classfile.ConstantPool.CONSTANT_Dynamic_info;
+import org.jacobin.jadis.classfile.ConstantPool.CONSTANT_InvokeDynamic_info;
+import org.jacobin.jadis.classfile.ConstantPool.CPInfo;
+import org.jacobin.jadis.classfile.ConstantPool.CPRefInfo;
+import org.jacobin.jadis.classfile.ConstantPoolException;
+import org.jacobin.jadis.classfile.DefaultAttribute;
+import org.jacobin.jadis.classfile.Instruction;
+import org.jacobin.jadis.classfile.Method;
+import org.jacobin.jadis.classfile.Opcode;
+import org.jacobin.jadis.classfile.StackMapTable_attribute;
+
+import static org.jacobin.jadis.asm.VType.*;
+import static org.jacobin.jadis.classfile.AccessFlags.ACC_STATIC;
+import static org.jacobin.jadis.classfile.ConstantPool.*;
+import static org.jacobin.jadis.classfile.StackMapTable_attribute.verification_type_info.*;
+
+/*
+ *  Verifies the code of each method of a class by type checking, as the JVM
+ *  does for class files of version 50 and later (JVMS 4.10.1), for --verify.
+ *
+ *  The instructions are checked in order. The types of the locals and of the
+ *  operand stack before an instruction are given by the frame of the
+ *  StackMapTable attribute at its offset, if there is one, and otherwise by
+ *  the instruction before it, which must go on to it. Each instruction must
+ *  find operands of the right types, and the types it leaves must be
+ *  assignable to those of the frame at the next instruction, if there is one,
+ *  at each target of a branch or switch, and at the handler of each exception
+ *  table entry whose range includes it.
+ *
+ *  As for the JVM, interfaces are treated as java/lang/Object, and so any class
+ *  may be assigned to one. The checks on access to protected members are not
+ *  made. Only the first error in each method is reported, since the types
+ *  after it cannot be known. A method whose Code or StackMapTable attribute
+ *  cannot be decoded cannot be checked, and that is reported as its error.
+ *
+ *  <p><b>This is NOT part of any supported API.
+ *  If you write code that depends on this, you do so at your own risk.
+ *  This code and its internal interfaces are subject to change or
+ *  deletion without notice.</b>
+ */
+public class Verifier {
+    /**
+     * An error found in the code of a method.
+     */
+    public static class Problem {
+        Problem(String method, int offset, Instruction instruction, String key, Object[] args,
+                String expected, String actual) {
+            this.method = method;
+            this.offset = offset;
+            this.instruction = instruction;
+            this.key = key;
+            this.args = args;
+            this.expected = expected;
+            this.actual = actual;
+        }
+
+        /** The class, name and descriptor of the method. */
+        public final String method;
+        /** The offset at which the error was found, or -1 if it is in the method as a whole. */
+        public final int offset;
+        /** The instruction at or before the offset, or null if the offset is -1. */
+        public final Instruction instruction;
+        /** The key of the message that describes the error, and its arguments. */
+        public final String key;
+        public final Object[] args;
+        /** The frame that was expected, or null if the error is not a mismatch of frames. */
+        public final String expected;
+        /** The frame that was found, or null if there is none. */
+        public final String actual;
+    }
+
+    public Verifier(ClassHierarchy hierarchy) {
+        this.hierarchy = hierarchy;
+    }
+
+    /**
+     * Verifies the methods of a class that have code. The class is added to
+     * the class hierarchy, for the classes verified after it.
+     * @param cf the class file
+     * @return the first error found in each method that has one
+     * @throws ConstantPoolException if the names of the class or its methods cannot be found
+     */
+    public List<Problem> verify(ClassFile cf) throws ConstantPoolException {
+        cp = cf.constant_pool;
+        thisClass = cf.getName();
+        superClass = (cf.super_class == 0) ? null : cf.getSuperclassName();
+        hierarchy.add(thisClass, superClass, cf.isInterface());
+        List<Problem> problems = new ArrayList<>();
+        for (Method m: cf.methods) {
+            Attribute a = m.attributes.get(Attribute.Code);
+            if (a == null)
+                continue;
+            String name = m.getName(cp);
+            String descriptor = m.descriptor.getValue(cp);
+            String method = thisClass + "." + name + descriptor;
+            Problem p;
+            if (a instanceof Code_attribute)
+                p = verify(method, m.access_flags.is(ACC_STATIC), name, descriptor, (Code_attribute) a);
+            else
+                p = new Problem(method, -1, null, "verify.bad.code", new Object[] { reason(a) }, null, null);
+            if (p != null)
+                problems.add(p);
+        }
+        return problems;
+    }
+
+    private Problem verify(String method, boolean isStatic, String name, String descriptor,
+            Code_attribute code) {
+        // without its frames, the method would be checked as if it had none
+        Attribute stackMap = code.attributes.get(Attribute.StackMapTable);
+        if (stackMap != null && !(stackMap instanceof StackMapTable_attribute))
+            return new Problem(method, -1, null, "verify.bad.stackmap", new Object[] { reason(stackMap) }, null, null);
+
+        this.code = code;
+        this.descriptor = descriptor;
+        isConstructor = name.equals("<init>");
+        instructions = new TreeMap<>();
+        try {
+            for (Instruction instr: code.getInstructions())
+                instructions.put(instr.getPC(), instr);
+        } catch (ArrayIndexOutOfBoundsException e) {
+            // an incomplete instruction, as reported by CodeWriter; the code ends before it
+        }
+        if (instructions.isEmpty())
+            return null;
+
+        current = null;
+        pc = 0;
+        try {
+            Frame incoming = initialFrame(isStatic, descriptor);
+            frames = stackMapFrames();
+            for (Instruction instr: instructions.values()) {
+                pc = instr.getPC();
+                Frame map = frames.get(pc);
+                if (map != null) {
+                    if (incoming != null && !isAssignable(incoming, map))
+                        throw new VerifyException("verify.frame.mismatch").frames(map, incoming);
+                    incoming = map;
+                }
+                current = incoming;
+                if (incoming == null)
+                    throw new VerifyException("verify.no.frame");
+                checkHandlers(incoming);
+                frame = incoming.copy();
+                execute(instr);
+                if (ControlFlowGraph.endsFlow(instr.getOpcode())) {
+                    incoming = null;
+                } else {
+                    checkHandlers(frame);
+                    incoming = frame;
+                }
+            }
+            if (incoming != null)
+                throw new VerifyException("verify.falls.off");
+            return null;
+        } catch (VerifyException e) {
+            int offset = (e.offset >= 0) ? e.offset : pc;
+            Map.Entry<Integer, Instruction> entry = instructions.floorEntry(offset);
+            Instruction instr = (entry != null ? entry : instructions.firstEntry()).getValue();
+            return new Problem(method, offset, instr, e.key, e.args,
+                    (e.expected == null) ? null : e.expected.toString(),
+                    (e.actual != null) ? e.actual.toString() : (current == null) ? null : current.toString());
+        }
+    }
+
+    /*
+     * Returns why an attribute was not decoded as the standard attribute of its name.
+     */
+    private static String reason(Attribute a) {
+        if (a instanceof DefaultAttribute && ((DefaultAttribute) a).reason != null)
+            return ((DefaultAttribute) a).reason;
+        return a.getClass().getName();
+    }
+
+    /*
+     * Returns the frame at the start of a method, given by its descriptor.
+     */
+    private Frame initialFrame(boolean isStatic, String descriptor) throws VerifyException {
+        initialLocals = new ArrayList<>();
+        if (!isStatic)
+            initialLocals.add(isConstructor && !thisClass.equals(OBJECT) ? UNINITIALIZED_THIS : object(thisClass));
+        initialLocals.addAll(argumentTypes(descriptor));
+        return frame(initialLocals, Collections.emptyList());
+    }
+
+    /*
+     * Returns the frames of the StackMapTable attribute, if any, by offset.
+     */
+    private TreeMap<Integer, Frame> stackMapFrames() throws VerifyException {
+        TreeMap<Integer, Frame> map = new TreeMap<>();
+        Attribute a = code.attributes.get(Attribute.StackMapTable);
+        if (!(a instanceof StackMapTable_attribute))
+            return map;
+        List<VType> locals = new ArrayList<>(initialLocals);
+        int offset = -1;
+        for (StackMapTable_attribute.stack_map_frame f: ((StackMapTable_attribute) a).entries) {
+            offset += f.getOffsetDelta() + 1;
+            List<VType> stack = Collections.emptyList();
+            if (f instanceof StackMapTable_attribute.same_locals_1_stack_item_frame) {
+                stack = types(((StackMapTable_attribute.same_locals_1_stack_item_frame) f).stack);
+            } else if (f instanceof StackMapTable_attribute.same_locals_1_stack_item_frame_extended) {
+                stack = types(((StackMapTable_attribute.same_locals_1_stack_item_frame_extended) f).stack);
+            } else if (f instanceof StackMapTable_attribute.chop_frame) {
+                int k = 251 - f.frame_type;
+                if (k > locals.size())
+                    throw new VerifyException("verify.bad.chop", offset, k).at(offset);
+                locals.subList(locals.size() - k, locals.size()).clear();
+            } else if (f instanceof StackMapTable_attribute.append_frame) {
+                locals.addAll(types(((StackMapTable_attribute.append_frame) f).locals));
+            } else if (f instanceof StackMapTable_attribute.full_frame) {
+                locals = types(((StackMapTable_attribute.full_frame) f).locals);
+                stack = types(((StackMapTable_attribute.full_frame) f).stack);
+            }
+            if (!instructions.containsKey(offset))
+                throw new VerifyException("verify.bad.frame.offset", offset).at(offset);
+            try {
+                map.put(offset, frame(locals, stack));
+            } catch (VerifyException e) {
+                throw e.at(offset);
+            }
+        }
+        return map;
+    }
+
+    private List<VType> types(StackMapTable_attribute.verification_type_info[] infos) throws VerifyException {
+        List<VType> types = new ArrayList<>();
+        for (StackMapTable_attribute.verification_type_info info: infos) {
+            switch (info.tag) {
+                case ITEM_Top:
+                    types.add(TOP);
+                    break;
+                case ITEM_Integer:
+                    types.add(INTEGER);
+                    break;
+                case ITEM_Float:
+                    types.add(FLOAT);
+                    break;
+                case ITEM_Long:
+                    types.add(LONG);
+                    break;
+                case ITEM_Double:
+                    types.add(DOUBLE);
+                    break;
+                case ITEM_Null:
+                    types.add(NULL);
+                    break;
+                case ITEM_UninitializedThis:
+                    types.add(UNINITIALIZED_THIS);
+                    break;
+                case ITEM_Object:
+                    types.add(object(className(((StackMapTable_attribute.Object_variable_info) info).cpool_index)));
+                    break;
+                case ITEM_Uninitialized:
+                    types.add(uninitialized(((StackMapTable_attribute.Uninitialized_variable_info) info).offset));
+                    break;
+            }
+        }
+        return types;
+    }
+
+    /*
+     * Returns a frame for the types of the locals and of the stack, in which
+     * a long or double takes one entry, as in a stack map frame.
+     */
+    private Frame frame(List<VType> locals, List<VType> stack) throws VerifyException {
+        VType[] l = new VType[code.max_locals];
+        Arrays.fill(l, TOP);
+        int i = 0;
+        boolean thisUninit = false;
+        for (VType t: locals) {
+            int size = t.isCategory2() ? 2 : 1;
+            if (i + size > l.length)
+                throw new VerifyException("verify.too.many.locals", code.max_locals);
+            l[i] = t;
+            i += size;
+            thisUninit |= t.equals(UNINITIALIZED_THIS);
+        }
+        List<VType> s = new ArrayList<>();
+        for (VType t: stack) {
+            s.add(t);
+            if (t.isCategory2())
+                s.add(TOP);
+        }
+        if (s.size() > code.max_stack)
+            throw new VerifyException("verify.too.many.stack", code.max_stack);
+        return new Frame(l, s, thisUninit);
+    }
+
+    /*
+     * Checks the frame at the handler of each exception table entry whose
+     * range includes the current instruction. The frame is given by the
+     * locals, with the type caught on the stack.
+     */
+    private void checkHandlers(Frame f) throws VerifyException {
+        for (Code_attribute.Exception_data h: code.exception_table) {
+            if (pc < h.start_pc || pc >= h.end_pc)
+                continue;
+            Frame map = frames.get(h.handler_pc);
+            if (map == null)
+                throw new VerifyException("verify.no.handler.frame", h.handler_pc);
+            String type = (h.catch_type == 0) ? THROWABLE : className(h.catch_type);
+            Frame thrown = new Frame(f.locals.clone(), new ArrayList<>(List.of(object(type))), f.thisUninit);
+            if (!isAssignable(thrown, map))
+                throw new VerifyException("verify.handler.mismatch", h.handler_pc).frames(map, thrown);
+        }
+    }
+
+    /*
+     * Checks the frame at each target of a branch or switch, given the types
+     * left by the instruction.
+     */
+    private void checkTargets(Instruction instr) throws VerifyException {
+        for (int target: ControlFlowGraph.getTargets(instr)) {
+            if (!instructions.containsKey(target))
+                throw new VerifyException("verify.bad.target", target);
+            Frame map = frames.get(target);
+            if (map == null)
+                throw new VerifyException("verify.no.target.frame", target);
+            if (!isAssignable(frame, map))
+                throw new VerifyException("verify.target.mismatch", target).frames(map, frame);
+        }
+    }
+
+    /*
+     * Checks the operands of an instruction, and changes the frame to give
+     * the types after it.
+     */
+    private void execute(Instruction instr) throws VerifyException {
+        Opcode opcode = instr.getOpcode();
+        if (opcode == null || opcode.set != Opcode.Set.STANDARD)
+            throw new VerifyException("verify.bad.opcode");
+        switch (opcode) {
+            case NOP:
+                break;
+            case ACONST_NULL:
+                push(NULL);
+                break;
+            case ICONST_M1: case ICONST_0: case ICONST_1: case ICONST_2: case ICONST_3:
+            case ICONST_4: case ICONST_5: case BIPUSH: case SIPUSH:
+                push(INTEGER);
+                break;
+            case LCONST_0: case LCONST_1:
+                push(LONG);
+                break;
+            case FCONST_0: case FCONST_1: case FCONST_2:
+                push(FLOAT);
+                break;
+            case DCONST_0: case DCONST_1:
+                push(DOUBLE);
+                break;
+            case LDC:
+                push(constantType(instr.getUnsignedByte(1), false));
+                break;
+            case LDC_W:
+                push(constantType(instr.getUnsignedShort(1), false));
+                break;
+            case LDC2_W:
+                push(constantType(instr.getUnsignedShort(1), true));
+                break;
+
+            case ILOAD: case ILOAD_W:
+                load(localIndex(instr), INTEGER);
+                break;
+            case LLOAD: case LLOAD_W:
+                load(localIndex(instr), LONG);
+                break;
+            case FLOAD: case FLOAD_W:
+                load(localIndex(instr), FLOAT);
+                break;
+            case DLOAD: case DLOAD_W:
+                load(localIndex(instr), DOUBLE);
+                break;
+            case ALOAD: case ALOAD_W:
+                loadReference(localIndex(instr));
+                break;
+            case ILOAD_0: case ILOAD_1: case ILOAD_2: case ILOAD_3:
+                load(opcode.opcode - Opcode.ILOAD_0.opcode, INTEGER);
+                break;
+            case LLOAD_0: case LLOAD_1: case LLOAD_2: case LLOAD_3:
+                load(opcode.opcode - Opcode.LLOAD_0.opcode, LONG);
+                break;
+            case FLOAD_0: case FLOAD_1: case FLOAD_2: case FLOAD_3:
+                load(opcode.opcode - Opcode.FLOAD_0.opcode, FLOAT);
+                break;
+            case DLOAD_0: case DLOAD_1: case DLOAD_2: case DLOAD_3:
+                load(opcode.opcode - Opcode.DLOAD_0.opcode, DOUBLE);
+                break;
+            case ALOAD_0: case ALOAD_1: case ALOAD_2: case ALOAD_3:
+                loadReference(opcode.opcode - Opcode.ALOAD_0.opcode);
+                break;
+
+            case IALOAD:
+                arrayLoad("[I", INTEGER);
+                break;
+            case LALOAD:
+                arrayLoad("[J", LONG);
+                break;
+            case FALOAD:
+                arrayLoad("[F", FLOAT);
+                break;
+            case DALOAD:
+                arrayLoad("[D", DOUBLE);
+                break;
+            case BALOAD: {
+                // for arrays of byte or boolean
+                pop(INTEGER);
+                pop(object(peek().equals(object("[Z")) ? "[Z" : "[B"));
+                push(INTEGER);
+                break;
+            }
+            case CALOAD:
+                arrayLoad("[C", INTEGER);
+                break;
+            case SALOAD:
+                arrayLoad("[S", INTEGER);
+                break;
+            case AALOAD: {
+                pop(INTEGER);
+                VType array = pop(object(OBJECT_ARRAY));
+                push(array.equals(NULL) ? NULL : VType.of(array.name.substring(1)));
+                break;
+            }
+
+            case ISTORE: case ISTORE_W:
+                store(localIndex(instr), pop(INTEGER));
+                break;
+            case LSTORE: case LSTORE_W:
+                store(localIndex(instr), pop(LONG));
+                break;
+            case FSTORE: case FSTORE_W:
+                store(localIndex(instr), pop(FLOAT));
+                break;
+            case DSTORE: case DSTORE_W:
+                store(localIndex(instr), pop(DOUBLE));
+                break;
+            case ASTORE: case ASTORE_W:
+                store(localIndex(instr), popReference());
+                break;
+            case ISTORE_0: case ISTORE_1: case ISTORE_2: case ISTORE_3:
+                store(opcode.opcode - Opcode.ISTORE_0.opcode, pop(INTEGER));
+                break;
+            case LSTORE_0: case LSTORE_1: case LSTORE_2: case LSTORE_3:
+                store(opcode.opcode - Opcode.LSTORE_0.opcode, pop(LONG));
+                break;
+            case FSTORE_0: case FSTORE_1: case FSTORE_2: case FSTORE_3:
+                store(opcode.opcode - Opcode.FSTORE_0.opcode, pop(FLOAT));
+                break;
+            case DSTORE_0: case DSTORE_1: case DSTORE_2: case DSTORE_3:
+                store(opcode.opcode - Opcode.DSTORE_0.opcode, pop(DOUBLE));
+                break;
+            case ASTORE_0: case ASTORE_1: case ASTORE_2: case ASTORE_3:
+                store(opcode.opcode - Opcode.ASTORE_0.opcode, popReference());
+                break;
+
+            case IASTORE:
+                arrayStore("[I", INTEGER);
+                break;
+            case LASTORE:
+                arrayStore("[J", LONG);
+                break;
+            case FASTORE:
+                arrayStore("[F", FLOAT);
+                break;
+            case DASTORE:
+                arrayStore("[D", DOUBLE);
+                break;
+            case BASTORE: {
+                // for arrays of byte or boolean
+                pop(INTEGER);
+                pop(INTEGER);
+                pop(object(peek().equals(object("[Z")) ? "[Z" : "[B"));
+                break;
+            }
+            case CASTORE:
+                arrayStore("[C", INTEGER);
+                break;
+            case SASTORE:
+                arrayStore("[S", INTEGER);
+                break;
+            case AASTORE:
+                arrayStore(OBJECT_ARRAY, object(OBJECT));
+                break;
+
+            case POP:
+                popCategory1();
+                break;
+            case POP2:
+                popSlots();
+                break;
+            case DUP: {
+                VType v = popCategory1();
+                push(v);
+                push(v);
+                break;
+            }
+            case DUP_X1: {
+                VType v1 = popCategory1();
+                VType v2 = popCategory1();
+                push(v1);
+                push(v2);
+                push(v1);
+                break;
+            }
+            case DUP_X2: {
+                VType v1 = popCategory1();
+                List<VType> v2 = popSlots();
+                push(v1);
+                pushSlots(v2);
+                push(v1);
+                break;
+            }
+            case DUP2: {
+                List<VType> v = popSlots();
+                pushSlots(v);
+                pushSlots(v);
+                break;
+            }
+            case DUP2_X1: {
+                List<VType> v1 = popSlots();
+                VType v2 = popCategory1();
+                pushSlots(v1);
+                push(v2);
+                pushSlots(v1);
+                break;
+            }
+            case DUP2_X2: {
+                List<VType> v1 = popSlots();
+                List<VType> v2 = popSlots();
+                pushSlots(v1);
+                pushSlots(v2);
+                pushSlots(v1);
+                break;
+            }
+            case SWAP: {
+                VType v1 = popCategory1();
+                VType v2 = popCategory1();
+                push(v1);
+                push(v2);
+                break;
+            }
+
+            case IADD: case ISUB: case IMUL: case IDIV: case IREM:
+            case ISHL: case ISHR: case IUSHR: case IAND: case IOR: case IXOR:
+                binary(INTEGER, INTEGER);
+                break;
+            case LADD: case LSUB: case LMUL: case LDIV: case LREM:
+            case LAND: case LOR: case LXOR:
+                binary(LONG, LONG);
+                break;
+            case LSHL: case LSHR: case LUSHR:
+                binary(LONG, INTEGER);
+                break;
+            case FADD: case FSUB: case FMUL: case FDIV: case FREM:
+                binary(FLOAT, FLOAT);
+                break;
+            case DADD: case DSUB: case DMUL: case DDIV: case DREM:
+                binary(DOUBLE, DOUBLE);
+                break;
+            case INEG:
+                convert(INTEGER, INTEGER);
+                break;
+            case LNEG:
+                convert(LONG, LONG);
+                break;
+            case FNEG:
+                convert(FLOAT, FLOAT);
+                break;
+            case DNEG:
+                convert(DOUBLE, DOUBLE);
+                break;
+            case IINC: case IINC_W: {
+                int index = localIndex(instr);
+                checkLocal(index, INTEGER);
+                if (!frame.locals[index].equals(INTEGER))
+                    throw new VerifyException("verify.bad.local", index, typeName(INTEGER), typeName(frame.locals[index]));
+                break;
+            }
+
+            case I2L:
+                convert(INTEGER, LONG);
+                break;
+            case I2F:
+                convert(INTEGER, FLOAT);
+                break;
+            case I2D:
+                convert(INTEGER, DOUBLE);
+                break;
+            case L2I:
+                convert(LONG, INTEGER);
+                break;
+            case L2F:
+                convert(LONG, FLOAT);
+                break;
+            case L2D:
+                convert(LONG, DOUBLE);
+                break;
+            case F2I:
+                convert(FLOAT, INTEGER);
+                break;
+            case F2L:
+                convert(FLOAT, LONG);
+                break;
+            case F2D:
+                convert(FLOAT, DOUBLE);
+                break;
+            case D2I:
+                convert(DOUBLE, INTEGER);
+                break;
+            case D2L:
+                convert(DOUBLE, LONG);
+                break;
+            case D2F:
+                convert(DOUBLE, FLOAT);
+                break;
+            case I2B: case I2C: case I2S:
+                convert(INTEGER, INTEGER);
+                break;
+            case LCMP:
+                pop(LONG);
+                pop(LONG);
+                push(INTEGER);
+                break;
+            case FCMPL: case FCMPG:
+                pop(FLOAT);
+                pop(FLOAT);
+                push(INTEGER);
+                break;
+            case DCMPL: case DCMPG:
+                pop(DOUBLE);
+                pop(DOUBLE);
+                push(INTEGER);
+                break;
+
+            case IFEQ: case IFNE: case IFLT: case IFGE: case IFGT: case IFLE:
+            case TABLESWITCH: case LOOKUPSWITCH:
+                pop(INTEGER);
+                checkTargets(instr);
+                break;
+            case IF_ICMPEQ: case IF_ICMPNE: case IF_ICMPLT: case IF_ICMPGE: case IF_ICMPGT: case IF_ICMPLE:
+                pop(INTEGER);
+                pop(INTEGER);
+                checkTargets(instr);
+                break;
+            case IF_ACMPEQ: case IF_ACMPNE:
+                popReference();
+                popReference();
+                checkTargets(instr);
+                break;
+            case IFNULL: case IFNONNULL:
+                popReference();
+                checkTargets(instr);
+                break;
+            case GOTO: case GOTO_W:
+                checkTargets(instr);
+                break;
+            case JSR: case JSR_W: case RET: case RET_W:
+                throw new VerifyException("verify.jsr");
+
+            case IRETURN:
+                checkReturn("IBCSZ");
+                pop(INTEGER);
+                break;
+            case LRETURN:
+                checkReturn("J");
+                pop(LONG);
+                break;
+            case FRETURN:
+                checkReturn("F");
+                pop(FLOAT);
+                break;
+            case DRETURN:
+                checkReturn("D");
+                pop(DOUBLE);
+                break;
+            case ARETURN:
+                checkReturn("L[");
+                pop(VType.of(returnType()));
+                break;
+            case RETURN:
+                checkReturn("V");
+                if (isConstructor && frame.thisUninit)
+                    throw new VerifyException("verify.uninitialized.return");
+                break;
+
+            case GETSTATIC: case PUTSTATIC: case GETFIELD: case PUTFIELD:
+                field(opcode, ref(instr.getUnsignedShort(1), CONSTANT_Fieldref));
+                break;
+            case INVOKEVIRTUAL: case INVOKESPECIAL: case INVOKESTATIC: case INVOKEINTERFACE:
+                invoke(opcode, ref(instr.getUnsignedShort(1), CONSTANT_Methodref, CONSTANT_InterfaceMethodref));
+                break;
+            case INVOKEDYNAMIC: {
+                int index = instr.getUnsignedShort(1);
+                String type;
+                try {
+                    type = ((CONSTANT_InvokeDynamic_info) constant(index, CONSTANT_InvokeDynamic))
+                            .getNameAndTypeInfo().getType();
+                } catch (ConstantPoolException e) {
+                    throw new VerifyException("verify.bad.constant", index);
+                }
+                popArguments(type);
+                pushReturn(type);
+                break;
+            }
+
+            case NEW: {
+                VType u = uninitialized(pc);
+                if (frame.stack.contains(u))
+                    throw new VerifyException("verify.uninitialized.in.use", pc);
+                for (int i = 0; i < frame.locals.length; i++) {
+                    if (frame.locals[i].equals(u))
+                        frame.locals[i] = TOP;
+                }
+                className(instr.getUnsignedShort(1));
+                push(u);
+                break;
+            }
+            case NEWARRAY: {
+                int type = instr.getUnsignedByte(1);
+                if (type < 4 || type > 11)
+                    throw new VerifyException("verify.bad.array.type", type);
+                pop(INTEGER);
+                push(object("[" + "ZCFDBSIJ".charAt(type - 4)));
+                break;
+            }
+            case ANEWARRAY: {
+                String name = className(instr.getUnsignedShort(1));
+                pop(INTEGER);
+                push(object(name.startsWith("[") ? "[" + name : "[L" + name + ";"));
+                break;
+            }
+            case MULTIANEWARRAY: {
+                String name = className(instr.getUnsignedShort(1));
+                int dimensions = instr.getUnsignedByte(3);
+                if (dimensions < 1 || dimensions > name.lastIndexOf('[') + 1)
+                    throw new VerifyException("verify.bad.dimensions", dimensions, name);
+                for (int i = 0; i < dimensions; i++)
+                    pop(INTEGER);
+                push(object(name));
+                break;
+            }
+            case ARRAYLENGTH: {
+                VType array = peek();
+                if (!array.equals(NULL) && !(array.tag == ITEM_Object && array.name.startsWith("[")))
+                    throw new VerifyException("verify.operand.not.array", typeName(array));
+                pop();
+                push(INTEGER);
+                break;
+            }
+            case ATHROW:
+                pop(object(THROWABLE));
+                break;
+            case CHECKCAST:
+                pop(object(OBJECT));
+                push(object(className(instr.getUnsignedShort(1))));
+                break;
+            case INSTANCEOF:
+                pop(object(OBJECT));
+                className(instr.getUnsignedShort(1));
+                push(INTEGER);
+                break;
+            case MONITORENTER: case MONITOREXIT:
+                popReference();
+                break;
+
+            default:
+                throw new VerifyException("verify.bad.opcode");
+        }
+    }
+
+    private void field(Opcode opcode, CPRefInfo ref) throws VerifyException {
+        String owner, type;
+        try {
+            owner = ref.getClassName();
+            type = ref.getNameAndTypeInfo().getType();
+        } catch (ConstantPoolException e) {
+            throw new VerifyException("verify.bad.constant", ref.name_and_type_index);
+        }
+        switch (opcode) {
+            case GETSTATIC:
+                push(VType.of(type));
+                break;
+            case PUTSTATIC:
+                pop(VType.of(type));
+                break;
+            case GETFIELD:
+                pop(object(owner));
+                push(VType.of(type));
+                break;
+            case PUTFIELD:
+                pop(VType.of(type));
+                // a constructor may set the fields of its class before calling super
+                if (isConstructor && owner.equals(thisClass) && peek().equals(UNINITIALIZED_THIS))
+                    pop();
+                else
+                    pop(object(owner));
+                break;
+        }
+    }
+
+    private void invoke(Opcode opcode, CPRefInfo ref) throws VerifyException {
+        String owner, name, type;
+        try {
+            owner = ref.getClassName();
+            name = ref.getNameAndTypeInfo().getName();
+            type = ref.getNameAndTypeInfo().getType();
+        } catch (ConstantPoolException e) {
+            throw new VerifyException("verify.bad.constant", ref.name_and_type_index);
+        }
+        popArguments(type);
+        if (opcode == Opcode.INVOKESPECIAL && name.equals("<init>")) {
+            initialize(owner);
+            return;
+        }
+        if (name.startsWith("<"))
+            throw new VerifyException("verify.bad.invoke", name);
+        switch (opcode) {
+            case INVOKESTATIC:
+                break;
+            case INVOKESPECIAL:
+                pop(object(thisClass));
+                break;
+            default:
+                pop(object(owner));
+        }
+        pushReturn(type);
+    }
+
+    /*
+     * Checks a call of an instance initialization method, and replaces the
+     * uninitialized object, wherever it appears, with the initialized class.
+     */
+    private void initialize(String owner) throws VerifyException {
+        VType receiver = peek();
+        VType initialized;
+        if (receiver.equals(UNINITIALIZED_THIS)) {
+            if (!owner.equals(thisClass) && !owner.equals(superClass))
+                throw new VerifyException("verify.bad.init", owner, typeName(receiver));
+            initialized = object(thisClass);
+            frame.thisUninit = false;
+        } else if (receiver.tag == ITEM_Uninitialized) {
+            Instruction instr = instructions.get(receiver.offset);
+            if (instr == null || instr.getOpcode() != Opcode.NEW)
+                throw new VerifyException("verify.bad.uninitialized", receiver.offset);
+            String name = className(instr.getUnsignedShort(1));
+            if (!owner.equals(name))
+                throw new VerifyException("verify.bad.init", owner, typeName(receiver));
+            initialized = object(name);
+        } else {
+            throw new VerifyException("verify.operand.not.uninitialized", typeName(receiver));
+        }
+        pop();
+        for (int i = 0; i < frame.locals.length; i++) {
+            if (frame.locals[i].equals(receiver))
+                frame.locals[i] = initialized;
+        }
+        for (int i = 0; i < frame.stack.size(); i++) {
+            if (frame.stack.get(i).equals(receiver))
+                frame.stack.set(i, initialized);
+        }
+    }
+
+    private void popArguments(String descriptor) throws VerifyException {
+        List<VType> args = argumentTypes(descriptor);
+        for (int i = args.size() - 1; i >= 0; i--)
+            pop(args.get(i));
+    }
+
+    private void pushReturn(String descriptor) throws VerifyException {
+        String type = descriptor.substring(descriptor.indexOf(')') + 1);
+        if (!type.equals("V"))
+            push(VType.of(type));
+    }
+
+    private String returnType() {
+        return descriptor.substring(descriptor.indexOf(')') + 1);
+    }
+
+    private void checkReturn(String types) throws VerifyException {
+        if (types.indexOf(returnType().charAt(0)) == -1)
+            throw new VerifyException("verify.bad.return", returnType());
+    }
+
+    private void binary(VType type, VType type2) throws VerifyException {
+        pop(type2);
+        pop(type);
+        push(type);
+    }
+
+    private void convert(VType from, VType to) throws VerifyException {
+        pop(from);
+        push(to);
+    }
+
+    private void arrayLoad(String array, VType type) throws VerifyException {
+        pop(INTEGER);
+        pop(object(array));
+        push(type);
+    }
+
+    private void arrayStore(String array, VType type) throws VerifyException {
+        pop(type);
+        pop(INTEGER);
+        pop(object(array));
+    }
+
+    /*
+     * Returns the type of the value on the top of the stack. A long or double
+     * is given by its type, rather than by the Top that follows it.
+     */
+    private VType peek() throws VerifyException {
+        List<VType> stack = frame.stack;
+        int n = stack.size();
+        if (n == 0)
+            throw new VerifyException("verify.stack.underflow");
+        VType t = stack.get(n - 1);
+        if (t.equals(TOP) && n >= 2 && stack.get(n - 2).isCategory2())
+            return stack.get(n - 2);
+        return t;
+    }
+
+    private VType pop() throws VerifyException {
+        VType t = peek();
+        frame.stack.subList(frame.stack.size() - (t.isCategory2() ? 2 : 1), frame.stack.size()).clear();
+        return t;
+    }
+
+    /*
+     * Pops a value that is assignable to a type.
+     */
+    private VType pop(VType type) throws VerifyException {
+        VType t = peek();
+        if (!isAssignable(t, type))
+            throw new VerifyException("verify.bad.operand", typeName(type), typeName(t));
+        return pop();
+    }
+
+    private VType popReference() throws VerifyException {
+        VType t = peek();
+        if (!t.isReference())
+            throw new VerifyException("verify.operand.not.reference", typeName(t));
+        return pop();
+    }
+
+    private VType popCategory1() throws VerifyException {
+        VType t = peek();
+        if (t.isCategory2() || t.equals(TOP))
+            throw new VerifyException("verify.operand.not.category1", typeName(t));
+        return pop();
+    }
+
+    /*
+     * Pops the two entries of the stack that hold either a value of category 2,
+     * or two values of category 1, for the forms of pop2 and dup2.
+     */
+    private List<VType> popSlots() throws VerifyException {
+        if (peek().isCategory2()) {
+            VType t = pop();
+            return List.of(t, TOP);
+        }
+        VType v1 = popCategory1();
+        VType v2 = popCategory1();
+        return List.of(v2, v1);
+    }
+
+    private void push(VType t) throws VerifyException {
+        frame.stack.add(t);
+        if (t.isCategory2())
+            frame.stack.add(TOP);
+        if (frame.stack.size() > code.max_stack)
+            throw new VerifyException("verify.stack.overflow", code.max_stack);
+    }
+
+    private void pushSlots(List<VType> slots) throws VerifyException {
+        frame.stack.addAll(slots);
+        if (frame.stack.size() > code.max_stack)
+            throw new VerifyException("verify.stack.overflow", code.max_stack);
+    }
+
+    private void checkLocal(int index, VType type) throws VerifyException {
+        if (index + (type.isCategory2() ? 1 : 0) >= code.max_locals)
+            throw new VerifyException("verify.bad.local.index", index, code.max_locals);
+    }
+
+    private void load(int index, VType type) throws VerifyException {
+        checkLocal(index, type);
+        VType t = frame.locals[index];
+        if (!t.equals(type))
+            throw new VerifyException("verify.bad.local", index, typeName(type), typeName(t));
+        push(type);
+    }
+
+    private void loadReference(int index) throws VerifyException {
+        checkLocal(index, TOP);
+        VType t = frame.locals[index];
+        if (!t.isReference())
+            throw new VerifyException("verify.local.not.reference", index, typeName(t));
+        push(t);
+    }
+
+    private void store(int index, VType type) throws VerifyException {
+        checkLocal(index, type);
+        VType[] locals = frame.locals;
+        locals[index] = type;
+        if (type.isCategory2())
+            locals[index + 1] = TOP;
+        if (index > 0 && locals[index - 1].isCategory2())
+            locals[index - 1] = TOP;
+    }
+
+    private static int localIndex(Instruction instr) {
+        return (instr.getOpcode().opcode > 0xff) ? instr.getUnsignedShort(2) : instr.getUnsignedByte(1);
+    }
+
+    /*
+     * Returns the type of the value of a constant, for ldc, ldc_w or ldc2_w.
+     */
+    private VType constantType(int index, boolean isCategory2) throws VerifyException {
+        VType type;
+        try {
+            CPInfo info = cp.get(index);
+            switch (info.getTag()) {
+                case CONSTANT_Integer:
+                    type = INTEGER;
+                    break;
+                case CONSTANT_Float:
+                    type = FLOAT;
+                    break;
+                case CONSTANT_Long:
+                    type = LONG;
+                    break;
+                case CONSTANT_Double:
+                    type = DOUBLE;
+                    break;
+                case CONSTANT_String:
+                    type = object("java/lang/String");
+                    break;
+                case CONSTANT_Class:
+                    type = object("java/lang/Class");
+                    break;
+                case CONSTANT_MethodType:
+                    type = object("java/lang/invoke/MethodType");
+                    break;
+                case CONSTANT_MethodHandle:
+                    type = object("java/lang/invoke/MethodHandle");
+                    break;
+                case CONSTANT_Dynamic:
+                    type = VType.of(((CONSTANT_Dynamic_info) info).getNameAndTypeInfo().getType());
+                    break;
+                default:
+                    throw new VerifyException("verify.bad.constant", index);
+            }
+        } catch (ConstantPoolException e) {
+            throw new VerifyException("verify.bad.constant", index);
+        }
+        if (type.isCategory2() != isCategory2)
+            throw new VerifyException("verify.bad.constant", index);
+        return type;
+    }
+
+    private CPInfo constant(int index, int... tags) throws VerifyException {
+        try {
+            CPInfo info = cp.get(index);
+            for (int tag: tags) {
+                if (info.getTag() == tag)
+                    return info;
+            }
+        } catch (ConstantPoolException e) {
+            // fall through
+        }
+        throw new VerifyException("verify.bad.constant", index);
+    }
+
+    private CPRefInfo ref(int index, int... tags) throws VerifyException {
+        return (CPRefInfo) constant(index, tags);
+    }
+
+    private String className(int index) throws VerifyException {
+        try {
+            return cp.getClassInfo(index).getName();
+        } catch (ConstantPoolException e) {
+            throw new VerifyException("verify.bad.constant", index);
+        }
+    }
+
+    /*
+     * Returns the types of the arguments of a method descriptor.
+     */
+    private static List<VType> argumentTypes(String descriptor) throws VerifyException {
+        List<VType> types = new ArrayList<>();
+        int end = descriptor.indexOf(')');
+        if (!descriptor.startsWith("(") || end == -1)
+            throw new VerifyException("verify.bad.descriptor", descriptor);
+        for (int i = 1; i < end; ) {
+            int start = i;
+            while (i < end && descriptor.charAt(i) == '[')
+                i++;
+            if (i < end && descriptor.charAt(i) == 'L')
+                i = descriptor.indexOf(';', i);
+            if (i < 0 || i >= end)
+                throw new VerifyException("verify.bad.descriptor", descriptor);
+            types.add(VType.of(descriptor.substring(start, ++i)));
+        }
+        return types;
+    }
+
+    /*
+     * Whether the types of one frame are assignable to those of another.
+     */
+    private boolean isAssignable(Frame from, Frame to) {
+        if (from.stack.size() != to.stack.size() || (from.thisUninit && !to.thisUninit))
+            return false;
+        for (int i = 0; i < from.locals.length; i++) {
+            if (!isAssignable(from.locals[i], to.locals[i]))
+                return false;
+        }
+        for (int i = 0; i < from.stack.size(); i++) {
+            if (!isAssignable(from.stack.get(i), to.stack.get(i)))
+                return false;
+        }
+        return true;
+    }
+
+    /*
+     * Whether a value of one verification type may be assigned to another
+     * (JVMS 4.10.1.2).
+     */
+    private boolean isAssignable(VType from, VType to) {
+        if (from.equals(to) || to.equals(TOP))
+            return true;
+        if (to.tag != ITEM_Object)
+            return false;
+        return from.equals(NULL) || (from.tag == ITEM_Object && isAssignable(from.name, to.name));
+    }
+
+    /*
+     * Whether a value of one class, or array class, may be assigned to another.
+     */
+    private boolean isAssignable(String from, String to) {
+        if (from.equals(to) || to.equals(OBJECT))
+            return true;
+        if (to.startsWith("[")) {
+            if (!from.startsWith("["))
+                return false;
+            String f = from.substring(1), t = to.substring(1);
+            if (isReference(f) && isReference(t))
+                return isAssignable(VType.of(f).name, VType.of(t).name);
+            return f.equals(t);
+        }
+        if (from.startsWith("["))
+            return to.equals("java/lang/Cloneable") || to.equals("java/io/Serializable");
+        return hierarchy.isSubclass(from, to);
+    }
+
+    private static boolean isReference(String descriptor) {
+        return descriptor.startsWith("L") || descriptor.startsWith("[");
+    }
+
+    /*
+     * Returns the name of a type, as in a StackMapTable attribute written by
+     * AttributeWriter.
+     */
+    static String typeName(VType t) {
+        switch (t.tag) {
+            case ITEM_Top:
+                return "top";
+            case ITEM_Integer:
+                return "int";
+            case ITEM_Float:
+                return "float";
+            case ITEM_Long:
+                return "long";
+            case ITEM_Double:
+                return "double";
+            case ITEM_Null:
+                return "null";
+            case ITEM_UninitializedThis:
+                return "this";
+            case ITEM_Object:
+                return "class " + t.name;
+            case ITEM_Uninitialized:
+                return "uninitialized " + t.offset;
+            default:
+                return "returnAddress";
+        }
+    }
+
+    /*
+     * The types of the locals and of the operand stack before or after an
+     * instruction, in which a long or double takes two entries, the second
+     * of which is Top, and whether the locals hold an uninitialized this.
+     */
+    private static class Frame {
+        Frame(VType[] locals, List<VType> stack, boolean thisUninit) {
+            this.locals = locals;
+            this.stack = stack;
+            this.thisUninit = thisUninit;
+        }
+
+        Frame copy() {
+            return new Frame(locals.clone(), new ArrayList<>(stack), thisUninit);
+        }
+
+        /*
+         * Returns the frame as a StackMapTable attribute is written by
+         * AttributeWriter, without the Top after a long or double, or at the
+         * end of the locals.
+         */
+        @Override
+        public String toString() {
+            int n = locals.length;
+            while (n > 0 && locals[n - 1].equals(TOP))
+                n--;
+            return "locals = " + toString(Arrays.asList(locals).subList(0, n)) + " stack = " + toString(stack);
+        }
+
+        private static String toString(List<VType> types) {
+            StringJoiner sj = new StringJoiner(", ", "[ ", " ]");
+            sj.setEmptyValue("[]");
+            for (int i = 0; i < types.size(); i++) {
+                VType t = types.get(i);
+                sj.add(typeName(t));
+                if (t.isCategory2())
+                    i++;
+            }
+            return sj.toString();
+        }
+
+        final VType[] locals;
+        final List<VType> stack;
+        boolean thisUninit;
+    }
+
+    private static class VerifyException extends Exception {
+        private static final long serialVersionUID = 1L;
+
+        VerifyException(String key, Object... args) {
+            super(key);
+            this.key = key;
+            this.args = args;
+        }
+
+        VerifyException frames(Frame expected, Frame actual) {
+            this.expected = expected;
+            this.actual = actual;
+            return this;
+        }
+
+        VerifyException at(int offset) {
+            this.offset = offset;
+            return this;
+        }
+
+        final String key;
+        final transient Object[] args;
+        transient Frame expected;
+        transient Frame actual;
+        int offset = -1;
+    }
+
+    private static final String OBJECT = "java/lang/Object";
+    private static final String OBJECT_ARRAY = "[Ljava/lang/Object;";
+    private static final String THROWABLE = "java/lang/Throwable";
+
+    private final ClassHierarchy hierarchy;
+    private ConstantPool cp;
+    private String thisClass;
+    private String superClass;
+
+    // the method being verified
+    private Code_attribute code;
+    private String descriptor;
+    private boolean isConstructor;
+    private TreeMap<Integer, Instruction> instructions;
+    private List<VType> initialLocals;
+    private TreeMap<Integer, Frame> frames;
+    private int pc;
+    private Frame current;      // the frame before the instruction at pc
+    private Frame frame;        // the frame as the instruction at pc is executed
+}
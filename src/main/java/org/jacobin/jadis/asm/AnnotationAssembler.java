@@ -0,0 +1,348 @@
+/*
+ * Copyright (c) 2021 by Andrew Binstock.
+ *
+ * Portions of this file are copyright Oracle Corp.
+ * Those portions are licensed under GPL v. 2.0
+ * with the Oracle classpath exception. Due to the
+ * requirements of that license, the portions that
+ * are copyrighted by Andrew Binstock are licensed
+ * using the same terms and requirements.
+ *
+ */
+
+package org.jacobin.jadis.asm;
+
+import java.util.Map;
+
+import org.jacobin.jadis.classfile.Attribute;
+
+import static org.jacobin.jadis.classfile.ConstantPool.*;
+
+/*
+ *  Assembles the attributes that hold annotations, from a .runtime directive
+ *  to .end runtime, and the AnnotationDefault attribute, from .annotationdefault.
+ *
+ *  An annotation is given by .annotation and its type, then a line for each
+ *  element value, with the name, =, the kind and the value, then .end annotation.
+ *  A nested annotation or an array of element values takes the lines up to its
+ *  own .end directive. The target of a type annotation is given by the target
+ *  type, a keyword and the values that locate the target, then typepath and the
+ *  entries of the type path. Offsets in the code are given by labels, and so
+ *  can only be given for the type annotations of a Code attribute.
+ *
+ *  <p><b>This is NOT part of any supported API.
+ *  If you write code that depends on this, you do so at your own risk.
+ *  This code and its internal interfaces are subject to change or
+ *  deletion without notice.</b>
+ */
+class AnnotationAssembler {
+    /*
+     *  The lines that follow the directive: the rest of the listing, or the lines
+     *  kept by the assembler of a Code attribute.
+     */
+    interface Lines {
+        Tokenizer.Line next() throws AsmException;
+    }
+
+    /*
+     *  Resolves a label to an offset in the code.
+     */
+    interface Labels {
+        int offset(Tokenizer.Line line, String label) throws AsmException;
+    }
+
+    /**
+     * Creates an assembler for annotations.
+     * @param assembler the assembler of the class
+     * @param lines the lines that follow the directive
+     * @param labels the labels of the code, or null if the attribute is not in code
+     */
+    AnnotationAssembler(Assembler assembler, Lines lines, Labels labels) {
+        this.assembler = assembler;
+        this.lines = lines;
+        this.labels = labels;
+        cp = assembler.getConstantPool();
+    }
+
+    /*
+     *  Reads a .runtime directive, after the directive itself, up to .end runtime:
+     *  visible or invisible, then annotations, paramannotations or typeannotations.
+     */
+    Bytes runtime(Tokenizer.Line line) throws AsmException {
+        String prefix;
+        if (line.accept("visible")) {
+            prefix = "RuntimeVisible";
+        } else {
+            line.expect("invisible");
+            prefix = "RuntimeInvisible";
+        }
+        String kind = line.word();
+        line.end();
+        Bytes b = new Bytes();
+        int count = 0;
+        switch (kind) {
+            case "annotations":
+                for (Tokenizer.Line l = lines.next(); !isEnd(l, "runtime"); l = lines.next()) {
+                    l.expect(".annotation");
+                    annotation(l, b, "annotation");
+                    count++;
+                }
+                return assembler.attribute(prefix + "Annotations", new Bytes().u2(count).bytes(b.toByteArray()));
+            case "paramannotations":
+                for (Tokenizer.Line l = lines.next(); !isEnd(l, "runtime"); l = lines.next()) {
+                    l.expect(".paramannotation");
+                    l.end();
+                    Bytes param = new Bytes();
+                    int n = 0;
+                    for (Tokenizer.Line a = lines.next(); !isEnd(a, "paramannotation"); a = lines.next()) {
+                        a.expect(".annotation");
+                        annotation(a, param, "annotation");
+                        n++;
+                    }
+                    b.u2(n).bytes(param.toByteArray());
+                    count++;
+                }
+                if (count > 0xff)
+                    throw line.error("too many parameters: " + count);
+                return assembler.attribute(prefix + "ParameterAnnotations",
+                        new Bytes().u1(count).bytes(b.toByteArray()));
+            case "typeannotations":
+                for (Tokenizer.Line l = lines.next(); !isEnd(l, "runtime"); l = lines.next()) {
+                    l.expect(".typeannotation");
+                    typeAnnotation(l, b);
+                    count++;
+                }
+                return assembler.attribute(prefix + "TypeAnnotations", new Bytes().u2(count).bytes(b.toByteArray()));
+            default:
+                throw line.error("expected annotations, paramannotations or typeannotations, found " + kind);
+        }
+    }
+
+    /*
+     *  Reads an .annotationdefault directive, after the directive itself: the
+     *  default value, as an element value.
+     */
+    Bytes annotationDefault(Tokenizer.Line line) throws AsmException {
+        Bytes b = new Bytes();
+        elementValue(line, b);
+        return assembler.attribute(Attribute.AnnotationDefault, b);
+    }
+
+    /*
+     *  Reads an annotation, after the directive or keyword that starts it: the
+     *  type, then the element values up to the given .end directive.
+     */
+    private void annotation(Tokenizer.Line line, Bytes b, String end) throws AsmException {
+        b.u2(cp.utf8(line.word()));
+        line.end();
+        Bytes pairs = new Bytes();
+        int count = 0;
+        for (Tokenizer.Line l = lines.next(); !isEnd(l, end); l = lines.next()) {
+            pairs.u2(cp.utf8(l.word()));
+            l.expect("=");
+            elementValue(l, pairs);
+            count++;
+        }
+        b.u2(count).bytes(pairs.toByteArray());
+    }
+
+    /*
+     *  Reads an element value: the kind, then the value.
+     */
+    private void elementValue(Tokenizer.Line line, Bytes b) throws AsmException {
+        String kind = line.word();
+        switch (kind) {
+            case "string":
+                b.u1('s').u2(cp.utf8(line.word()));
+                break;
+            case "enum":
+                b.u1('e').u2(cp.utf8(line.word())).u2(cp.utf8(line.word()));
+                break;
+            case "class":
+                b.u1('c').u2(cp.utf8(line.word()));
+                break;
+            case "annotation":
+                b.u1('@');
+                annotation(line, b, "annotation");
+                return;
+            case "array": {
+                line.end();
+                Bytes values = new Bytes();
+                int count = 0;
+                for (Tokenizer.Line l = lines.next(); !isEnd(l, "array"); l = lines.next()) {
+                    elementValue(l, values);
+                    count++;
+                }
+                b.u1('[').u2(count).bytes(values.toByteArray());
+                return;
+            }
+            default: {
+                Character tag = TAGS.get(kind);
+                if (tag == null)
+                    throw line.error("unknown element value kind: " + kind);
+                int index = assembler.constant(line);
+                int expected;
+                switch (tag) {
+                    case 'J':
+                        expected = CONSTANT_Long;
+                        break;
+                    case 'F':
+                        expected = CONSTANT_Float;
+                        break;
+                    case 'D':
+                        expected = CONSTANT_Double;
+                        break;
+                    default:
+                        expected = CONSTANT_Integer;
+                }
+                if (cp.get(index).tag != expected)
+                    throw line.error("bad " + kind + " value");
+                b.u1(tag).u2(index);
+            }
+        }
+        line.end();
+    }
+
+    /*
+     *  Reads a type annotation, after the .typeannotation directive: the target
+     *  type, the keyword and values that locate the target, the type path, then
+     *  the annotation.
+     */
+    private void typeAnnotation(Tokenizer.Line line, Bytes b) throws AsmException {
+        String t = line.word();
+        int target;
+        try {
+            target = Integer.decode(t);
+        } catch (NumberFormatException e) {
+            throw line.error("bad type annotation target: " + t);
+        }
+        b.u1(target);
+        switch (target) {
+            case 0x00:
+            case 0x01:
+                line.expect("typeparam");
+                b.u1(value(line, 0xff));
+                break;
+            case 0x10:
+                line.expect("super");
+                b.u2(value(line, 0xffff));
+                break;
+            case 0x11:
+            case 0x12:
+                line.expect("typeparambound");
+                b.u1(value(line, 0xff)).u1(value(line, 0xff));
+                break;
+            case 0x13:
+            case 0x14:
+            case 0x15:
+                line.expect("empty");
+                break;
+            case 0x16:
+                line.expect("methodparam");
+                b.u1(value(line, 0xff));
+                break;
+            case 0x17:
+                line.expect("throws");
+                b.u2(value(line, 0xffff));
+                break;
+            case 0x40:
+            case 0x41: {
+                line.expect("localvar");
+                Bytes table = new Bytes();
+                int count = 0;
+                while (line.hasNext() && !line.peek().equals("typepath")) {
+                    int start = label(line);
+                    int end = label(line);
+                    table.u2(start).u2(end - start).u2(value(line, 0xffff));
+                    count++;
+                }
+                b.u2(count).bytes(table.toByteArray());
+                break;
+            }
+            case 0x42:
+                line.expect("catch");
+                b.u2(value(line, 0xffff));
+                break;
+            case 0x43:
+            case 0x44:
+            case 0x45:
+            case 0x46:
+                line.expect("offset");
+                b.u2(label(line));
+                break;
+            case 0x47:
+            case 0x48:
+            case 0x49:
+            case 0x4a:
+            case 0x4b:
+                line.expect("typearg");
+                b.u2(label(line)).u1(value(line, 0xff));
+                break;
+            default:
+                throw line.error("unknown type annotation target: " + t);
+        }
+
+        line.expect("typepath");
+        Bytes path = new Bytes();
+        int count = 0;
+        while (true) {
+            if (line.accept("array"))
+                path.u1(0).u1(0);
+            else if (line.accept("inner"))
+                path.u1(1).u1(0);
+            else if (line.accept("wildcard"))
+                path.u1(2).u1(0);
+            else if (line.accept("typearg"))
+                path.u1(3).u1(value(line, 0xff));
+            else
+                break;
+            count++;
+        }
+        b.u1(count).bytes(path.toByteArray());
+        annotation(line, b, "typeannotation");
+    }
+
+    private int label(Tokenizer.Line line) throws AsmException {
+        String label = line.word();
+        if (labels == null)
+            throw line.error("label " + label + " can only be given in code");
+        return labels.offset(line, label);
+    }
+
+    private static int value(Tokenizer.Line line, int max) throws AsmException {
+        int v = line.integer();
+        if (v < 0 || v > max)
+            throw line.error("value " + v + " is out of range: it must be from 0 to " + max);
+        return v;
+    }
+
+    /*
+     *  Reads the given .end directive, if it is the next line.
+     */
+    private static boolean isEnd(Tokenizer.Line line, String name) throws AsmException {
+        if (!line.accept(".end"))
+            return false;
+        line.expect(name);
+        line.end();
+        return true;
+    }
+
+    /*
+     *  The tags of the element values that are constants, other than string,
+     *  by their kinds.
+     */
+    private static final Map<String, Character> TAGS = Map.of(
+        "byte", 'B',
+        "char", 'C',
+        "double", 'D',
+        "float", 'F',
+        "int", 'I',
+        "long", 'J',
+        "short", 'S',
+        "boolean", 'Z');
+
+    private final Assembler assembler;
+    private final Lines lines;
+    private final Labels labels;
+    private final ConstantPoolBuilder cp;
+}
@@ -0,0 +1,33 @@
+/*
+ * Copyright (c) 2021 by Andrew Binstock.
+ *
+ * Portions of this file are copyright Oracle Corp.
+ * Those portions are licensed under GPL v. 2.0
+ * with the Oracle classpath exception. Due to the
+ * requirements of that license, the portions that
+ * are copyrighted by Andrew Binstock are licensed
+ * using the same terms and requirements.
+ *
+ */
+
+package org.jacobin.jadis.asm;
+
+/*
+ *  An error in an assembly listing, at a given line.
+ *
+ *  <p><b>This is NOT part of any supported API.
+ *  If you write code that depends on this, you do so at your own risk.
+ *  This code and its internal interfaces are subject to change or
+ *  deletion without notice.</b>
+ */
+public class AsmException extends Exception {
+    private static final long serialVersionUID = 1L;
+
+    public AsmException(int line, String message) {
+        super(message);
+        this.line = line;
+    }
+
+    /** The line of the listing at which the error was found, or 0 if not known. */
+    public final int line;
+}
@@ -0,0 +1,217 @@
+/*
+ * Copyright (c) 2021 by Andrew Binstock.
+ *
+ * Portions of this file are copyright Oracle Corp.
+ * Those portions are licensed under GPL v. 2.0
+ * with the Oracle classpath exception. Due to the
+ * requirements of that license, the portions that
+ * are copyrighted by Andrew Binstock are licensed
+ * using the same terms and requirements.
+ *
+ */
+
+package org.jacobin.jadis.asm;
+
+import java.io.File;
+import java.io.IOException;
+import java.io.PrintWriter;
+import java.net.MalformedURLException;
+import java.net.URL;
+import java.net.URLClassLoader;
+import java.nio.charset.StandardCharsets;
+import java.nio.file.Files;
+import java.nio.file.NoSuchFileException;
+import java.nio.file.Path;
+import java.nio.file.Paths;
+import java.text.MessageFormat;
+import java.util.ArrayList;
+import java.util.LinkedHashMap;
+import java.util.List;
+import java.util.Locale;
+import java.util.Map;
+import java.util.MissingResourceException;
+import java.util.ResourceBundle;
+
+/*
+ *  The main program of jadis-asm, which assembles listings in the syntax
+ *  written by jadis --format=krakatau back into class files.
+ *
+ *  <p><b>This is NOT part of any supported API.
+ *  If you write code that depends on this, you do so at your own risk.
+ *  This code and its internal interfaces are subject to change or
+ *  deletion without notice.</b>
+ */
+public class AsmTask {
+    public static final int
+        EXIT_OK = 0,        // All the listings were assembled.
+        EXIT_ERROR = 1,     // Completed but reported errors.
+        EXIT_CMDERR = 2;    // Bad command-line arguments
+
+    /** The name used for the standard input. */
+    static final String STDIN = "-";
+
+    public void setLog(PrintWriter log) {
+        this.log = log;
+    }
+
+    /**
+     * Assembles the listings given by the command-line arguments.
+     * @param args the command-line arguments
+     * @return the exit code
+     */
+    public int run(String[] args) {
+        if (log == null)
+            log = new PrintWriter(System.err, true);
+        try {
+            return run0(args);
+        } finally {
+            log.flush();
+        }
+    }
+
+    private int run0(String[] args) {
+        List<String> files = new ArrayList<>();
+        for (int i = 0; i < args.length; i++) {
+            String arg = args[i];
+            switch (arg) {
+                case "--help":
+                case "-help":
+                case "-h":
+                case "-?":
+                    log.println(getMessage("main.usage", PROGNAME));
+                    return EXIT_OK;
+                case "--compute-maxs":
+                    computeMaxs = true;
+                    break;
+                case "--compute-frames":
+                    computeFrames = true;
+                    break;
+                case "-d":
+                case "--class-path":
+                case "-classpath":
+                case "-cp":
+                    if (i + 1 == args.length) {
+                        reportError("err.missing.arg", arg);
+                        return EXIT_CMDERR;
+                    }
+                    if (arg.equals("-d"))
+                        outputDir = Paths.get(args[++i]);
+                    else
+                        classPath = args[++i];
+                    break;
+                default:
+                    if (arg.startsWith("-") && !arg.equals(STDIN)) {
+                        reportError("err.unknown.option", arg);
+                        log.println(getMessage("main.usage.summary", PROGNAME));
+                        return EXIT_CMDERR;
+                    }
+                    files.add(arg);
+            }
+        }
+        if (files.isEmpty()) {
+            reportError("err.no.files.specified");
+            log.println(getMessage("main.usage.summary", PROGNAME));
+            return EXIT_CMDERR;
+        }
+
+        Map<String, String> listings = new LinkedHashMap<>();
+        for (String file: files) {
+            try {
+                byte[] bytes = file.equals(STDIN) ? System.in.readAllBytes() : Files.readAllBytes(Paths.get(file));
+                listings.put(file, new String(bytes, StandardCharsets.UTF_8));
+            } catch (NoSuchFileException e) {
+                reportError("err.file.not.found", file);
+                return EXIT_ERROR;
+            } catch (IOException e) {
+                reportError("err.ioerror", file, e.getLocalizedMessage());
+                return EXIT_ERROR;
+            }
+        }
+
+        ClassHierarchy hierarchy = new ClassHierarchy(classLoader());
+        Assembler assembler = new Assembler(hierarchy, computeMaxs, computeFrames);
+        int result = EXIT_OK;
+        for (Map.Entry<String, String> e: listings.entrySet()) {
+            try {
+                assembler.scan(e.getValue());
+            } catch (AsmException ex) {
+                // reported when the listing is assembled
+            }
+        }
+        for (Map.Entry<String, String> e: listings.entrySet()) {
+            String file = e.getKey().equals(STDIN) ? getMessage("stdin") : e.getKey();
+            Map<String, byte[]> classes;
+            try {
+                classes = assembler.assemble(e.getValue());
+            } catch (AsmException ex) {
+                reportError("err.asm", file, String.valueOf(ex.line), ex.getMessage());
+                result = EXIT_ERROR;
+                continue;
+            }
+            for (Map.Entry<String, byte[]> c: classes.entrySet()) {
+                Path out = outputDir.resolve(c.getKey() + ".class");
+                try {
+                    if (out.getParent() != null)
+                        Files.createDirectories(out.getParent());
+                    Files.write(out, c.getValue());
+                } catch (IOException ex) {
+                    reportError("err.cant.write", out, ex.getLocalizedMessage());
+                    result = EXIT_ERROR;
+                }
+            }
+        }
+        for (String name: hierarchy.getMissingClasses())
+            reportWarning("warn.class.not.found", name);
+        return result;
+    }
+
+    /*
+     *  The class loader used to find classes that are not being assembled,
+     *  to compute stack map frames.
+     */
+    private ClassLoader classLoader() {
+        if (classPath == null)
+            return ClassLoader.getSystemClassLoader();
+        List<URL> urls = new ArrayList<>();
+        for (String entry: classPath.split(File.pathSeparator)) {
+            try {
+                urls.add(Paths.get(entry.isEmpty() ? "." : entry).toUri().toURL());
+            } catch (MalformedURLException e) {
+                reportWarning("warn.bad.class.path", entry);
+            }
+        }
+        return new URLClassLoader(urls.toArray(new URL[0]), ClassLoader.getPlatformClassLoader());
+    }
+
+    private void reportError(String key, Object... args) {
+        log.println(getMessage("err.prefix") + " " + getMessage(key, args));
+    }
+
+    private void reportWarning(String key, Object... args) {
+        log.println(getMessage("warn.prefix") + " " + getMessage(key, args));
+    }
+
+    private String getMessage(String key, Object... args) {
+        if (bundle == null) {
+            try {
+                bundle = ResourceBundle.getBundle("org.jacobin.jadis.resources.asm", Locale.getDefault());
+            } catch (MissingResourceException e) {
+                throw new InternalError("Cannot find jadis-asm resource bundle for locale " + Locale.getDefault());
+            }
+        }
+        try {
+            return MessageFormat.format(bundle.getString(key), args);
+        } catch (MissingResourceException e) {
+            throw new InternalError("Cannot find jadis-asm message " + key, e);
+        }
+    }
+
+    private static final String PROGNAME = "jadis-asm";
+
+    private PrintWriter log;
+    private ResourceBundle bundle;
+    private Path outputDir = Paths.get(".");
+    private String classPath;
+    private boolean computeMaxs;
+    private boolean computeFrames;
+}
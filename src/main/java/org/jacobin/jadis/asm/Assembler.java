@@ -0,0 +1,926 @@
+/*
+ * Copyright (c) 2021 by Andrew Binstock.
+ *
+ * Portions of this file are copyright Oracle Corp.
+ * Those portions are licensed under GPL v. 2.0
+ * with the Oracle classpath exception. Due to the
+ * requirements of that license, the portions that
+ * are copyrighted by Andrew Binstock are licensed
+ * using the same terms and requirements.
+ *
+ */
+
+package org.jacobin.jadis.asm;
+
+import java.io.ByteArrayOutputStream;
+import java.io.DataOutputStream;
+import java.io.IOException;
+import java.util.ArrayList;
+import java.util.Arrays;
+import java.util.HashMap;
+import java.util.LinkedHashMap;
+import java.util.List;
+import java.util.Map;
+
+import org.jacobin.jadis.classfile.Attribute;
+import org.jacobin.jadis.classfile.ConstantPool.RefKind;
+import org.jacobin.jadis.classfile.Module_attribute;
+
+import static org.jacobin.jadis.classfile.AccessFlags.*;
+import static org.jacobin.jadis.classfile.ConstantPool.*;
+
+/*
+ *  Assembles listings in the Krakatau syntax written by --format=krakatau back
+ *  into class files. A listing may contain several classes, each ending with
+ *  .end class.
+ *
+ *  The constant pool is built afresh from the symbolic operands, so the indexes
+ *  of constants need not be the same as in the class from which the listing was
+ *  written. Labels are resolved to offsets, and ldc and the instructions that
+ *  use locals are widened as needed. max_stack and max_locals are recomputed if
+ *  they are not given, or if asked; stack map frames are recomputed if asked,
+ *  and are otherwise assembled from the .stack directives. Annotations are
+ *  assembled by AnnotationAssembler.
+ *
+ *  <p><b>This is NOT part of any supported API.
+ *  If you write code that depends on this, you do so at your own risk.
+ *  This code and its internal interfaces are subject to change or
+ *  deletion without notice.</b>
+ */
+public class Assembler {
+    /**
+     * Creates an assembler.
+     * @param hierarchy the class hierarchy, used when computing frames
+     * @param computeMaxs whether to recompute max_stack and max_locals, even where they are given
+     * @param computeFrames whether to compute stack map frames, rather than use those given
+     */
+    public Assembler(ClassHierarchy hierarchy, boolean computeMaxs, boolean computeFrames) {
+        this.hierarchy = hierarchy;
+        this.computeMaxs = computeMaxs;
+        this.computeFrames = computeFrames;
+    }
+
+    /**
+     * Adds the classes of a listing to the class hierarchy, so that the frames of
+     * classes that refer to each other can be computed. This should be called for
+     * all the listings to be assembled, before any are assembled.
+     * @param text the listing
+     * @throws AsmException if the listing cannot be read
+     */
+    public void scan(String text) throws AsmException {
+        String name = null;
+        String superName = null;
+        boolean isInterface = false;
+        for (Tokenizer.Line line: Tokenizer.tokenize(text)) {
+            List<Tokenizer.Token> tokens = line.tokens;
+            switch (tokens.get(0).text) {
+                case ".class":
+                    name = tokens.get(tokens.size() - 1).text;
+                    superName = null;
+                    isInterface = false;
+                    for (Tokenizer.Token t: tokens.subList(1, tokens.size() - 1))
+                        isInterface |= t.text.equals("interface");
+                    break;
+                case ".super":
+                    if (tokens.size() > 1)
+                        superName = tokens.get(1).text;
+                    break;
+                case ".end":
+                    if (name != null && tokens.size() > 1 && tokens.get(1).text.equals("class")) {
+                        hierarchy.add(name, superName, isInterface);
+                        name = null;
+                    }
+                    break;
+            }
+        }
+    }
+
+    /**
+     * Assembles the classes in a listing.
+     * @param text the listing
+     * @return the class files, keyed by the names of the classes, in the order given
+     * @throws AsmException if there is an error in the listing
+     */
+    public Map<String, byte[]> assemble(String text) throws AsmException {
+        lines = Tokenizer.tokenize(text);
+        pos = 0;
+        Map<String, byte[]> classes = new LinkedHashMap<>();
+        while (pos < lines.size()) {
+            int first = lines.get(pos).number;
+            byte[] bytes;
+            try {
+                bytes = assembleClass();
+            } catch (IllegalStateException e) {
+                throw new AsmException(lines.get(pos - 1).number, e.getMessage());
+            }
+            if (classes.put(thisName, bytes) != null)
+                throw new AsmException(first, "class " + thisName + " is given more than once");
+        }
+        return classes;
+    }
+
+    private byte[] assembleClass() throws AsmException {
+        cp = new ConstantPoolBuilder();
+        major = 49;
+        minor = 0;
+        access = 0;
+        thisName = null;
+        superName = null;
+        interfaces = new ArrayList<>();
+        fields = new ArrayList<>();
+        methods = new ArrayList<>();
+        attributes = new ArrayList<>();
+        bootstrapMethods = new ArrayList<>();
+        bootstrapMethodIndexes = new HashMap<>();
+        addLdcConstants();
+
+        Tokenizer.Line line;
+        while (true) {
+            if (pos >= lines.size())
+                throw new AsmException(lines.get(lines.size() - 1).number, "missing .end class");
+            line = nextLine();
+            String directive = line.word();
+            if (thisName == null && !directive.equals(".version") && !directive.equals(".class"))
+                throw line.error("expected .class, found " + directive);
+            switch (directive) {
+                case ".version":
+                    major = line.integer();
+                    minor = line.integer();
+                    break;
+                case ".class": {
+                    if (thisName != null)
+                        throw line.error("missing .end class");
+                    List<String> words = words(line);
+                    if (words.isEmpty())
+                        throw line.error("missing class name");
+                    thisName = words.remove(words.size() - 1);
+                    access = flags(line, words, CLASS_FLAGS);
+                    break;
+                }
+                case ".super":
+                    superName = line.word();
+                    break;
+                case ".implements":
+                    interfaces.add(cp.classRef(line.word()));
+                    break;
+                case ".sourcefile":
+                    attributes.add(attribute(Attribute.SourceFile, new Bytes().u2(cp.utf8(line.word()))));
+                    break;
+                case ".signature":
+                    attributes.add(attribute(Attribute.Signature, new Bytes().u2(cp.utf8(line.word()))));
+                    break;
+                case ".deprecated":
+                    attributes.add(attribute(Attribute.Deprecated, new Bytes()));
+                    break;
+                case ".enclosing": {
+                    line.expect("method");
+                    int c = cp.classRef(line.word());
+                    int m = line.accept("[0]") ? 0 : cp.nameAndType(line.word(), line.word());
+                    attributes.add(attribute(Attribute.EnclosingMethod, new Bytes().u2(c).u2(m)));
+                    break;
+                }
+                case ".innerclasses":
+                    line.end();
+                    attributes.add(innerClasses());
+                    continue;
+                case ".nesthost":
+                    attributes.add(attribute(Attribute.NestHost, new Bytes().u2(cp.classRef(line.word()))));
+                    break;
+                case ".nestmembers":
+                    attributes.add(attribute(Attribute.NestMembers, classes(line)));
+                    continue;
+                case ".permittedsubclasses":
+                    attributes.add(attribute(Attribute.PermittedSubclasses, classes(line)));
+                    continue;
+                case ".field":
+                    fields.add(field(line));
+                    continue;
+                case ".method":
+                    methods.add(method(line));
+                    continue;
+                case ".end":
+                    line.expect("class");
+                    line.end();
+                    return toByteArray();
+                default: {
+                    Bytes attr = attributeDirective(directive, line);
+                    if (attr == null)
+                        throw line.error("unknown directive: " + directive);
+                    attributes.add(attr);
+                    continue;
+                }
+            }
+            line.end();
+        }
+    }
+
+    /*
+     *  Adds the operands of the ldc instructions of the class to the constant
+     *  pool before anything else, so that they have indexes that fit in a byte,
+     *  and ldc need not be widened to ldc_w.
+     */
+    private void addLdcConstants() throws AsmException {
+        for (int i = pos; i < lines.size(); i++) {
+            Tokenizer.Line line = lines.get(i);
+            String first = line.peek();
+            if (first.equals(".end") && line.tokens.size() > 1 && line.tokens.get(1).text.equals("class"))
+                break;
+            if (first.endsWith(":"))
+                line.next();
+            // an element value named ldc is not an instruction
+            if (line.accept("ldc") && !"=".equals(line.peek()))
+                constant(line);
+            line.rewind();
+        }
+    }
+
+    private byte[] toByteArray() {
+        if (!bootstrapMethods.isEmpty()) {
+            Bytes b = new Bytes().u2(bootstrapMethods.size());
+            for (int[] bsm: bootstrapMethods) {
+                b.u2(bsm[0]).u2(bsm.length - 1);
+                for (int i = 1; i < bsm.length; i++)
+                    b.u2(bsm[i]);
+            }
+            attributes.add(attribute(Attribute.BootstrapMethods, b));
+        }
+        int thisClass = cp.classRef(thisName);
+        int superClass = (superName == null) ? 0 : cp.classRef(superName);
+
+        try {
+            ByteArrayOutputStream bytes = new ByteArrayOutputStream();
+            DataOutputStream out = new DataOutputStream(bytes);
+            out.writeInt(0xCAFEBABE);
+            out.writeShort(minor);
+            out.writeShort(major);
+            cp.write(out);
+            out.writeShort(access);
+            out.writeShort(thisClass);
+            out.writeShort(superClass);
+            out.writeShort(interfaces.size());
+            for (int i: interfaces)
+                out.writeShort(i);
+            for (List<Bytes> members: List.of(fields, methods)) {
+                out.writeShort(members.size());
+                for (Bytes m: members)
+                    out.write(m.toByteArray());
+            }
+            out.writeShort(attributes.size());
+            for (Bytes a: attributes)
+                out.write(a.toByteArray());
+            out.flush();
+            return bytes.toByteArray();
+        } catch (IOException e) {
+            // cannot happen when writing to a byte array
+            throw new AssertionError(e);
+        }
+    }
+
+    /*
+     *  Reads the lines of an .innerclasses directive, each giving the inner class,
+     *  the outer class, the simple name and the flags.
+     */
+    private Bytes innerClasses() throws AsmException {
+        List<Bytes> entries = new ArrayList<>();
+        while (true) {
+            Tokenizer.Line line = nextLine();
+            if (line.accept(".end")) {
+                line.expect("innerclasses");
+                line.end();
+                break;
+            }
+            int inner = optClassRef(line.word());
+            int outer = optClassRef(line.word());
+            int name = line.accept("[0]") ? 0 : cp.utf8(line.word());
+            int flags = flags(line, words(line), INNER_CLASS_FLAGS);
+            entries.add(new Bytes().u2(inner).u2(outer).u2(name).u2(flags));
+        }
+        Bytes b = new Bytes().u2(entries.size());
+        for (Bytes e: entries)
+            b.bytes(e.toByteArray());
+        return attribute(Attribute.InnerClasses, b);
+    }
+
+    private Bytes classes(Tokenizer.Line line) throws AsmException {
+        List<String> names = words(line);
+        Bytes b = new Bytes().u2(names.size());
+        for (String name: names)
+            b.u2(cp.classRef(name));
+        return b;
+    }
+
+    /*
+     *  Reads a field: the flags, the name and the descriptor, then an optional
+     *  constant value, then optionally the directives of its other attributes.
+     */
+    private Bytes field(Tokenizer.Line line) throws AsmException {
+        List<Tokenizer.Token> tokens = line.tokens;
+        int end = 1;
+        while (end < tokens.size() && !(!tokens.get(end).quoted
+                && (tokens.get(end).text.equals("=") || tokens.get(end).text.equals(".fieldattributes"))))
+            end++;
+        if (end < 3)
+            throw line.error("missing field name or descriptor");
+        List<String> words = new ArrayList<>();
+        for (int i = 1; i < end; i++)
+            words.add(line.word());
+        int flags = flags(line, new ArrayList<>(words.subList(0, words.size() - 2)), FIELD_FLAGS);
+        String descriptor = words.get(words.size() - 1);
+        Bytes b = new Bytes().u2(flags).u2(cp.utf8(words.get(words.size() - 2))).u2(cp.utf8(descriptor));
+
+        List<Bytes> attrs = new ArrayList<>();
+        if (line.accept("=")) {
+            int value = constant(line);
+            attrs.add(attribute(Attribute.ConstantValue, new Bytes().u2(value)));
+        }
+        if (line.accept(".fieldattributes")) {
+            line.end();
+            while (true) {
+                Tokenizer.Line l = nextLine();
+                String directive = l.word();
+                switch (directive) {
+                    case ".signature":
+                        attrs.add(attribute(Attribute.Signature, new Bytes().u2(cp.utf8(l.word()))));
+                        break;
+                    case ".deprecated":
+                        attrs.add(attribute(Attribute.Deprecated, new Bytes()));
+                        break;
+                    case ".end":
+                        l.expect("fieldattributes");
+                        l.end();
+                        return member(b, attrs);
+                    default: {
+                        Bytes attr = attributeDirective(directive, l);
+                        if (attr == null)
+                            throw l.error("unknown field directive: " + directive);
+                        attrs.add(attr);
+                        continue;
+                    }
+                }
+                l.end();
+            }
+        }
+        line.end();
+        return member(b, attrs);
+    }
+
+    /*
+     *  Reads a method, from the .method directive to .end method.
+     */
+    private Bytes method(Tokenizer.Line line) throws AsmException {
+        List<String> words = new ArrayList<>();
+        while (!line.accept(":"))
+            words.add(line.word());
+        String descriptor = line.word();
+        line.end();
+        if (words.isEmpty())
+            throw line.error("missing method name");
+        String name = words.remove(words.size() - 1);
+        int flags = flags(line, words, METHOD_FLAGS);
+        Bytes b = new Bytes().u2(flags).u2(cp.utf8(name)).u2(cp.utf8(descriptor));
+
+        List<Bytes> attrs = new ArrayList<>();
+        List<Integer> exceptions = null;
+        int exceptionsIndex = -1;
+        while (true) {
+            Tokenizer.Line l = nextLine();
+            String directive = l.word();
+            switch (directive) {
+                case ".code":
+                    attrs.add(attribute(Attribute.Code,
+                            new CodeAssembler(this, flags, name, descriptor).assemble(l)));
+                    continue;
+                case ".throws":
+                    if (exceptions == null) {
+                        exceptions = new ArrayList<>();
+                        exceptionsIndex = attrs.size();
+                        attrs.add(null);
+                    }
+                    exceptions.add(cp.classRef(l.word()));
+                    break;
+                case ".signature":
+                    attrs.add(attribute(Attribute.Signature, new Bytes().u2(cp.utf8(l.word()))));
+                    break;
+                case ".deprecated":
+                    attrs.add(attribute(Attribute.Deprecated, new Bytes()));
+                    break;
+                case ".end":
+                    l.expect("method");
+                    l.end();
+                    if (exceptions != null) {
+                        Bytes e = new Bytes().u2(exceptions.size());
+                        for (int i: exceptions)
+                            e.u2(i);
+                        attrs.set(exceptionsIndex, attribute(Attribute.Exceptions, e));
+                    }
+                    return member(b, attrs);
+                default: {
+                    Bytes attr = attributeDirective(directive, l);
+                    if (attr == null)
+                        throw l.error("unknown method directive: " + directive);
+                    attrs.add(attr);
+                    continue;
+                }
+            }
+            l.end();
+        }
+    }
+
+    /*
+     *  Reads the directive of an attribute that is not special to a class, field
+     *  or method, and returns the attribute, or null if the directive is not one
+     *  of these. The lines of the directive, up to its .end directive if it has
+     *  one, are all read.
+     */
+    private Bytes attributeDirective(String directive, Tokenizer.Line line) throws AsmException {
+        switch (directive) {
+            case ".runtime":
+                return new AnnotationAssembler(this, this::nextLine, null).runtime(line);
+            case ".annotationdefault":
+                return new AnnotationAssembler(this, this::nextLine, null).annotationDefault(line);
+            case ".methodparameters":
+                line.end();
+                return methodParameters();
+            case ".record":
+                line.end();
+                return record();
+            case ".module":
+                return module(line);
+            case ".modulepackages": {
+                List<String> names = words(line);
+                Bytes b = new Bytes().u2(names.size());
+                for (String name: names)
+                    b.u2(cp.packageRef(name));
+                return attribute(Attribute.ModulePackages, b);
+            }
+            case ".modulemainclass": {
+                Bytes b = new Bytes().u2(cp.classRef(line.word()));
+                line.end();
+                return attribute(Attribute.ModuleMainClass, b);
+            }
+            case ".attribute":
+                return rawAttribute(line);
+            default:
+                return null;
+        }
+    }
+
+    /*
+     *  Reads an .attribute directive, after the directive itself: the name of an
+     *  attribute and its content, as a byte string.
+     */
+    Bytes rawAttribute(Tokenizer.Line line) throws AsmException {
+        String name = line.word();
+        Tokenizer.Token t = line.next();
+        if (!t.binary)
+            throw line.error("expected a byte string, found " + t);
+        line.end();
+        Bytes b = new Bytes();
+        for (int i = 0; i < t.text.length(); i++)
+            b.u1(t.text.charAt(i));
+        return attribute(name, b);
+    }
+
+    /*
+     *  Reads the lines of a .methodparameters directive, each giving the name
+     *  of a parameter, or [0], and its flags.
+     */
+    private Bytes methodParameters() throws AsmException {
+        List<Bytes> entries = new ArrayList<>();
+        while (true) {
+            Tokenizer.Line line = nextLine();
+            if (line.accept(".end")) {
+                line.expect("methodparameters");
+                line.end();
+                break;
+            }
+            int name = line.accept("[0]") ? 0 : cp.utf8(line.word());
+            entries.add(new Bytes().u2(name).u2(flags(line, words(line), PARAMETER_FLAGS)));
+        }
+        if (entries.size() > 0xff)
+            throw new AsmException(lines.get(pos - 1).number, "too many parameters: " + entries.size());
+        Bytes b = new Bytes().u1(entries.size());
+        for (Bytes e: entries)
+            b.bytes(e.toByteArray());
+        return attribute(Attribute.MethodParameters, b);
+    }
+
+    /*
+     *  Reads the lines of a .record directive, each giving the name and the
+     *  descriptor of a record component, then optionally the directives of its
+     *  attributes.
+     */
+    private Bytes record() throws AsmException {
+        List<Bytes> components = new ArrayList<>();
+        while (true) {
+            Tokenizer.Line line = nextLine();
+            if (line.accept(".end")) {
+                line.expect("record");
+                line.end();
+                break;
+            }
+            Bytes c = new Bytes().u2(cp.utf8(line.word())).u2(cp.utf8(line.word()));
+            List<Bytes> attrs = new ArrayList<>();
+            if (line.accept(".recordattributes")) {
+                line.end();
+                while (true) {
+                    Tokenizer.Line l = nextLine();
+                    String directive = l.word();
+                    if (directive.equals(".end")) {
+                        l.expect("recordattributes");
+                        l.end();
+                        break;
+                    } else if (directive.equals(".signature")) {
+                        attrs.add(attribute(Attribute.Signature, new Bytes().u2(cp.utf8(l.word()))));
+                        l.end();
+                    } else {
+                        Bytes attr = attributeDirective(directive, l);
+                        if (attr == null)
+                            throw l.error("unknown record component directive: " + directive);
+                        attrs.add(attr);
+                    }
+                }
+            } else {
+                line.end();
+            }
+            components.add(member(c, attrs));
+        }
+        Bytes b = new Bytes().u2(components.size());
+        for (Bytes c: components)
+            b.bytes(c.toByteArray());
+        return attribute(Attribute.Record, b);
+    }
+
+    /*
+     *  Reads a module, from the .module directive, which gives the flags, the
+     *  name and the version, to .end module. Each line in between is a .requires,
+     *  .exports, .opens, .uses or .provides directive.
+     */
+    private Bytes module(Tokenizer.Line line) throws AsmException {
+        List<String> words = new ArrayList<>();
+        while (!line.accept("version"))
+            words.add(line.word());
+        if (words.isEmpty())
+            throw line.error("missing module name");
+        int name = cp.moduleRef(words.remove(words.size() - 1));
+        int flags = flags(line, words, MODULE_FLAGS);
+        int version = line.accept("[0]") ? 0 : cp.utf8(line.word());
+        line.end();
+        Bytes b = new Bytes().u2(name).u2(flags).u2(version);
+
+        List<Bytes> requires = new ArrayList<>();
+        List<Bytes> exports = new ArrayList<>();
+        List<Bytes> opens = new ArrayList<>();
+        List<Bytes> uses = new ArrayList<>();
+        List<Bytes> provides = new ArrayList<>();
+        while (true) {
+            Tokenizer.Line l = nextLine();
+            String directive = l.word();
+            switch (directive) {
+                case ".requires": {
+                    List<String> w = new ArrayList<>();
+                    while (!l.accept("version"))
+                        w.add(l.word());
+                    if (w.isEmpty())
+                        throw l.error("missing module name");
+                    int m = cp.moduleRef(w.remove(w.size() - 1));
+                    int f = flags(l, w, REQUIRES_FLAGS);
+                    int v = l.accept("[0]") ? 0 : cp.utf8(l.word());
+                    requires.add(new Bytes().u2(m).u2(f).u2(v));
+                    break;
+                }
+                case ".exports":
+                    exports.add(exportsOrOpens(l));
+                    continue;
+                case ".opens":
+                    opens.add(exportsOrOpens(l));
+                    continue;
+                case ".uses":
+                    uses.add(new Bytes().u2(cp.classRef(l.word())));
+                    break;
+                case ".provides": {
+                    int c = cp.classRef(l.word());
+                    l.expect("with");
+                    List<String> with = words(l);
+                    Bytes p = new Bytes().u2(c).u2(with.size());
+                    for (String w: with)
+                        p.u2(cp.classRef(w));
+                    provides.add(p);
+                    continue;
+                }
+                case ".end":
+                    l.expect("module");
+                    l.end();
+                    for (List<Bytes> entries: List.of(requires, exports, opens, uses, provides)) {
+                        b.u2(entries.size());
+                        for (Bytes e: entries)
+                            b.bytes(e.toByteArray());
+                    }
+                    return attribute(Attribute.Module, b);
+                default:
+                    throw l.error("unknown module directive: " + directive);
+            }
+            l.end();
+        }
+    }
+
+    /*
+     *  Reads an .exports or .opens directive, after the directive itself: the
+     *  flags and the package, then optionally to and the modules.
+     */
+    private Bytes exportsOrOpens(Tokenizer.Line line) throws AsmException {
+        List<String> words = new ArrayList<>();
+        while (line.hasNext() && !line.accept("to"))
+            words.add(line.word());
+        List<String> to = words(line);
+        if (words.isEmpty())
+            throw line.error("missing package name");
+        int p = cp.packageRef(words.remove(words.size() - 1));
+        Bytes b = new Bytes().u2(p).u2(flags(line, words, EXPORTS_FLAGS)).u2(to.size());
+        for (String m: to)
+            b.u2(cp.moduleRef(m));
+        return b;
+    }
+
+    private static Bytes member(Bytes b, List<Bytes> attrs) {
+        b.u2(attrs.size());
+        for (Bytes a: attrs)
+            b.bytes(a.toByteArray());
+        return b;
+    }
+
+    Bytes attribute(String name, Bytes content) {
+        return new Bytes().u2(cp.utf8(name)).u4(content.length()).bytes(content.toByteArray());
+    }
+
+    /*
+     *  Reads a loadable constant, as for ldc or a ConstantValue attribute,
+     *  and returns its index in the constant pool.
+     */
+    int constant(Tokenizer.Line line) throws AsmException {
+        Tokenizer.Token t = line.next();
+        if (t.quoted)
+            return cp.string(t.text);
+        switch (t.text) {
+            case "Class":
+                return cp.classRef(line.word());
+            case "MethodType":
+                return cp.methodType(line.word());
+            case "MethodHandle":
+                return methodHandle(line);
+            case "Dynamic":
+                return dynamic(CONSTANT_Dynamic, line);
+            default:
+                return number(line, t.text);
+        }
+    }
+
+    private int number(Tokenizer.Line line, String s) throws AsmException {
+        try {
+            if (s.endsWith("L"))
+                return cp.longConstant(Long.parseLong(s.substring(0, s.length() - 1)));
+            if (s.endsWith("f")) {
+                String f = s.substring(0, s.length() - 1);
+                switch (f) {
+                    case "+Infinity":
+                        return cp.floatConstant(Float.POSITIVE_INFINITY);
+                    case "-Infinity":
+                        return cp.floatConstant(Float.NEGATIVE_INFINITY);
+                    case "+NaN":
+                        return cp.floatConstant(Float.NaN);
+                    default:
+                        return cp.floatConstant(Float.parseFloat(f));
+                }
+            }
+            switch (s) {
+                case "+Infinity":
+                    return cp.doubleConstant(Double.POSITIVE_INFINITY);
+                case "-Infinity":
+                    return cp.doubleConstant(Double.NEGATIVE_INFINITY);
+                case "+NaN":
+                    return cp.doubleConstant(Double.NaN);
+            }
+            if (s.indexOf('.') >= 0 || s.indexOf('e') >= 0 || s.indexOf('E') >= 0)
+                return cp.doubleConstant(Double.parseDouble(s));
+            return cp.integer(Integer.parseInt(s));
+        } catch (NumberFormatException e) {
+            throw line.error("bad constant: " + s);
+        }
+    }
+
+    /*
+     *  Reads a reference to a field or method: Field, Method or InterfaceMethod,
+     *  then the class, the name and the descriptor.
+     */
+    int memberRef(Tokenizer.Line line) throws AsmException {
+        String kind = line.word();
+        int tag;
+        switch (kind) {
+            case "Field":
+                tag = CONSTANT_Fieldref;
+                break;
+            case "Method":
+                tag = CONSTANT_Methodref;
+                break;
+            case "InterfaceMethod":
+                tag = CONSTANT_InterfaceMethodref;
+                break;
+            default:
+                throw line.error("expected Field, Method or InterfaceMethod, found " + kind);
+        }
+        return cp.memberRef(tag, line.word(), line.word(), line.word());
+    }
+
+    /*
+     *  Reads a method handle, after the MethodHandle keyword: the kind, such
+     *  as invokeStatic, then a reference to a field or method.
+     */
+    private int methodHandle(Tokenizer.Line line) throws AsmException {
+        String kind = line.word();
+        RefKind refKind;
+        try {
+            refKind = RefKind.valueOf("REF_" + kind);
+        } catch (IllegalArgumentException e) {
+            throw line.error("unknown method handle kind: " + kind);
+        }
+        return cp.methodHandle(refKind.tag, memberRef(line));
+    }
+
+    /*
+     *  Reads a dynamically-computed constant or call site, after the Dynamic or
+     *  InvokeDynamic keyword: the bootstrap method handle and its static arguments,
+     *  then the name and type after a colon.
+     */
+    int dynamic(int tag, Tokenizer.Line line) throws AsmException {
+        List<Integer> bsm = new ArrayList<>();
+        bsm.add(methodHandle(line));
+        while (!line.accept(":"))
+            bsm.add(constant(line));
+        int[] key = bsm.stream().mapToInt(Integer::intValue).toArray();
+        Integer index = bootstrapMethodIndexes.get(Arrays.toString(key));
+        if (index == null) {
+            index = bootstrapMethods.size();
+            bootstrapMethods.add(key);
+            bootstrapMethodIndexes.put(Arrays.toString(key), index);
+        }
+        return cp.dynamic(tag, index, line.word(), line.word());
+    }
+
+    private int optClassRef(String name) {
+        return name.equals("[0]") ? 0 : cp.classRef(name);
+    }
+
+    private static List<String> words(Tokenizer.Line line) throws AsmException {
+        List<String> words = new ArrayList<>();
+        while (line.hasNext())
+            words.add(line.word());
+        return words;
+    }
+
+    private static int flags(Tokenizer.Line line, List<String> words, Object[][] table) throws AsmException {
+        int flags = 0;
+        for (String w: words) {
+            int flag = 0;
+            for (Object[] entry: table) {
+                if (entry[1].equals(w))
+                    flag = (Integer) entry[0];
+            }
+            if (flag == 0)
+                throw line.error("unknown flag: " + w);
+            flags |= flag;
+        }
+        return flags;
+    }
+
+    Tokenizer.Line nextLine() throws AsmException {
+        if (pos >= lines.size())
+            throw new AsmException(lines.isEmpty() ? 0 : lines.get(lines.size() - 1).number,
+                    "unexpected end of file");
+        return lines.get(pos++);
+    }
+
+    ConstantPoolBuilder getConstantPool() {
+        return cp;
+    }
+
+    ClassHierarchy getClassHierarchy() {
+        return hierarchy;
+    }
+
+    String getClassName() {
+        return thisName;
+    }
+
+    boolean hasSuperclass() {
+        return superName != null;
+    }
+
+    int getMajorVersion() {
+        return major;
+    }
+
+    boolean getComputeMaxs() {
+        return computeMaxs;
+    }
+
+    boolean getComputeFrames() {
+        return computeFrames;
+    }
+
+    private static final Object[][] CLASS_FLAGS = {
+        { ACC_PUBLIC, "public" },
+        { ACC_FINAL, "final" },
+        { ACC_SUPER, "super" },
+        { ACC_INTERFACE, "interface" },
+        { ACC_ABSTRACT, "abstract" },
+        { ACC_SYNTHETIC, "synthetic" },
+        { ACC_ANNOTATION, "annotation" },
+        { ACC_ENUM, "enum" },
+        { ACC_MODULE, "module" }
+    };
+
+    private static final Object[][] INNER_CLASS_FLAGS = {
+        { ACC_PUBLIC, "public" },
+        { ACC_PRIVATE, "private" },
+        { ACC_PROTECTED, "protected" },
+        { ACC_STATIC, "static" },
+        { ACC_FINAL, "final" },
+        { ACC_INTERFACE, "interface" },
+        { ACC_ABSTRACT, "abstract" },
+        { ACC_SYNTHETIC, "synthetic" },
+        { ACC_ANNOTATION, "annotation" },
+        { ACC_ENUM, "enum" }
+    };
+
+    private static final Object[][] FIELD_FLAGS = {
+        { ACC_PUBLIC, "public" },
+        { ACC_PRIVATE, "private" },
+        { ACC_PROTECTED, "protected" },
+        { ACC_STATIC, "static" },
+        { ACC_FINAL, "final" },
+        { ACC_VOLATILE, "volatile" },
+        { ACC_TRANSIENT, "transient" },
+        { ACC_SYNTHETIC, "synthetic" },
+        { ACC_ENUM, "enum" }
+    };
+
+    private static final Object[][] PARAMETER_FLAGS = {
+        { ACC_FINAL, "final" },
+        { ACC_SYNTHETIC, "synthetic" },
+        { ACC_MANDATED, "mandated" }
+    };
+
+    private static final Object[][] MODULE_FLAGS = {
+        { Module_attribute.ACC_OPEN, "open" },
+        { ACC_SYNTHETIC, "synthetic" },
+        { ACC_MANDATED, "mandated" }
+    };
+
+    private static final Object[][] REQUIRES_FLAGS = {
+        { Module_attribute.ACC_TRANSITIVE, "transitive" },
+        { Module_attribute.ACC_STATIC_PHASE, "static_phase" },
+        { ACC_SYNTHETIC, "synthetic" },
+        { ACC_MANDATED, "mandated" }
+    };
+
+    private static final Object[][] EXPORTS_FLAGS = {
+        { ACC_SYNTHETIC, "synthetic" },
+        { ACC_MANDATED, "mandated" }
+    };
+
+    private static final Object[][] METHOD_FLAGS = {
+        { ACC_PUBLIC, "public" },
+        { ACC_PRIVATE, "private" },
+        { ACC_PROTECTED, "protected" },
+        { ACC_STATIC, "static" },
+        { ACC_FINAL, "final" },
+        { ACC_SYNCHRONIZED, "synchronized" },
+        { ACC_BRIDGE, "bridge" },
+        { ACC_VARARGS, "varargs" },
+        { ACC_NATIVE, "native" },
+        { ACC_ABSTRACT, "abstract" },
+        { ACC_STRICT, "strict" },
+        { ACC_SYNTHETIC, "synthetic" }
+    };
+
+    private final ClassHierarchy hierarchy;
+    private final boolean computeMaxs;
+    private final boolean computeFrames;
+
+    private List<Tokenizer.Line> lines;
+    private int pos;
+
+    // the class being assembled
+    private ConstantPoolBuilder cp;
+    private int major;
+    private int minor;
+    private int access;
+    private String thisName;
+    private String superName;
+    private List<Integer> interfaces;
+    private List<Bytes> fields;
+    private List<Bytes> methods;
+    private List<Bytes> attributes;
+    private List<int[]> bootstrapMethods;
+    private Map<String, Integer> bootstrapMethodIndexes;
+}
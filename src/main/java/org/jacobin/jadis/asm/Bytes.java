@@ -0,0 +1,85 @@
+/*
+ * Copyright (c) 2021 by Andrew Binstock.
+ *
+ * Portions of this file are copyright Oracle Corp.
+ * Those portions are licensed under GPL v. 2.0
+ * with the Oracle classpath exception. Due to the
+ * requirements of that license, the portions that
+ * are copyrighted by Andrew Binstock are licensed
+ * using the same terms and requirements.
+ *
+ */
+
+package org.jacobin.jadis.asm;
+
+import java.util.Arrays;
+
+/*
+ *  A growable array of bytes, written in the big-endian order of a class file.
+ *
+ *  <p><b>This is NOT part of any supported API.
+ *  If you write code that depends on this, you do so at your own risk.
+ *  This code and its internal interfaces are subject to change or
+ *  deletion without notice.</b>
+ */
+class Bytes {
+    Bytes u1(int b) {
+        ensure(1);
+        data[length++] = (byte) b;
+        return this;
+    }
+
+    Bytes u2(int s) {
+        ensure(2);
+        data[length++] = (byte) (s >> 8);
+        data[length++] = (byte) s;
+        return this;
+    }
+
+    Bytes u4(int i) {
+        ensure(4);
+        data[length++] = (byte) (i >> 24);
+        data[length++] = (byte) (i >> 16);
+        data[length++] = (byte) (i >> 8);
+        data[length++] = (byte) i;
+        return this;
+    }
+
+    Bytes bytes(byte[] b) {
+        ensure(b.length);
+        System.arraycopy(b, 0, data, length, b.length);
+        length += b.length;
+        return this;
+    }
+
+    /*
+     *  Overwrites a value that has already been written, such as a branch offset.
+     */
+    void put2(int pos, int s) {
+        data[pos] = (byte) (s >> 8);
+        data[pos + 1] = (byte) s;
+    }
+
+    void put4(int pos, int i) {
+        data[pos] = (byte) (i >> 24);
+        data[pos + 1] = (byte) (i >> 16);
+        data[pos + 2] = (byte) (i >> 8);
+        data[pos + 3] = (byte) i;
+    }
+
+    int length() {
+        return length;
+    }
+
+    byte[] toByteArray() {
+        return Arrays.copyOf(data, length);
+    }
+
+    private void ensure(int n) {
+        if (length + n > data.length)
+            data = Arrays.copyOf(data, Math.max(data.length * 2, length + n));
+    }
+
+    private byte[] data = new byte[64];
+    private int length;
+}
@@ -0,0 +1,160 @@
+/*
+ * Copyright (c) 2021 by Andrew Binstock.
+ *
+ * Portions of this file are copyright Oracle Corp.
+ * Those portions are licensed under GPL v. 2.0
+ * with the Oracle classpath exception. Due to the
+ * requirements of that license, the portions that
+ * are copyrighted by Andrew Binstock are licensed
+ * using the same terms and requirements.
+ *
+ */
+
+package org.jacobin.jadis.asm;
+
+import java.io.IOException;
+import java.io.InputStream;
+import java.util.ArrayList;
+import java.util.HashMap;
+import java.util.LinkedHashSet;
+import java.util.List;
+import java.util.Map;
+import java.util.Set;
+
+import org.jacobin.jadis.classfile.ClassFile;
+import org.jacobin.jadis.classfile.ConstantPoolException;
+
+/*
+ *  The superclasses of classes, as needed to merge types when computing stack
+ *  map frames, and to check the types in them. Classes that are being assembled
+ *  or verified are given by add; any other class is read by a finder, such as
+ *  one for a class loader, which normally searches the class path and the system
+ *  classes.
+ *
+ *  <p><b>This is NOT part of any supported API.
+ *  If you write code that depends on this, you do so at your own risk.
+ *  This code and its internal interfaces are subject to change or
+ *  deletion without notice.</b>
+ */
+public class ClassHierarchy {
+    /**
+     * Opens the class files of classes.
+     */
+    public interface Finder {
+        /**
+         * Opens the class file of a class.
+         * @param name the name of the class, in internal form
+         * @return the contents of the class file, or null if it cannot be found
+         * @throws IOException if the class file cannot be read
+         */
+        InputStream open(String name) throws IOException;
+    }
+
+    public ClassHierarchy(ClassLoader loader) {
+        this(name -> loader.getResourceAsStream(name + ".class"));
+    }
+
+    public ClassHierarchy(Finder finder) {
+        this.finder = finder;
+    }
+
+    /**
+     * Adds a class, as given by its superclass and whether it is an interface.
+     * @param name the name of the class
+     * @param superName the name of its superclass, or null for java/lang/Object
+     * @param isInterface whether the class is an interface
+     */
+    public void add(String name, String superName, boolean isInterface) {
+        classes.put(name, new Info(superName, isInterface));
+    }
+
+    /**
+     * Returns the names of the classes that could not be found, and were
+     * assumed to be direct subclasses of java/lang/Object.
+     * @return the names of the missing classes
+     */
+    public Set<String> getMissingClasses() {
+        return missing;
+    }
+
+    /*
+     *  Returns the nearest common superclass of two classes. As for the verifier
+     *  (JVMS 4.10.1.2), interfaces are treated as java/lang/Object.
+     */
+    public String commonSuperclass(String a, String b) {
+        if (a.equals(b))
+            return a;
+        if (isInterface(a) || isInterface(b))
+            return OBJECT;
+        List<String> supers = superclasses(a);
+        for (String s = b; s != null; s = superclass(s)) {
+            if (supers.contains(s))
+                return s;
+        }
+        return OBJECT;
+    }
+
+    /*
+     *  Whether a value of class a may be assigned to a variable of class b.
+     */
+    public boolean isSubclass(String a, String b) {
+        if (b.equals(OBJECT) || isInterface(b))
+            return true;
+        return superclasses(a).contains(b);
+    }
+
+    public boolean isInterface(String name) {
+        Info info = get(name);
+        return info != null && info.isInterface;
+    }
+
+    private List<String> superclasses(String name) {
+        List<String> list = new ArrayList<>();
+        for (String s = name; s != null && !list.contains(s); s = superclass(s))
+            list.add(s);
+        return list;
+    }
+
+    private String superclass(String name) {
+        if (name.equals(OBJECT))
+            return null;
+        Info info = get(name);
+        return (info == null || info.superName == null) ? OBJECT : info.superName;
+    }
+
+    private Info get(String name) {
+        if (name.startsWith("["))
+            return null;
+        if (classes.containsKey(name))
+            return classes.get(name);
+        Info info = null;
+        try (InputStream in = finder.open(name)) {
+            if (in != null) {
+                ClassFile cf = ClassFile.read(in);
+                info = new Info(cf.super_class == 0 ? null : cf.getSuperclassName(), cf.isInterface());
+            }
+        } catch (IOException | ConstantPoolException e) {
+            // treat as missing
+        }
+        if (info == null)
+            missing.add(name);
+        classes.put(name, info);
+        return info;
+    }
+
+    private static class Info {
+        Info(String superName, boolean isInterface) {
+            this.superName = superName;
+            this.isInterface = isInterface;
+        }
+
+        final String superName;
+        final boolean isInterface;
+    }
+
+    static final String OBJECT = "java/lang/Object";
+
+    private final Finder finder;
+    private final Map<String, Info> classes = new HashMap<>();
+    private final Set<String> missing = new LinkedHashSet<>();
+}
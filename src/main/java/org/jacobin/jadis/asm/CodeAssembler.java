@@ -0,0 +1,723 @@
+/*
+ * Copyright (c) 2021 by Andrew Binstock.
+ *
+ * Portions of this file are copyright Oracle Corp.
+ * Those portions are licensed under GPL v. 2.0
+ * with the Oracle classpath exception. Due to the
+ * requirements of that license, the portions that
+ * are copyrighted by Andrew Binstock are licensed
+ * using the same terms and requirements.
+ *
+ */
+
+package org.jacobin.jadis.asm;
+
+import java.util.ArrayList;
+import java.util.HashMap;
+import java.util.Iterator;
+import java.util.List;
+import java.util.Locale;
+import java.util.Map;
+import java.util.TreeMap;
+
+import org.jacobin.jadis.classfile.Attribute;
+import org.jacobin.jadis.classfile.Instruction;
+import org.jacobin.jadis.classfile.Opcode;
+
+import static org.jacobin.jadis.classfile.ConstantPool.*;
+import static org.jacobin.jadis.classfile.Opcode.*;
+import static org.jacobin.jadis.classfile.StackMapTable_attribute.verification_type_info.*;
+
+/*
+ *  Assembles the Code attribute of a method, from the .code directive to
+ *  .end code.
+ *
+ *  A label is defined by a word ending with a colon at the start of a line,
+ *  and is the offset of the instruction that follows it, if any, on the same
+ *  line or on a later line. The operands of branches, switches, the exception
+ *  table, the tables of line numbers and local variables, and the frames of
+ *  the stack map all refer to labels.
+ *
+ *  <p><b>This is NOT part of any supported API.
+ *  If you write code that depends on this, you do so at your own risk.
+ *  This code and its internal interfaces are subject to change or
+ *  deletion without notice.</b>
+ */
+class CodeAssembler {
+    /*
+     *  A branch offset or other use of a label that is resolved when all the
+     *  labels are known.
+     */
+    private static class Fixup {
+        Fixup(int pos, int base, int size, String label, Tokenizer.Line line) {
+            this.pos = pos;
+            this.base = base;
+            this.size = size;
+            this.label = label;
+            this.line = line;
+        }
+
+        final int pos;
+        final int base;
+        final int size;
+        final String label;
+        final Tokenizer.Line line;
+    }
+
+    /*
+     *  A .catch directive, which is resolved when all the labels are known.
+     */
+    private static class Catch {
+        Catch(Tokenizer.Line line, String type, String start, String end, String handler) {
+            this.line = line;
+            this.type = type;
+            this.start = start;
+            this.end = end;
+            this.handler = handler;
+        }
+
+        final Tokenizer.Line line;
+        final String type;
+        final String start;
+        final String end;
+        final String handler;
+    }
+
+    CodeAssembler(Assembler assembler, int access, String name, String descriptor) {
+        this.assembler = assembler;
+        this.access = access;
+        this.name = name;
+        this.descriptor = descriptor;
+        cp = assembler.getConstantPool();
+    }
+
+    /*
+     *  Reads the code, from the given .code directive to .end code, and returns
+     *  the content of the Code attribute.
+     */
+    Bytes assemble(Tokenizer.Line directive) throws AsmException {
+        int maxStack = -1;
+        int maxLocals = -1;
+        while (directive.hasNext()) {
+            if (directive.accept("stack"))
+                maxStack = directive.integer();
+            else if (directive.accept("locals"))
+                maxLocals = directive.integer();
+            else
+                throw directive.error("unexpected " + directive.peek());
+        }
+
+        while (true) {
+            Tokenizer.Line line = assembler.nextLine();
+            Tokenizer.Token first = line.tokens.get(0);
+            if (!first.quoted && first.text.length() > 1 && first.text.endsWith(":")
+                    && !first.text.startsWith(".")) {
+                line.next();
+                String label = first.text.substring(0, first.text.length() - 1);
+                if (labels.put(label, code.length()) != null)
+                    throw line.error("label " + label + " is defined more than once");
+                if (!line.hasNext())
+                    continue;
+            }
+            String word = line.peek();
+            switch (word) {
+                case ".catch": {
+                    line.next();
+                    String type = line.word();
+                    line.expect("from");
+                    String start = line.word();
+                    line.expect("to");
+                    String end = line.word();
+                    line.expect("using");
+                    handlers.add(new Catch(line, type, start, end, line.word()));
+                    break;
+                }
+                case ".stack":
+                    line.next();
+                    frames.add(frame(line));
+                    break;
+                case ".linenumbertable":
+                    line.next();
+                    line.end();
+                    table("linenumbertable", lineNumbers);
+                    continue;
+                case ".localvariabletable":
+                    line.next();
+                    line.end();
+                    table("localvariabletable", localVariables);
+                    continue;
+                case ".localvariabletypetable":
+                    line.next();
+                    line.end();
+                    table("localvariabletypetable", localVariableTypes);
+                    continue;
+                case ".runtime":
+                    line.next();
+                    runtime(line);
+                    continue;
+                case ".attribute":
+                    line.next();
+                    rawAttributes.add(assembler.rawAttribute(line));
+                    continue;
+                case ".end":
+                    line.next();
+                    line.expect("code");
+                    line.end();
+                    return finish(maxStack, maxLocals);
+                default:
+                    if (word.startsWith("."))
+                        throw line.error("unknown code directive: " + word);
+                    instruction(line);
+            }
+            line.end();
+        }
+    }
+
+    /*
+     *  Reads the lines of a .runtime directive, which gives the type annotations
+     *  of the code, up to .end runtime. The lines are kept to be read when the
+     *  labels are known.
+     */
+    private void runtime(Tokenizer.Line directive) throws AsmException {
+        List<Tokenizer.Line> block = new ArrayList<>();
+        block.add(directive);
+        while (true) {
+            Tokenizer.Line line = assembler.nextLine();
+            boolean end = line.accept(".end") && line.accept("runtime");
+            line.rewind();
+            block.add(line);
+            if (end)
+                break;
+        }
+        annotations.add(block);
+    }
+
+    /*
+     *  Reads the lines of a table, up to its .end directive. The lines are
+     *  kept to be read when the labels are known.
+     */
+    private void table(String name, List<Tokenizer.Line> entries) throws AsmException {
+        while (true) {
+            Tokenizer.Line line = assembler.nextLine();
+            if (line.accept(".end")) {
+                line.expect(name);
+                line.end();
+                return;
+            }
+            entries.add(line);
+        }
+    }
+
+    private void instruction(Tokenizer.Line line) throws AsmException {
+        String mnemonic = line.word();
+        Opcode opcode;
+        if (mnemonic.equals("wide")) {
+            mnemonic = line.word();
+            opcode = OPCODES.get(mnemonic + "_w");
+            if (opcode == null || (opcode.opcode >> 8) != WIDE)
+                throw line.error(mnemonic + " cannot be modified by wide");
+        } else {
+            opcode = OPCODES.get(mnemonic);
+            if (opcode == null || (opcode.opcode >> 8) != 0)
+                throw line.error("unknown instruction: " + mnemonic);
+        }
+        int pc = code.length();
+        pcLines.put(pc, line.number);
+
+        switch (opcode.kind) {
+            case NO_OPERANDS:
+                code.u1(opcode.opcode);
+                break;
+            case ATYPE: {
+                String type = line.word();
+                for (Instruction.TypeKind kind: Instruction.TypeKind.values()) {
+                    if (kind.name.equals(type)) {
+                        code.u1(opcode.opcode).u1(kind.value);
+                        return;
+                    }
+                }
+                throw line.error("unknown array type: " + type);
+            }
+            case BRANCH:
+                code.u1(opcode.opcode);
+                branch(line, pc, 2);
+                break;
+            case BRANCH_W:
+                code.u1(opcode.opcode);
+                branch(line, pc, 4);
+                break;
+            case BYTE:
+                code.u1(opcode.opcode).u1(value(line, Byte.MIN_VALUE, Byte.MAX_VALUE));
+                break;
+            case SHORT:
+                code.u1(opcode.opcode).u2(value(line, Short.MIN_VALUE, Short.MAX_VALUE));
+                break;
+            case CPREF: {
+                // ldc
+                int index = assembler.constant(line);
+                int tag = cp.get(index).tag;
+                if (tag == CONSTANT_Long || tag == CONSTANT_Double)
+                    throw line.error("ldc2_w is needed for a long or double constant");
+                if (index > 0xff)
+                    code.u1(LDC_W.opcode).u2(index);
+                else
+                    code.u1(opcode.opcode).u1(index);
+                break;
+            }
+            case CPREF_W:
+                code.u1(opcode.opcode).u2(operand(line, opcode));
+                break;
+            case CPREF_W_UBYTE:
+                // multianewarray
+                code.u1(opcode.opcode).u2(cp.classRef(line.word())).u1(value(line, 1, 0xff));
+                break;
+            case CPREF_W_UBYTE_ZERO:
+                if (opcode == INVOKEDYNAMIC) {
+                    line.expect("InvokeDynamic");
+                    code.u1(opcode.opcode).u2(assembler.dynamic(CONSTANT_InvokeDynamic, line)).u2(0);
+                } else {
+                    int index = assembler.memberRef(line);
+                    int count = line.hasNext()
+                            ? value(line, 1, 0xff)
+                            : 1 + slots(FrameComputer.argumentTypes(cp.get(index).strings[2]));
+                    code.u1(opcode.opcode).u2(index).u1(count).u1(0);
+                }
+                break;
+            case LOCAL: {
+                int index = value(line, 0, 0xffff);
+                if (index > 0xff)
+                    code.u1(WIDE).u1(opcode.opcode).u2(index);
+                else
+                    code.u1(opcode.opcode).u1(index);
+                break;
+            }
+            case LOCAL_BYTE: {
+                // iinc
+                int index = value(line, 0, 0xffff);
+                int value = value(line, Short.MIN_VALUE, Short.MAX_VALUE);
+                if (index > 0xff || value < Byte.MIN_VALUE || value > Byte.MAX_VALUE)
+                    code.u1(WIDE).u1(opcode.opcode).u2(index).u2(value);
+                else
+                    code.u1(opcode.opcode).u1(index).u1(value);
+                break;
+            }
+            case WIDE_LOCAL:
+                code.u1(WIDE).u1(opcode.opcode).u2(value(line, 0, 0xffff));
+                break;
+            case WIDE_LOCAL_SHORT:
+                code.u1(WIDE).u1(opcode.opcode).u2(value(line, 0, 0xffff))
+                        .u2(value(line, Short.MIN_VALUE, Short.MAX_VALUE));
+                break;
+            case DYNAMIC:
+                if (opcode == TABLESWITCH)
+                    tableSwitch(line, pc);
+                else
+                    lookupSwitch(line, pc);
+                break;
+            default:
+                throw line.error("unknown instruction: " + mnemonic);
+        }
+    }
+
+    /*
+     *  Returns the constant pool index for the operand of an instruction with
+     *  a 2-byte index.
+     */
+    private int operand(Tokenizer.Line line, Opcode opcode) throws AsmException {
+        switch (opcode) {
+            case LDC_W: {
+                int index = assembler.constant(line);
+                int tag = cp.get(index).tag;
+                if (tag == CONSTANT_Long || tag == CONSTANT_Double)
+                    throw line.error("ldc2_w is needed for a long or double constant");
+                return index;
+            }
+            case LDC2_W: {
+                int index = assembler.constant(line);
+                int tag = cp.get(index).tag;
+                if (tag != CONSTANT_Long && tag != CONSTANT_Double
+                        && !(tag == CONSTANT_Dynamic && "JD".indexOf(cp.get(index).strings[1].charAt(0)) >= 0))
+                    throw line.error("ldc2_w needs a long or double constant");
+                return index;
+            }
+            case NEW:
+            case ANEWARRAY:
+            case CHECKCAST:
+            case INSTANCEOF:
+                return cp.classRef(line.word());
+            default:
+                return assembler.memberRef(line);
+        }
+    }
+
+    /*
+     *  Reads tableswitch: the low value, and optionally the high value, then one
+     *  line for each target, then a line giving the default target.
+     */
+    private void tableSwitch(Tokenizer.Line line, int pc) throws AsmException {
+        int low = line.integer();
+        Integer high = line.hasNext() ? line.integer() : null;
+        line.end();
+        List<Tokenizer.Line> targets = new ArrayList<>();
+        Tokenizer.Line defaultLine;
+        while (true) {
+            Tokenizer.Line l = assembler.nextLine();
+            if (l.accept("default")) {
+                l.expect(":");
+                defaultLine = l;
+                break;
+            }
+            targets.add(l);
+        }
+        if (targets.isEmpty())
+            throw line.error("tableswitch has no targets");
+        if (high != null && high - low + 1 != targets.size())
+            throw line.error("tableswitch has " + targets.size() + " targets, not " + (high - low + 1));
+
+        code.u1(TABLESWITCH.opcode);
+        while (code.length() % 4 != 0)
+            code.u1(0);
+        branch(defaultLine, pc, 4);
+        defaultLine.end();
+        code.u4(low).u4(low + targets.size() - 1);
+        for (Tokenizer.Line l: targets) {
+            branch(l, pc, 4);
+            l.end();
+        }
+    }
+
+    /*
+     *  Reads lookupswitch: one line for each match and its target, then a line
+     *  giving the default target. The matches are sorted, as they must be in the
+     *  class file.
+     */
+    private void lookupSwitch(Tokenizer.Line line, int pc) throws AsmException {
+        line.end();
+        TreeMap<Integer, Tokenizer.Line> pairs = new TreeMap<>();
+        Tokenizer.Line defaultLine;
+        while (true) {
+            Tokenizer.Line l = assembler.nextLine();
+            if (l.accept("default")) {
+                l.expect(":");
+                defaultLine = l;
+                break;
+            }
+            int match = l.integer();
+            l.expect(":");
+            if (pairs.put(match, l) != null)
+                throw l.error("lookupswitch has more than one target for " + match);
+        }
+
+        code.u1(LOOKUPSWITCH.opcode);
+        while (code.length() % 4 != 0)
+            code.u1(0);
+        branch(defaultLine, pc, 4);
+        defaultLine.end();
+        code.u4(pairs.size());
+        for (Map.Entry<Integer, Tokenizer.Line> e: pairs.entrySet()) {
+            code.u4(e.getKey());
+            branch(e.getValue(), pc, 4);
+            e.getValue().end();
+        }
+    }
+
+    /*
+     *  Reads a label, and writes a placeholder for its offset from the
+     *  given instruction.
+     */
+    private void branch(Tokenizer.Line line, int pc, int size) throws AsmException {
+        fixups.add(new Fixup(code.length(), pc, size, line.word(), line));
+        if (size == 2)
+            code.u2(0);
+        else
+            code.u4(0);
+    }
+
+    /*
+     *  Reads a .stack directive, for a frame at the current offset.
+     */
+    private Frame frame(Tokenizer.Line line) throws AsmException {
+        int pc = code.length();
+        String kind = line.word();
+        switch (kind) {
+            case "same":
+            case "same_extended":
+                return new Frame(pc, kind, 0, List.of(), List.of(), line.number);
+            case "stack_1":
+            case "stack_1_extended":
+                return new Frame(pc, kind, 0, List.of(), List.of(type(line)), line.number);
+            case "chop": {
+                int chop = line.integer();
+                if (chop < 1 || chop > 3)
+                    throw line.error("chop must remove from 1 to 3 locals");
+                return new Frame(pc, kind, chop, List.of(), List.of(), line.number);
+            }
+            case "append": {
+                List<VType> locals = new ArrayList<>();
+                while (line.hasNext())
+                    locals.add(type(line));
+                if (locals.isEmpty() || locals.size() > 3)
+                    throw line.error("append must add from 1 to 3 locals");
+                return new Frame(pc, kind, 0, locals, List.of(), line.number);
+            }
+            case "full": {
+                line.end();
+                List<VType> locals = new ArrayList<>();
+                List<VType> stack = new ArrayList<>();
+                while (true) {
+                    Tokenizer.Line l = assembler.nextLine();
+                    List<VType> types;
+                    if (l.accept("locals")) {
+                        types = locals;
+                    } else if (l.accept("stack")) {
+                        types = stack;
+                    } else {
+                        l.expect(".end");
+                        l.expect("stack");
+                        l.end();
+                        break;
+                    }
+                    while (l.hasNext())
+                        types.add(type(l));
+                }
+                return new Frame(pc, kind, 0, locals, stack, line.number);
+            }
+            default:
+                throw line.error("unknown frame type: " + kind);
+        }
+    }
+
+    private static VType type(Tokenizer.Line line) throws AsmException {
+        String t = line.word();
+        switch (t) {
+            case "Top":
+                return VType.TOP;
+            case "Integer":
+                return VType.INTEGER;
+            case "Float":
+                return VType.FLOAT;
+            case "Long":
+                return VType.LONG;
+            case "Double":
+                return VType.DOUBLE;
+            case "Null":
+                return VType.NULL;
+            case "UninitializedThis":
+                return VType.UNINITIALIZED_THIS;
+            case "Object":
+                return VType.object(line.word());
+            case "Uninitialized":
+                return VType.uninitialized(line.word());
+            default:
+                throw line.error("unknown verification type: " + t);
+        }
+    }
+
+    /*
+     *  Resolves the labels, computes what is to be computed, and returns the
+     *  content of the Code attribute.
+     */
+    private Bytes finish(int maxStack, int maxLocals) throws AsmException {
+        if (code.length() == 0 || code.length() > 0xffff)
+            throw new AsmException(pcLines.isEmpty() ? 0 : pcLines.values().iterator().next(),
+                    "the code of " + name + " must be from 1 to 65535 bytes long, not " + code.length());
+        for (Fixup f: fixups) {
+            int offset = label(f.line, f.label) - f.base;
+            if (f.size == 2) {
+                if (offset < Short.MIN_VALUE || offset > Short.MAX_VALUE)
+                    throw f.line.error("branch to " + f.label + " is too far; use goto_w");
+                code.put2(f.pos, offset);
+            } else {
+                code.put4(f.pos, offset);
+            }
+        }
+        byte[] bytes = code.toByteArray();
+
+        List<FrameComputer.Handler> table = new ArrayList<>();
+        for (Catch c: handlers) {
+            table.add(new FrameComputer.Handler(label(c.line, c.start), label(c.line, c.end),
+                    label(c.line, c.handler), c.type.equals("[0]") ? null : c.type));
+        }
+
+        boolean computeFrames = assembler.getComputeFrames() && assembler.getMajorVersion() >= 50;
+        boolean computeMaxs = assembler.getComputeMaxs() || maxStack < 0 || maxLocals < 0;
+        List<Frame> stackMap = frames;
+        if (computeMaxs || computeFrames) {
+            FrameComputer fc = new FrameComputer(cp, assembler.getClassHierarchy(),
+                    assembler.getClassName(), assembler.hasSuperclass());
+            fc.compute(access, name, descriptor, bytes, table, pcLines, computeFrames);
+            if (assembler.getComputeMaxs() || maxStack < 0)
+                maxStack = fc.getMaxStack();
+            if (assembler.getComputeMaxs() || maxLocals < 0)
+                maxLocals = fc.getMaxLocals();
+            if (computeFrames)
+                stackMap = fc.getFrames();
+        }
+
+        Bytes b = new Bytes().u2(maxStack).u2(maxLocals).u4(bytes.length).bytes(bytes);
+        b.u2(table.size());
+        for (FrameComputer.Handler h: table)
+            b.u2(h.start).u2(h.end).u2(h.handler).u2(h.type == null ? 0 : cp.classRef(h.type));
+
+        List<Bytes> attrs = new ArrayList<>();
+        if (!lineNumbers.isEmpty()) {
+            Bytes a = new Bytes().u2(lineNumbers.size());
+            for (Tokenizer.Line l: lineNumbers) {
+                a.u2(label(l, l.word())).u2(l.integer());
+                l.end();
+            }
+            attrs.add(assembler.attribute(Attribute.LineNumberTable, a));
+        }
+        if (!localVariables.isEmpty())
+            attrs.add(assembler.attribute(Attribute.LocalVariableTable, localVariables(localVariables)));
+        if (!localVariableTypes.isEmpty())
+            attrs.add(assembler.attribute(Attribute.LocalVariableTypeTable, localVariables(localVariableTypes)));
+        if (!stackMap.isEmpty())
+            attrs.add(assembler.attribute(Attribute.StackMapTable, stackMapTable(stackMap)));
+        for (List<Tokenizer.Line> block: annotations) {
+            Iterator<Tokenizer.Line> lines = block.iterator();
+            Tokenizer.Line directive = lines.next();
+            attrs.add(new AnnotationAssembler(assembler, lines::next, this::label).runtime(directive));
+        }
+        attrs.addAll(rawAttributes);
+        b.u2(attrs.size());
+        for (Bytes a: attrs)
+            b.bytes(a.toByteArray());
+        return b;
+    }
+
+    /*
+     *  Returns the entries of a LocalVariableTable or LocalVariableTypeTable,
+     *  each given as: index is name descriptor-or-signature from label to label.
+     */
+    private Bytes localVariables(List<Tokenizer.Line> entries) throws AsmException {
+        Bytes b = new Bytes().u2(entries.size());
+        for (Tokenizer.Line l: entries) {
+            int index = value(l, 0, 0xffff);
+            l.expect("is");
+            int name = cp.utf8(l.word());
+            int type = cp.utf8(l.word());
+            l.expect("from");
+            int start = label(l, l.word());
+            l.expect("to");
+            int end = label(l, l.word());
+            l.end();
+            b.u2(start).u2(end - start).u2(name).u2(type).u2(index);
+        }
+        return b;
+    }
+
+    private Bytes stackMapTable(List<Frame> frames) throws AsmException {
+        Bytes b = new Bytes().u2(frames.size());
+        int previous = -1;
+        for (Frame f: frames) {
+            if (f.pc <= previous)
+                throw new AsmException(f.line, "more than one frame at offset " + f.pc);
+            int delta = (previous < 0) ? f.pc : f.pc - previous - 1;
+            previous = f.pc;
+            switch (f.kind) {
+                case "same":
+                    if (delta <= 63)
+                        b.u1(delta);
+                    else
+                        b.u1(251).u2(delta);
+                    break;
+                case "same_extended":
+                    b.u1(251).u2(delta);
+                    break;
+                case "stack_1":
+                    if (delta <= 63)
+                        b.u1(64 + delta);
+                    else
+                        b.u1(247).u2(delta);
+                    types(b, f.stack, f);
+                    break;
+                case "stack_1_extended":
+                    b.u1(247).u2(delta);
+                    types(b, f.stack, f);
+                    break;
+                case "chop":
+                    b.u1(251 - f.chop).u2(delta);
+                    break;
+                case "append":
+                    b.u1(251 + f.locals.size()).u2(delta);
+                    types(b, f.locals, f);
+                    break;
+                default:
+                    b.u1(255).u2(delta).u2(f.locals.size());
+                    types(b, f.locals, f);
+                    b.u2(f.stack.size());
+                    types(b, f.stack, f);
+            }
+        }
+        return b;
+    }
+
+    private void types(Bytes b, List<VType> types, Frame f) throws AsmException {
+        for (VType t: types) {
+            b.u1(t.tag);
+            if (t.tag == ITEM_Object) {
+                b.u2(cp.classRef(t.name));
+            } else if (t.tag == ITEM_Uninitialized) {
+                if (t.name == null) {
+                    b.u2(t.offset);
+                } else {
+                    Integer offset = labels.get(t.name);
+                    if (offset == null)
+                        throw new AsmException(f.line, "undefined label: " + t.name);
+                    b.u2(offset);
+                }
+            }
+        }
+    }
+
+    private int label(Tokenizer.Line line, String label) throws AsmException {
+        Integer offset = labels.get(label);
+        if (offset == null)
+            throw line.error("undefined label: " + label);
+        return offset;
+    }
+
+    private static int value(Tokenizer.Line line, int min, int max) throws AsmException {
+        int v = line.integer();
+        if (v < min || v > max)
+            throw line.error("value " + v + " is out of range: it must be from " + min + " to " + max);
+        return v;
+    }
+
+    private static int slots(List<VType> types) {
+        int n = 0;
+        for (VType t: types)
+            n += t.isCategory2() ? 2 : 1;
+        return n;
+    }
+
+    /*
+     *  The opcodes, by mnemonic. The PicoJava opcodes are not included.
+     */
+    private static final Map<String, Opcode> OPCODES = new HashMap<>();
+    static {
+        for (Opcode o: Opcode.values()) {
+            if (o.set == Opcode.Set.STANDARD)
+                OPCODES.put(o.name().toLowerCase(Locale.US), o);
+        }
+    }
+
+    private final Assembler assembler;
+    private final ConstantPoolBuilder cp;
+    private final int access;
+    private final String name;
+    private final String descriptor;
+
+    private final Bytes code = new Bytes();
+    private final Map<String, Integer> labels = new HashMap<>();
+    private final List<Fixup> fixups = new ArrayList<>();
+    private final List<Catch> handlers = new ArrayList<>();
+    private final List<Frame> frames = new ArrayList<>();
+    private final List<Tokenizer.Line> lineNumbers = new ArrayList<>();
+    private final List<Tokenizer.Line> localVariables = new ArrayList<>();
+    private final List<Tokenizer.Line> localVariableTypes = new ArrayList<>();
+    private final List<List<Tokenizer.Line>> annotations = new ArrayList<>();
+    private final List<Bytes> rawAttributes = new ArrayList<>();
+    private final Map<Integer, Integer> pcLines = new HashMap<>();
+}
@@ -0,0 +1,192 @@
+/*
+ * Copyright (c) 2021 by Andrew Binstock.
+ *
+ * Portions of this file are copyright Oracle Corp.
+ * Those portions are licensed under GPL v. 2.0
+ * with the Oracle classpath exception. Due to the
+ * requirements of that license, the portions that
+ * are copyrighted by Andrew Binstock are licensed
+ * using the same terms and requirements.
+ *
+ */
+
+package org.jacobin.jadis.asm;
+
+import java.io.DataOutputStream;
+import java.io.IOException;
+import java.util.ArrayList;
+import java.util.HashMap;
+import java.util.List;
+import java.util.Map;
+
+import static org.jacobin.jadis.classfile.ConstantPool.*;
+
+/*
+ *  Builds the constant pool of a class file. Each entry is added once, the
+ *  first time it is needed, and is given the next free index.
+ *
+ *  <p><b>This is NOT part of any supported API.
+ *  If you write code that depends on this, you do so at your own risk.
+ *  This code and its internal interfaces are subject to change or
+ *  deletion without notice.</b>
+ */
+class ConstantPoolBuilder {
+    /*
+     *  An entry in the pool. The strings are the names and descriptors to which
+     *  the entry refers, directly or indirectly: the string of a Utf8, Class,
+     *  String, MethodType, Module or Package entry; the class, name and type of
+     *  a member reference; the name and type of a NameAndType, Dynamic or
+     *  InvokeDynamic entry.
+     */
+    static class Entry {
+        Entry(int tag, Object value, int[] indexes, String... strings) {
+            this.tag = tag;
+            this.value = value;
+            this.indexes = indexes;
+            this.strings = strings;
+        }
+
+        final int tag;
+        final Object value;         // for numeric constants
+        final int[] indexes;        // the entries to which this entry refers, as written
+        final String[] strings;
+    }
+
+    int utf8(String s) {
+        return add("U" + s, new Entry(CONSTANT_Utf8, null, new int[0], s));
+    }
+
+    int classRef(String name) {
+        int n = utf8(name);
+        return add("C" + name, new Entry(CONSTANT_Class, null, new int[] { n }, name));
+    }
+
+    int moduleRef(String name) {
+        int n = utf8(name);
+        return add("M" + name, new Entry(CONSTANT_Module, null, new int[] { n }, name));
+    }
+
+    int packageRef(String name) {
+        int n = utf8(name);
+        return add("P" + name, new Entry(CONSTANT_Package, null, new int[] { n }, name));
+    }
+
+    int string(String s) {
+        int n = utf8(s);
+        return add("S" + s, new Entry(CONSTANT_String, null, new int[] { n }, s));
+    }
+
+    int integer(int i) {
+        return add("I" + i, new Entry(CONSTANT_Integer, i, new int[0]));
+    }
+
+    int floatConstant(float f) {
+        return add("F" + Float.floatToRawIntBits(f), new Entry(CONSTANT_Float, f, new int[0]));
+    }
+
+    int longConstant(long l) {
+        return add("J" + l, new Entry(CONSTANT_Long, l, new int[0]));
+    }
+
+    int doubleConstant(double d) {
+        return add("D" + Double.doubleToRawLongBits(d), new Entry(CONSTANT_Double, d, new int[0]));
+    }
+
+    int nameAndType(String name, String type) {
+        int n = utf8(name);
+        int t = utf8(type);
+        return add("N" + name + " " + type, new Entry(CONSTANT_NameAndType, null, new int[] { n, t }, name, type));
+    }
+
+    /*
+     *  Adds a Fieldref, Methodref or InterfaceMethodref entry.
+     */
+    int memberRef(int tag, String cls, String name, String type) {
+        int c = classRef(cls);
+        int nt = nameAndType(name, type);
+        return add(tag + ":" + cls + " " + name + " " + type,
+                new Entry(tag, null, new int[] { c, nt }, cls, name, type));
+    }
+
+    int methodType(String descriptor) {
+        int d = utf8(descriptor);
+        return add("T" + descriptor, new Entry(CONSTANT_MethodType, null, new int[] { d }, descriptor));
+    }
+
+    int methodHandle(int kind, int ref) {
+        return add("H" + kind + ":" + ref, new Entry(CONSTANT_MethodHandle, kind, new int[] { ref }));
+    }
+
+    /*
+     *  Adds a Dynamic or InvokeDynamic entry.
+     */
+    int dynamic(int tag, int bootstrapMethod, String name, String type) {
+        int nt = nameAndType(name, type);
+        return add(tag + ":" + bootstrapMethod + " " + name + " " + type,
+                new Entry(tag, bootstrapMethod, new int[] { nt }, name, type));
+    }
+
+    Entry get(int index) {
+        return entries.get(index);
+    }
+
+    int size() {
+        return entries.size();
+    }
+
+    void write(DataOutputStream out) throws IOException {
+        out.writeShort(entries.size());
+        for (int i = 1; i < entries.size(); i++) {
+            Entry e = entries.get(i);
+            if (e == null)
+                continue;   // the second slot of a long or double
+            out.writeByte(e.tag);
+            switch (e.tag) {
+                case CONSTANT_Utf8:
+                    out.writeUTF(e.strings[0]);
+                    break;
+                case CONSTANT_Integer:
+                    out.writeInt((Integer) e.value);
+                    break;
+                case CONSTANT_Float:
+                    out.writeFloat((Float) e.value);
+                    break;
+                case CONSTANT_Long:
+                    out.writeLong((Long) e.value);
+                    break;
+                case CONSTANT_Double:
+                    out.writeDouble((Double) e.value);
+                    break;
+                case CONSTANT_MethodHandle:
+                    out.writeByte((Integer) e.value);
+                    out.writeShort(e.indexes[0]);
+                    break;
+                case CONSTANT_Dynamic:
+                case CONSTANT_InvokeDynamic:
+                    out.writeShort((Integer) e.value);
+                    out.writeShort(e.indexes[0]);
+                    break;
+                default:
+                    for (int index: e.indexes)
+                        out.writeShort(index);
+            }
+        }
+    }
+
+    private int add(String key, Entry entry) {
+        Integer index = map.get(key);
+        if (index != null)
+            return index;
+        index = entries.size();
+        entries.add(entry);
+        if (entry.tag == CONSTANT_Long || entry.tag == CONSTANT_Double)
+            entries.add(null);
+        if (entries.size() > 0xffff)
+            throw new IllegalStateException("too many constants");
+        map.put(key, index);
+        return index;
+    }
+
+    private final List<Entry> entries = new ArrayList<>(List.of(new Entry(0, null, new int[0])));
+    private final Map<String, Integer> map = new HashMap<>();
+}
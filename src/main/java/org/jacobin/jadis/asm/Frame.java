@@ -0,0 +1,43 @@
+/*
+ * Copyright (c) 2021 by Andrew Binstock.
+ *
+ * Portions of this file are copyright Oracle Corp.
+ * Those portions are licensed under GPL v. 2.0
+ * with the Oracle classpath exception. Due to the
+ * requirements of that license, the portions that
+ * are copyrighted by Andrew Binstock are licensed
+ * using the same terms and requirements.
+ *
+ */
+
+package org.jacobin.jadis.asm;
+
+import java.util.List;
+
+/*
+ *  A stack map frame, as given by a .stack directive or as computed.
+ *  The locals are given as in the class file, with one entry for a long
+ *  or double; the kind is the name used by the .stack directive.
+ *
+ *  <p><b>This is NOT part of any supported API.
+ *  If you write code that depends on this, you do so at your own risk.
+ *  This code and its internal interfaces are subject to change or
+ *  deletion without notice.</b>
+ */
+class Frame {
+    Frame(int pc, String kind, int chop, List<VType> locals, List<VType> stack, int line) {
+        this.pc = pc;
+        this.kind = kind;
+        this.chop = chop;
+        this.locals = locals;
+        this.stack = stack;
+        this.line = line;
+    }
+
+    final int pc;
+    final String kind;          // same, same_extended, stack_1, stack_1_extended, chop, append or full
+    final int chop;             // for chop: the number of locals removed
+    final List<VType> locals;   // for append: the locals added; for full: all the locals
+    final List<VType> stack;    // for stack_1, stack_1_extended and full
+    final int line;
+}
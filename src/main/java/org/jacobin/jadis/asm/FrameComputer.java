@@ -0,0 +1,853 @@
+/*
+ * Copyright (c) 2021 by Andrew Binstock.
+ *
+ * Portions of this file are copyright Oracle Corp.
+ * Those portions are licensed under GPL v. 2.0
+ * with the Oracle classpath exception. Due to the
+ * requirements of that license, the portions that
+ * are copyrighted by Andrew Binstock are licensed
+ * using the same terms and requirements.
+ *
+ */
+
+package org.jacobin.jadis.asm;
+
+import java.util.ArrayDeque;
+import java.util.ArrayList;
+import java.util.Deque;
+import java.util.HashMap;
+import java.util.HashSet;
+import java.util.List;
+import java.util.Map;
+import java.util.Set;
+import java.util.TreeMap;
+import java.util.TreeSet;
+
+import org.jacobin.jadis.classfile.Instruction;
+import org.jacobin.jadis.classfile.Opcode;
+
+import static org.jacobin.jadis.classfile.AccessFlags.ACC_STATIC;
+import static org.jacobin.jadis.classfile.ConstantPool.*;
+import static org.jacobin.jadis.classfile.StackMapTable_attribute.verification_type_info.*;
+
+/*
+ *  Computes max_stack and max_locals for the code of a method, and optionally
+ *  the frames of its StackMapTable attribute.
+ *
+ *  The types of the locals and of the operand stack are found by data-flow
+ *  analysis, as by the type-inferring verifier (JVMS 4.10.2). Where two paths
+ *  meet, two class types are merged to their nearest common superclass, as
+ *  given by the class hierarchy; interfaces are treated as java/lang/Object.
+ *  Frames are given at the targets of branches, at exception handlers, and
+ *  after unconditional transfers of control, and are written in the shortest
+ *  form, as javac does.
+ *
+ *  When only the maximums are wanted, types are merged without the class
+ *  hierarchy, and jsr and ret are allowed; when frames are wanted, jsr and ret
+ *  are errors, as they are for class files of version 50 and later, and so is
+ *  any code that cannot be reached.
+ *
+ *  <p><b>This is NOT part of any supported API.
+ *  If you write code that depends on this, you do so at your own risk.
+ *  This code and its internal interfaces are subject to change or
+ *  deletion without notice.</b>
+ */
+class FrameComputer {
+    /*
+     *  An entry in the exception table. The type is null for a handler
+     *  that catches any exception.
+     */
+    static class Handler {
+        Handler(int start, int end, int handler, String type) {
+            this.start = start;
+            this.end = end;
+            this.handler = handler;
+            this.type = type;
+        }
+
+        final int start;
+        final int end;
+        final int handler;
+        final String type;
+    }
+
+    /*
+     *  The types of the locals and of the stack before an instruction. A long or
+     *  double takes two entries, the second of which is Top.
+     */
+    private static class State {
+        State(List<VType> locals, List<VType> stack) {
+            this.locals = locals;
+            this.stack = stack;
+        }
+
+        State copy() {
+            return new State(new ArrayList<>(locals), new ArrayList<>(stack));
+        }
+
+        @Override
+        public boolean equals(Object o) {
+            return (o instanceof State)
+                    && locals.equals(((State) o).locals) && stack.equals(((State) o).stack);
+        }
+
+        @Override
+        public int hashCode() {
+            return locals.hashCode() * 31 + stack.hashCode();
+        }
+
+        final List<VType> locals;
+        final List<VType> stack;
+    }
+
+    FrameComputer(ConstantPoolBuilder cp, ClassHierarchy hierarchy, String thisClass, boolean hasSuper) {
+        this.cp = cp;
+        this.hierarchy = hierarchy;
+        this.thisClass = thisClass;
+        this.hasSuper = hasSuper;
+    }
+
+    /**
+     * Analyzes the code of a method.
+     * @param access the access flags of the method
+     * @param name the name of the method
+     * @param descriptor the descriptor of the method
+     * @param code the bytecode
+     * @param handlers the exception table
+     * @param lines the line of the listing for each instruction, for errors
+     * @param computeFrames whether to compute stack map frames
+     * @throws AsmException if the code cannot be analyzed
+     */
+    void compute(int access, String name, String descriptor, byte[] code,
+            List<Handler> handlers, Map<Integer, Integer> lines, boolean computeFrames)
+            throws AsmException {
+        this.code = code;
+        this.handlers = handlers;
+        this.lines = lines;
+        this.computeFrames = computeFrames;
+        instructions = new TreeMap<>();
+        for (int pc = 0; pc < code.length; ) {
+            Instruction instr = new Instruction(code, pc);
+            instructions.put(pc, instr);
+            pc += instr.length();
+        }
+
+        State initial = initialState(access, name, descriptor);
+        maxStack = 0;
+        maxLocals = initial.locals.size();
+        for (Instruction instr: instructions.values())
+            maxLocals = Math.max(maxLocals, localLimit(instr));
+        frames = new ArrayList<>();
+        if (instructions.isEmpty())
+            return;
+
+        states = new HashMap<>();
+        frameTargets = new TreeSet<>();
+        worklist = new ArrayDeque<>();
+        queued = new HashSet<>();
+        merge(0, initial, 0);
+        while (!worklist.isEmpty()) {
+            int pc = worklist.poll();
+            queued.remove(pc);
+            execute(pc, states.get(pc));
+        }
+
+        if (computeFrames) {
+            for (int pc: instructions.keySet()) {
+                if (!states.containsKey(pc))
+                    throw error(pc, "unreachable code at offset " + pc
+                            + " cannot be given a stack map frame");
+            }
+            List<VType> previous = compactLocals(initial.locals);
+            for (int pc: frameTargets) {
+                State s = states.get(pc);
+                List<VType> locals = compactLocals(s.locals);
+                frames.add(frame(pc, previous, locals, compact(s.stack)));
+                previous = locals;
+            }
+        }
+    }
+
+    int getMaxStack() {
+        return maxStack;
+    }
+
+    int getMaxLocals() {
+        return maxLocals;
+    }
+
+    List<Frame> getFrames() {
+        return frames;
+    }
+
+    private State initialState(int access, String name, String descriptor) {
+        List<VType> locals = new ArrayList<>();
+        if ((access & ACC_STATIC) == 0) {
+            if (name.equals("<init>") && hasSuper)
+                locals.add(VType.UNINITIALIZED_THIS);
+            else
+                locals.add(VType.object(thisClass));
+        }
+        for (VType t: argumentTypes(descriptor)) {
+            locals.add(t);
+            if (t.isCategory2())
+                locals.add(VType.TOP);
+        }
+        return new State(locals, new ArrayList<>());
+    }
+
+    /*
+     *  The number of locals needed by an instruction that uses a local.
+     */
+    private static int localLimit(Instruction instr) {
+        Opcode opcode = instr.getOpcode();
+        if (opcode == null)
+            return 0;
+        boolean wide = (opcode.opcode >> 8) == Opcode.WIDE;
+        int op = opcode.opcode & 0xff;
+        int index;
+        if (op >= 0x1a && op <= 0x2d)           // xload_n
+            index = (op - 0x1a) % 4;
+        else if (op >= 0x3b && op <= 0x4e)      // xstore_n
+            index = (op - 0x3b) % 4;
+        else if ((op >= 0x15 && op <= 0x19) || (op >= 0x36 && op <= 0x3a) || op == 0x84 || op == 0xa9)
+            index = wide ? instr.getUnsignedShort(2) : instr.getUnsignedByte(1);
+        else
+            return 0;
+        return index + (isCategory2Local(op) ? 2 : 1);
+    }
+
+    private static boolean isCategory2Local(int op) {
+        switch (op) {
+            case 0x16: case 0x18: case 0x37: case 0x39:             // lload, dload, lstore, dstore
+                return true;
+            default:
+                return (op >= 0x1e && op <= 0x21) || (op >= 0x26 && op <= 0x29)      // lload_n, dload_n
+                        || (op >= 0x3f && op <= 0x42) || (op >= 0x47 && op <= 0x4a); // lstore_n, dstore_n
+        }
+    }
+
+    /*
+     *  Simulates an instruction, and merges the resulting state into the state
+     *  of each of its successors, and into the states of its exception handlers.
+     */
+    private void execute(int pc, State before) throws AsmException {
+        Instruction instr = instructions.get(pc);
+        Opcode opcode = instr.getOpcode();
+        if (opcode == null || opcode.set != Opcode.Set.STANDARD)
+            throw error(pc, "unknown instruction at offset " + pc);
+        boolean wide = (opcode.opcode >> 8) == Opcode.WIDE;
+        int op = opcode.opcode & 0xff;
+        int next = pc + instr.length();
+        State s = before.copy();
+        List<Integer> targets = new ArrayList<>();
+        boolean fallsThrough = true;
+
+        switch (op) {
+            case 0x00:      // nop
+                break;
+            case 0x01:      // aconst_null
+                push(s, VType.NULL);
+                break;
+            case 0x02: case 0x03: case 0x04: case 0x05: case 0x06: case 0x07: case 0x08:
+            case 0x10: case 0x11:
+                push(s, VType.INTEGER);
+                break;
+            case 0x09: case 0x0a:
+                push(s, VType.LONG);
+                break;
+            case 0x0b: case 0x0c: case 0x0d:
+                push(s, VType.FLOAT);
+                break;
+            case 0x0e: case 0x0f:
+                push(s, VType.DOUBLE);
+                break;
+            case 0x12:      // ldc
+                push(s, constantType(pc, instr.getUnsignedByte(1)));
+                break;
+            case 0x13: case 0x14:   // ldc_w, ldc2_w
+                push(s, constantType(pc, instr.getUnsignedShort(1)));
+                break;
+            case 0x15: case 0x16: case 0x17: case 0x18: case 0x19:
+                load(s, op - 0x15, wide ? instr.getUnsignedShort(2) : instr.getUnsignedByte(1));
+                break;
+            case 0x1a: case 0x1b: case 0x1c: case 0x1d: case 0x1e: case 0x1f: case 0x20: case 0x21:
+            case 0x22: case 0x23: case 0x24: case 0x25: case 0x26: case 0x27: case 0x28: case 0x29:
+            case 0x2a: case 0x2b: case 0x2c: case 0x2d:
+                load(s, (op - 0x1a) / 4, (op - 0x1a) % 4);
+                break;
+            case 0x2e: case 0x33: case 0x34: case 0x35:     // iaload, baload, caload, saload
+                pop(s, pc, 2);
+                push(s, VType.INTEGER);
+                break;
+            case 0x2f:
+                pop(s, pc, 2);
+                push(s, VType.LONG);
+                break;
+            case 0x30:
+                pop(s, pc, 2);
+                push(s, VType.FLOAT);
+                break;
+            case 0x31:
+                pop(s, pc, 2);
+                push(s, VType.DOUBLE);
+                break;
+            case 0x32: {    // aaload
+                pop(s, pc, 1);
+                VType array = pop(s, pc, 1);
+                push(s, componentType(array));
+                break;
+            }
+            case 0x36: case 0x37: case 0x38: case 0x39: case 0x3a:
+                store(s, pc, op - 0x36, wide ? instr.getUnsignedShort(2) : instr.getUnsignedByte(1));
+                break;
+            case 0x3b: case 0x3c: case 0x3d: case 0x3e: case 0x3f: case 0x40: case 0x41: case 0x42:
+            case 0x43: case 0x44: case 0x45: case 0x46: case 0x47: case 0x48: case 0x49: case 0x4a:
+            case 0x4b: case 0x4c: case 0x4d: case 0x4e:
+                store(s, pc, (op - 0x3b) / 4, (op - 0x3b) % 4);
+                break;
+            case 0x4f: case 0x51: case 0x53: case 0x54: case 0x55: case 0x56:
+                pop(s, pc, 3);
+                break;
+            case 0x50: case 0x52:   // lastore, dastore
+                pop(s, pc, 4);
+                break;
+            case 0x57:      // pop
+                pop(s, pc, 1);
+                break;
+            case 0x58:      // pop2
+                pop(s, pc, 2);
+                break;
+            case 0x59: {    // dup
+                VType v1 = pop(s, pc, 1);
+                pushSlots(s, v1, v1);
+                break;
+            }
+            case 0x5a: {    // dup_x1
+                VType v1 = pop(s, pc, 1), v2 = pop(s, pc, 1);
+                pushSlots(s, v1, v2, v1);
+                break;
+            }
+            case 0x5b: {    // dup_x2
+                VType v1 = pop(s, pc, 1), v2 = pop(s, pc, 1), v3 = pop(s, pc, 1);
+                pushSlots(s, v1, v3, v2, v1);
+                break;
+            }
+            case 0x5c: {    // dup2
+                VType v1 = pop(s, pc, 1), v2 = pop(s, pc, 1);
+                pushSlots(s, v2, v1, v2, v1);
+                break;
+            }
+            case 0x5d: {    // dup2_x1
+                VType v1 = pop(s, pc, 1), v2 = pop(s, pc, 1), v3 = pop(s, pc, 1);
+                pushSlots(s, v2, v1, v3, v2, v1);
+                break;
+            }
+            case 0x5e: {    // dup2_x2
+                VType v1 = pop(s, pc, 1), v2 = pop(s, pc, 1), v3 = pop(s, pc, 1), v4 = pop(s, pc, 1);
+                pushSlots(s, v2, v1, v4, v3, v2, v1);
+                break;
+            }
+            case 0x5f: {    // swap
+                VType v1 = pop(s, pc, 1), v2 = pop(s, pc, 1);
+                pushSlots(s, v1, v2);
+                break;
+            }
+            default:
+                if (op >= 0x60 && op <= 0x73) {             // add, sub, mul, div, rem
+                    VType t = PRIMITIVES[(op - 0x60) % 4];
+                    pop(s, pc, 2 * size(t));
+                    push(s, t);
+                } else if (op >= 0x74 && op <= 0x77) {      // neg
+                    VType t = PRIMITIVES[op - 0x74];
+                    pop(s, pc, size(t));
+                    push(s, t);
+                } else if (op >= 0x78 && op <= 0x7d) {      // shl, shr, ushr
+                    VType t = (op % 2 == 0) ? VType.INTEGER : VType.LONG;
+                    pop(s, pc, 1 + size(t));
+                    push(s, t);
+                } else if (op >= 0x7e && op <= 0x83) {      // and, or, xor
+                    VType t = (op % 2 == 0) ? VType.INTEGER : VType.LONG;
+                    pop(s, pc, 2 * size(t));
+                    push(s, t);
+                } else if (op == 0x84) {                    // iinc
+                    // the local remains an int
+                } else if (op >= 0x85 && op <= 0x93) {      // conversions
+                    VType[] conversion = CONVERSIONS[op - 0x85];
+                    pop(s, pc, size(conversion[0]));
+                    push(s, conversion[1]);
+                } else if (op >= 0x94 && op <= 0x98) {      // lcmp, fcmpl, fcmpg, dcmpl, dcmpg
+                    pop(s, pc, (op == 0x94 || op >= 0x97) ? 4 : 2);
+                    push(s, VType.INTEGER);
+                } else {
+                    fallsThrough = executeControl(pc, instr, op, s, targets);
+                }
+        }
+
+        for (Handler h: handlers) {
+            if (pc >= h.start && pc < h.end) {
+                List<VType> stack = List.of(VType.object(h.type == null ? THROWABLE : h.type));
+                merge(h.handler, new State(before.locals, stack), pc);
+                merge(h.handler, new State(s.locals, stack), pc);
+                frameTargets.add(h.handler);
+            }
+        }
+        for (int target: targets) {
+            merge(target, s, pc);
+            frameTargets.add(target);
+        }
+        if (fallsThrough) {
+            if (next < code.length)
+                merge(next, s, pc);
+            else if (computeFrames)
+                throw error(pc, "execution can fall off the end of the code");
+        } else if (next < code.length) {
+            frameTargets.add(next);
+        }
+    }
+
+    /*
+     *  Simulates the instructions from ifeq on: branches, switches, returns,
+     *  field and method instructions, and objects and arrays. Returns whether
+     *  execution can continue with the next instruction.
+     */
+    private boolean executeControl(int pc, Instruction instr, int op, State s, List<Integer> targets)
+            throws AsmException {
+        switch (op) {
+            case 0x99: case 0x9a: case 0x9b: case 0x9c: case 0x9d: case 0x9e:
+            case 0xc6: case 0xc7:       // ifnull, ifnonnull
+                pop(s, pc, 1);
+                targets.add(pc + instr.getShort(1));
+                return true;
+            case 0x9f: case 0xa0: case 0xa1: case 0xa2: case 0xa3: case 0xa4: case 0xa5: case 0xa6:
+                pop(s, pc, 2);
+                targets.add(pc + instr.getShort(1));
+                return true;
+            case 0xa7:      // goto
+                targets.add(pc + instr.getShort(1));
+                return false;
+            case 0xc8:      // goto_w
+                targets.add(pc + instr.getInt(1));
+                return false;
+            case 0xa8: case 0xc9: {     // jsr, jsr_w
+                if (computeFrames)
+                    throw error(pc, "jsr cannot be used in code that has stack map frames");
+                int target = pc + (op == 0xa8 ? instr.getShort(1) : instr.getInt(1));
+                State sub = s.copy();
+                push(sub, VType.RETURN_ADDRESS);
+                merge(target, sub, pc);
+                return true;        // the subroutine returns to the next instruction
+            }
+            case 0xa9:      // ret
+                if (computeFrames)
+                    throw error(pc, "ret cannot be used in code that has stack map frames");
+                return false;
+            case 0xaa: {    // tableswitch
+                pop(s, pc, 1);
+                int base = ((pc + 4) & ~3) - pc;
+                targets.add(pc + instr.getInt(base));
+                int low = instr.getInt(base + 4);
+                int high = instr.getInt(base + 8);
+                for (int i = 0; i < high - low + 1; i++)
+                    targets.add(pc + instr.getInt(base + 12 + 4 * i));
+                return false;
+            }
+            case 0xab: {    // lookupswitch
+                pop(s, pc, 1);
+                int base = ((pc + 4) & ~3) - pc;
+                targets.add(pc + instr.getInt(base));
+                int npairs = instr.getInt(base + 4);
+                for (int i = 0; i < npairs; i++)
+                    targets.add(pc + instr.getInt(base + 12 + 8 * i));
+                return false;
+            }
+            case 0xac: case 0xad: case 0xae: case 0xaf: case 0xb0: case 0xb1:
+            case 0xbf:      // athrow
+                s.stack.clear();
+                return false;
+            case 0xb2:      // getstatic
+                push(s, VType.of(entry(pc, instr.getUnsignedShort(1)).strings[2]));
+                return true;
+            case 0xb3:      // putstatic
+                pop(s, pc, size(VType.of(entry(pc, instr.getUnsignedShort(1)).strings[2])));
+                return true;
+            case 0xb4:      // getfield
+                pop(s, pc, 1);
+                push(s, VType.of(entry(pc, instr.getUnsignedShort(1)).strings[2]));
+                return true;
+            case 0xb5:      // putfield
+                pop(s, pc, size(VType.of(entry(pc, instr.getUnsignedShort(1)).strings[2])) + 1);
+                return true;
+            case 0xb6: case 0xb7: case 0xb8: case 0xb9: {
+                ConstantPoolBuilder.Entry e = entry(pc, instr.getUnsignedShort(1));
+                String type = e.strings[2];
+                pop(s, pc, slots(argumentTypes(type)));
+                if (op != 0xb8) {
+                    VType receiver = pop(s, pc, 1);
+                    if (op == 0xb7 && e.strings[1].equals("<init>"))
+                        initialize(s, pc, receiver);
+                }
+                pushReturn(s, type);
+                return true;
+            }
+            case 0xba: {    // invokedynamic
+                String type = entry(pc, instr.getUnsignedShort(1)).strings[1];
+                pop(s, pc, slots(argumentTypes(type)));
+                pushReturn(s, type);
+                return true;
+            }
+            case 0xbb:      // new
+                push(s, VType.uninitialized(pc));
+                return true;
+            case 0xbc:      // newarray
+                pop(s, pc, 1);
+                push(s, VType.object("[" + "ZCFDBSIJ".charAt(instr.getUnsignedByte(1) - 4)));
+                return true;
+            case 0xbd: {    // anewarray
+                pop(s, pc, 1);
+                String name = className(pc, instr.getUnsignedShort(1));
+                push(s, VType.object(name.startsWith("[") ? "[" + name : "[L" + name + ";"));
+                return true;
+            }
+            case 0xbe:      // arraylength
+                pop(s, pc, 1);
+                push(s, VType.INTEGER);
+                return true;
+            case 0xc0:      // checkcast
+                pop(s, pc, 1);
+                push(s, VType.object(className(pc, instr.getUnsignedShort(1))));
+                return true;
+            case 0xc1:      // instanceof
+                pop(s, pc, 1);
+                push(s, VType.INTEGER);
+                return true;
+            case 0xc2: case 0xc3:       // monitorenter, monitorexit
+                pop(s, pc, 1);
+                return true;
+            case 0xc5:      // multianewarray
+                pop(s, pc, instr.getUnsignedByte(3));
+                push(s, VType.object(className(pc, instr.getUnsignedShort(1))));
+                return true;
+            default:
+                throw error(pc, "unknown instruction at offset " + pc);
+        }
+    }
+
+    /*
+     *  Replaces an uninitialized type by the class that has been initialized,
+     *  after a call of an instance initialization method.
+     */
+    private void initialize(State s, int pc, VType receiver) throws AsmException {
+        VType initialized;
+        if (receiver.equals(VType.UNINITIALIZED_THIS)) {
+            initialized = VType.object(thisClass);
+        } else if (receiver.tag == ITEM_Uninitialized) {
+            Instruction instr = instructions.get(receiver.offset);
+            if (instr == null || instr.getOpcode() != Opcode.NEW)
+                throw error(pc, "no new instruction at offset " + receiver.offset);
+            initialized = VType.object(className(pc, instr.getUnsignedShort(1)));
+        } else {
+            return;
+        }
+        s.locals.replaceAll(t -> t.equals(receiver) ? initialized : t);
+        s.stack.replaceAll(t -> t.equals(receiver) ? initialized : t);
+    }
+
+    private void load(State s, int kind, int index) {
+        if (kind == 4)
+            push(s, index < s.locals.size() ? s.locals.get(index) : VType.TOP);
+        else
+            push(s, PRIMITIVES[kind]);
+    }
+
+    private void store(State s, int pc, int kind, int index) throws AsmException {
+        VType t;
+        if (kind == 4) {
+            t = pop(s, pc, 1);
+        } else {
+            t = PRIMITIVES[kind];
+            pop(s, pc, size(t));
+        }
+        while (s.locals.size() < index + size(t))
+            s.locals.add(VType.TOP);
+        if (index > 0 && s.locals.get(index - 1).isCategory2())
+            s.locals.set(index - 1, VType.TOP);
+        s.locals.set(index, t);
+        if (t.isCategory2())
+            s.locals.set(index + 1, VType.TOP);
+    }
+
+    private void push(State s, VType t) {
+        s.stack.add(t);
+        if (t.isCategory2())
+            s.stack.add(VType.TOP);
+        maxStack = Math.max(maxStack, s.stack.size());
+    }
+
+    private void pushReturn(State s, String descriptor) {
+        String r = descriptor.substring(descriptor.indexOf(')') + 1);
+        if (!r.equals("V"))
+            push(s, VType.of(r));
+    }
+
+    /*
+     *  Pushes the given stack entries, as they were popped, without regard
+     *  to whether they are the halves of a long or double.
+     */
+    private void pushSlots(State s, VType... slots) {
+        for (VType t: slots)
+            s.stack.add(t);
+        maxStack = Math.max(maxStack, s.stack.size());
+    }
+
+    /*
+     *  Pops the given number of stack entries, and returns the last one popped.
+     */
+    private VType pop(State s, int pc, int n) throws AsmException {
+        if (s.stack.size() < n)
+            throw error(pc, "stack underflow at offset " + pc);
+        VType t = null;
+        for (int i = 0; i < n; i++)
+            t = s.stack.remove(s.stack.size() - 1);
+        return t;
+    }
+
+    private static VType componentType(VType array) {
+        if (array.tag == ITEM_Object && array.name.startsWith("["))
+            return VType.of(array.name.substring(1));
+        if (array.tag == ITEM_Null)
+            return VType.NULL;
+        return VType.object(ClassHierarchy.OBJECT);
+    }
+
+    private VType constantType(int pc, int index) throws AsmException {
+        ConstantPoolBuilder.Entry e = entry(pc, index);
+        switch (e.tag) {
+            case CONSTANT_Integer:
+                return VType.INTEGER;
+            case CONSTANT_Float:
+                return VType.FLOAT;
+            case CONSTANT_Long:
+                return VType.LONG;
+            case CONSTANT_Double:
+                return VType.DOUBLE;
+            case CONSTANT_String:
+                return VType.object("java/lang/String");
+            case CONSTANT_Class:
+                return VType.object("java/lang/Class");
+            case CONSTANT_MethodType:
+                return VType.object("java/lang/invoke/MethodType");
+            case CONSTANT_MethodHandle:
+                return VType.object("java/lang/invoke/MethodHandle");
+            case CONSTANT_Dynamic:
+                return VType.of(e.strings[1]);
+            default:
+                throw error(pc, "constant #" + index + " cannot be loaded");
+        }
+    }
+
+    private ConstantPoolBuilder.Entry entry(int pc, int index) throws AsmException {
+        if (index <= 0 || index >= cp.size() || cp.get(index) == null)
+            throw error(pc, "bad constant pool index " + index + " at offset " + pc);
+        return cp.get(index);
+    }
+
+    private String className(int pc, int index) throws AsmException {
+        return entry(pc, index).strings[0];
+    }
+
+    /*
+     *  Merges a state into the state before an instruction, and queues the
+     *  instruction to be analyzed again if its state has changed.
+     */
+    private void merge(int target, State s, int pc) throws AsmException {
+        if (!instructions.containsKey(target))
+            throw error(pc, "offset " + target + " is not the start of an instruction");
+        State old = states.get(target);
+        State merged;
+        if (old == null) {
+            merged = s.copy();
+        } else {
+            if (old.stack.size() != s.stack.size())
+                throw error(pc, "inconsistent stack height at offset " + target
+                        + ": " + old.stack.size() + " and " + s.stack.size());
+            List<VType> locals = new ArrayList<>();
+            for (int i = 0; i < Math.min(old.locals.size(), s.locals.size()); i++)
+                locals.add(mergeType(old.locals.get(i), s.locals.get(i)));
+            while (!locals.isEmpty() && locals.get(locals.size() - 1).equals(VType.TOP))
+                locals.remove(locals.size() - 1);
+            List<VType> stack = new ArrayList<>();
+            for (int i = 0; i < old.stack.size(); i++) {
+                VType t = mergeType(old.stack.get(i), s.stack.get(i));
+                if (t.equals(VType.TOP) && !old.stack.get(i).equals(VType.TOP) && computeFrames)
+                    throw error(pc, "inconsistent types on the stack at offset " + target
+                            + ": " + old.stack.get(i) + " and " + s.stack.get(i));
+                stack.add(t);
+            }
+            merged = new State(locals, stack);
+            if (merged.equals(old))
+                return;
+        }
+        states.put(target, merged);
+        if (queued.add(target))
+            worklist.add(target);
+    }
+
+    private VType mergeType(VType a, VType b) {
+        if (a.equals(b))
+            return a;
+        boolean aClass = (a.tag == ITEM_Object || a.tag == ITEM_Null);
+        boolean bClass = (b.tag == ITEM_Object || b.tag == ITEM_Null);
+        if (!aClass || !bClass)
+            return VType.TOP;
+        if (a.tag == ITEM_Null)
+            return b;
+        if (b.tag == ITEM_Null)
+            return a;
+        if (!computeFrames)
+            return VType.object(ClassHierarchy.OBJECT);
+        return VType.object(commonSuperclass(a.name, b.name));
+    }
+
+    private String commonSuperclass(String a, String b) {
+        if (a.equals(b))
+            return a;
+        boolean aArray = a.startsWith("["), bArray = b.startsWith("[");
+        if (aArray && bArray) {
+            String ea = a.substring(1), eb = b.substring(1);
+            if (isReference(ea) && isReference(eb)) {
+                String e = commonSuperclass(elementClass(ea), elementClass(eb));
+                return "[" + (e.startsWith("[") ? e : "L" + e + ";");
+            }
+            return ClassHierarchy.OBJECT;
+        }
+        if (aArray || bArray)
+            return ClassHierarchy.OBJECT;
+        return hierarchy.commonSuperclass(a, b);
+    }
+
+    private static boolean isReference(String descriptor) {
+        return descriptor.startsWith("L") || descriptor.startsWith("[");
+    }
+
+    private static String elementClass(String descriptor) {
+        return descriptor.startsWith("L") ? descriptor.substring(1, descriptor.length() - 1) : descriptor;
+    }
+
+    /*
+     *  Returns the frame at an offset, in the shortest form that gives it in
+     *  terms of the locals of the frame before it.
+     */
+    private static Frame frame(int pc, List<VType> previous, List<VType> locals, List<VType> stack) {
+        if (stack.isEmpty()) {
+            if (locals.equals(previous))
+                return new Frame(pc, "same", 0, List.of(), List.of(), 0);
+            int chop = previous.size() - locals.size();
+            if (chop > 0 && chop <= 3 && previous.subList(0, locals.size()).equals(locals))
+                return new Frame(pc, "chop", chop, List.of(), List.of(), 0);
+            int append = -chop;
+            if (append > 0 && append <= 3 && locals.subList(0, previous.size()).equals(previous))
+                return new Frame(pc, "append", 0, locals.subList(previous.size(), locals.size()), List.of(), 0);
+        } else if (stack.size() == 1 && locals.equals(previous)) {
+            return new Frame(pc, "stack_1", 0, List.of(), stack, 0);
+        }
+        return new Frame(pc, "full", 0, locals, stack, 0);
+    }
+
+    /*
+     *  Returns the types of the locals or the stack, as given in a frame,
+     *  with one entry for a long or double. Trailing Top entries are also
+     *  dropped from the locals.
+     */
+    private static List<VType> compact(List<VType> slots) {
+        List<VType> types = new ArrayList<>();
+        for (int i = 0; i < slots.size(); i++) {
+            VType t = slots.get(i);
+            if (t.equals(VType.RETURN_ADDRESS))
+                t = VType.TOP;
+            types.add(t);
+            if (t.isCategory2())
+                i++;
+        }
+        return types;
+    }
+
+    private static List<VType> compactLocals(List<VType> slots) {
+        List<VType> types = compact(slots);
+        while (!types.isEmpty() && types.get(types.size() - 1).equals(VType.TOP))
+            types.remove(types.size() - 1);
+        return types;
+    }
+
+    static List<VType> argumentTypes(String descriptor) {
+        List<VType> types = new ArrayList<>();
+        int i = 1;
+        while (i < descriptor.length() && descriptor.charAt(i) != ')') {
+            int start = i;
+            while (descriptor.charAt(i) == '[')
+                i++;
+            if (descriptor.charAt(i) == 'L')
+                i = descriptor.indexOf(';', i);
+            types.add(VType.of(descriptor.substring(start, i + 1)));
+            i++;
+        }
+        return types;
+    }
+
+    private static int slots(List<VType> types) {
+        int n = 0;
+        for (VType t: types)
+            n += size(t);
+        return n;
+    }
+
+    private static int size(VType t) {
+        return t.isCategory2() ? 2 : 1;
+    }
+
+    private AsmException error(int pc, String message) {
+        return new AsmException(lines.getOrDefault(pc, 0), message);
+    }
+
+    private static final String THROWABLE = "java/lang/Throwable";
+
+    private static final VType[] PRIMITIVES = {
+        VType.INTEGER, VType.LONG, VType.FLOAT, VType.DOUBLE
+    };
+
+    private static final VType[][] CONVERSIONS = {
+        { VType.INTEGER, VType.LONG },      // i2l
+        { VType.INTEGER, VType.FLOAT },     // i2f
+        { VType.INTEGER, VType.DOUBLE },    // i2d
+        { VType.LONG, VType.INTEGER },      // l2i
+        { VType.LONG, VType.FLOAT },        // l2f
+        { VType.LONG, VType.DOUBLE },       // l2d
+        { VType.FLOAT, VType.INTEGER },     // f2i
+        { VType.FLOAT, VType.LONG },        // f2l
+        { VType.FLOAT, VType.DOUBLE },      // f2d
+        { VType.DOUBLE, VType.INTEGER },    // d2i
+        { VType.DOUBLE, VType.LONG },       // d2l
+        { VType.DOUBLE, VType.FLOAT },      // d2f
+        { VType.INTEGER, VType.INTEGER },   // i2b
+        { VType.INTEGER, VType.INTEGER },   // i2c
+        { VType.INTEGER, VType.INTEGER }    // i2s
+    };
+
+    private final ConstantPoolBuilder cp;
+    private final ClassHierarchy hierarchy;
+    private final String thisClass;
+    private final boolean hasSuper;
+
+    private byte[] code;
+    private List<Handler> handlers;
+    private Map<Integer, Integer> lines;
+    private boolean computeFrames;
+    private TreeMap<Integer, Instruction> instructions;
+    private Map<Integer, State> states;
+    private Set<Integer> frameTargets;
+    private Deque<Integer> worklist;
+    private Set<Integer> queued;
+    private int maxStack;
+    private int maxLocals;
+    private List<Frame> frames;
+}
@@ -0,0 +1,227 @@
+/*
+ * Copyright (c) 2021 by Andrew Binstock.
+ *
+ * Portions of this file are copyright Oracle Corp.
+ * Those portions are licensed under GPL v. 2.0
+ * with the Oracle classpath exception. Due to the
+ * requirements of that license, the portions that
+ * are copyrighted by Andrew Binstock are licensed
+ * using the same terms and requirements.
+ *
+ */
+
+package org.jacobin.jadis.asm;
+
+import java.util.ArrayList;
+import java.util.List;
+
+/*
+ *  Splits an assembly listing into lines of tokens.
+ *
+ *  A token is a run of characters other than whitespace, or a string quoted
+ *  with " or ', in which \\, \", \', \n, \r, \t and \\uXXXX are escapes.
+ *  A quoted string with the prefix b is a byte string, in which \xNN is also
+ *  an escape, and every character must be a byte.
+ *  A token that begins with ; starts a comment, which runs to the end of the
+ *  line. Lines with no tokens are dropped.
+ *
+ *  <p><b>This is NOT part of any supported API.
+ *  If you write code that depends on this, you do so at your own risk.
+ *  This code and its internal interfaces are subject to change or
+ *  deletion without notice.</b>
+ */
+class Tokenizer {
+    static class Token {
+        Token(String text, boolean quoted) {
+            this(text, quoted, false);
+        }
+
+        Token(String text, boolean quoted, boolean binary) {
+            this.text = text;
+            this.quoted = quoted;
+            this.binary = binary;
+        }
+
+        @Override
+        public String toString() {
+            return quoted ? "'" + text + "'" : text;
+        }
+
+        final String text;
+        final boolean quoted;
+        final boolean binary;   // a byte string, each character of which is a byte
+    }
+
+    /*
+     *  A line of tokens, which are read in turn from the start of the line.
+     */
+    static class Line {
+        Line(int number, List<Token> tokens) {
+            this.number = number;
+            this.tokens = tokens;
+        }
+
+        boolean hasNext() {
+            return pos < tokens.size();
+        }
+
+        /** Returns the text of the next token, without reading it, or null at the end of the line. */
+        String peek() {
+            return hasNext() ? tokens.get(pos).text : null;
+        }
+
+        Token next() throws AsmException {
+            if (!hasNext())
+                throw error("unexpected end of line");
+            return tokens.get(pos++);
+        }
+
+        String word() throws AsmException {
+            return next().text;
+        }
+
+        /*
+         *  Reads the next token if it is the given keyword, which cannot be quoted.
+         */
+        boolean accept(String keyword) {
+            if (hasNext() && !tokens.get(pos).quoted && tokens.get(pos).text.equals(keyword)) {
+                pos++;
+                return true;
+            }
+            return false;
+        }
+
+        void expect(String keyword) throws AsmException {
+            if (!accept(keyword))
+                throw error(hasNext() ? "expected " + keyword + ", found " + peek() : "expected " + keyword);
+        }
+
+        int integer() throws AsmException {
+            Token t = next();
+            try {
+                return Integer.parseInt(t.text);
+            } catch (NumberFormatException e) {
+                throw error("bad number: " + t.text);
+            }
+        }
+
+        /*
+         *  Checks that all the tokens have been read.
+         */
+        void end() throws AsmException {
+            if (hasNext())
+                throw error("unexpected " + tokens.get(pos));
+        }
+
+        /*
+         *  Returns to the start of the line, to read the tokens again.
+         */
+        void rewind() {
+            pos = 0;
+        }
+
+        AsmException error(String message) {
+            return new AsmException(number, message);
+        }
+
+        final int number;
+        final List<Token> tokens;
+        private int pos;
+    }
+
+    static List<Line> tokenize(String text) throws AsmException {
+        List<Line> lines = new ArrayList<>();
+        String[] rawLines = text.split("\r\n|\r|\n", -1);
+        for (int n = 0; n < rawLines.length; n++) {
+            List<Token> tokens = tokenize(rawLines[n], n + 1);
+            if (!tokens.isEmpty())
+                lines.add(new Line(n + 1, tokens));
+        }
+        return lines;
+    }
+
+    private static List<Token> tokenize(String line, int number) throws AsmException {
+        List<Token> tokens = new ArrayList<>();
+        int i = 0;
+        while (i < line.length()) {
+            char c = line.charAt(i);
+            if (Character.isWhitespace(c)) {
+                i++;
+            } else if (c == ';') {
+                break;
+            } else if (c == '"' || c == '\'' || isByteString(line, i)) {
+                boolean binary = (c == 'b');
+                if (binary)
+                    c = line.charAt(++i);
+                StringBuilder sb = new StringBuilder();
+                i++;
+                while (true) {
+                    if (i >= line.length())
+                        throw new AsmException(number, "unterminated string");
+                    char d = line.charAt(i++);
+                    if (d == c)
+                        break;
+                    if (d != '\\') {
+                        sb.append(d);
+                        continue;
+                    }
+                    if (i >= line.length())
+                        throw new AsmException(number, "unterminated string");
+                    char e = line.charAt(i++);
+                    switch (e) {
+                        case 'n':
+                            sb.append('\n');
+                            break;
+                        case 'r':
+                            sb.append('\r');
+                            break;
+                        case 't':
+                            sb.append('\t');
+                            break;
+                        case 'u':
+                            if (i + 4 > line.length())
+                                throw new AsmException(number, "bad escape in string");
+                            try {
+                                sb.append((char) Integer.parseInt(line.substring(i, i + 4), 16));
+                            } catch (NumberFormatException ex) {
+                                throw new AsmException(number, "bad escape in string");
+                            }
+                            i += 4;
+                            break;
+                        case 'x':
+                            if (!binary || i + 2 > line.length())
+                                throw new AsmException(number, "bad escape in string");
+                            try {
+                                sb.append((char) Integer.parseInt(line.substring(i, i + 2), 16));
+                            } catch (NumberFormatException ex) {
+                                throw new AsmException(number, "bad escape in string");
+                            }
+                            i += 2;
+                            break;
+                        case '\\':
+                        case '"':
+                        case '\'':
+                            sb.append(e);
+                            break;
+                        default:
+                            throw new AsmException(number, "bad escape in string: \\" + e);
+                    }
+                }
+                if (binary && sb.chars().anyMatch(ch -> ch > 0xff))
+                    throw new AsmException(number, "bad character in byte string");
+                tokens.add(new Token(sb.toString(), true, binary));
+            } else {
+                int start = i;
+                while (i < line.length() && !Character.isWhitespace(line.charAt(i)))
+                    i++;
+                tokens.add(new Token(line.substring(start, i), false));
+            }
+        }
+        return tokens;
+    }
+
+    private static boolean isByteString(String line, int i) {
+        return line.charAt(i) == 'b' && i + 1 < line.length()
+                && (line.charAt(i + 1) == '\'' || line.charAt(i + 1) == '"');
+    }
+}
@@ -0,0 +1,136 @@
+/*
+ * Copyright (c) 2021 by Andrew Binstock.
+ *
+ * Portions of this file are copyright Oracle Corp.
+ * Those portions are licensed under GPL v. 2.0
+ * with the Oracle classpath exception. Due to the
+ * requirements of that license, the portions that
+ * are copyrighted by Andrew Binstock are licensed
+ * using the same terms and requirements.
+ *
+ */
+
+package org.jacobin.jadis.asm;
+
+import java.util.Objects;
+
+import static org.jacobin.jadis.classfile.StackMapTable_attribute.verification_type_info.*;
+
+/*
+ *  A verification type, as given in a stack map frame (JVMS 4.10.1.2).
+ *  An Object type is named by the name of its class, as in a Class entry in
+ *  the constant pool; an Uninitialized type is given by the label or offset
+ *  of the new instruction that created it.
+ *
+ *  <p><b>This is NOT part of any supported API.
+ *  If you write code that depends on this, you do so at your own risk.
+ *  This code and its internal interfaces are subject to change or
+ *  deletion without notice.</b>
+ */
+public class VType {
+    public static final VType TOP = new VType(ITEM_Top, null, -1);
+    public static final VType INTEGER = new VType(ITEM_Integer, null, -1);
+    public static final VType FLOAT = new VType(ITEM_Float, null, -1);
+    public static final VType LONG = new VType(ITEM_Long, null, -1);
+    public static final VType DOUBLE = new VType(ITEM_Double, null, -1);
+    public static final VType NULL = new VType(ITEM_Null, null, -1);
+    public static final VType UNINITIALIZED_THIS = new VType(ITEM_UninitializedThis, null, -1);
+
+    /*
+     *  The return address pushed by jsr. This is not a verification type, and
+     *  cannot appear in a stack map frame; it is used when computing max_stack.
+     */
+    public static final VType RETURN_ADDRESS = new VType(-1, null, -1);
+
+    public static VType object(String name) {
+        return new VType(ITEM_Object, name, -1);
+    }
+
+    public static VType uninitialized(int offset) {
+        return new VType(ITEM_Uninitialized, null, offset);
+    }
+
+    public static VType uninitialized(String label) {
+        return new VType(ITEM_Uninitialized, label, -1);
+    }
+
+    /*
+     *  Returns the verification type of a value of the given field descriptor.
+     */
+    public static VType of(String descriptor) {
+        switch (descriptor.charAt(0)) {
+            case 'B': case 'C': case 'I': case 'S': case 'Z':
+                return INTEGER;
+            case 'F':
+                return FLOAT;
+            case 'J':
+                return LONG;
+            case 'D':
+                return DOUBLE;
+            case 'L':
+                return object(descriptor.substring(1, descriptor.length() - 1));
+            default:
+                return object(descriptor);
+        }
+    }
+
+    private VType(int tag, String name, int offset) {
+        this.tag = tag;
+        this.name = name;
+        this.offset = offset;
+    }
+
+    /** Whether this type takes two slots, in the locals or on the stack. */
+    public boolean isCategory2() {
+        return tag == ITEM_Long || tag == ITEM_Double;
+    }
+
+    /** Whether this is the type of a reference, possibly uninitialized. */
+    public boolean isReference() {
+        return tag == ITEM_Object || tag == ITEM_Null
+                || tag == ITEM_Uninitialized || tag == ITEM_UninitializedThis;
+    }
+
+    @Override
+    public boolean equals(Object o) {
+        if (!(o instanceof VType))
+            return false;
+        VType t = (VType) o;
+        return tag == t.tag && offset == t.offset && Objects.equals(name, t.name);
+    }
+
+    @Override
+    public int hashCode() {
+        return Objects.hash(tag, name, offset);
+    }
+
+    @Override
+    public String toString() {
+        switch (tag) {
+            case ITEM_Top:
+                return "Top";
+            case ITEM_Integer:
+                return "Integer";
+            case ITEM_Float:
+                return "Float";
+            case ITEM_Long:
+                return "Long";
+            case ITEM_Double:
+                return "Double";
+            case ITEM_Null:
+                return "Null";
+            case ITEM_UninitializedThis:
+                return "UninitializedThis";
+            case ITEM_Object:
+                return "Object " + name;
+            case ITEM_Uninitialized:
+                return "Uninitialized " + (name != null ? name : "L" + offset);
+            default:
+                return "ReturnAddress";
+        }
+    }
+
+    public final int tag;
+    public final String name;      // the class of an Object, or the label of an Uninitialized
+    public final int offset;       // the offset of an Uninitialized
+}
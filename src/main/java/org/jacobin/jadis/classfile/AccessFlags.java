@@ -61,9 +61,9 @@ public class AccessFlags {
 
     public static enum Kind { Class, InnerClass, Field, Method}
 
-//    AccessFlags(ClassReader cr) throws IOException { // by ALB
-//        this(cr.readUnsignedShort());
-//    }
+    AccessFlags(ClassReader cr) throws IOException {
+        this(cr.readUnsignedShort());
+    }
 
     public AccessFlags(int flags) {
         this.flags = flags;
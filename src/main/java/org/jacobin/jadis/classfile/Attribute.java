@@ -26,9 +26,15 @@
 package org.jacobin.jadis.classfile;
 
 import java.io.IOException;
+import java.io.PrintWriter;
 import java.lang.reflect.Constructor;
+import java.lang.reflect.InvocationTargetException;
 import java.util.HashMap;
+import java.util.Iterator;
 import java.util.Map;
+import java.util.Objects;
+import java.util.ServiceConfigurationError;
+import java.util.ServiceLoader;
 
 /**
  *  <p><b>This is NOT part of any supported API.
@@ -81,95 +87,190 @@ public abstract class Attribute {
             // defer init of standardAttributeClasses until after options set up
         }
 
-//        public Attribute createAttribute(ClassReader cr, int name_index, byte[] data)
-//                throws IOException {
-//            if (standardAttributes == null) {
-//                init();
-//            }
-//
-//            ConstantPool cp = cr.getConstantPool();
-//            String reasonForDefaultAttr;
-//            try {
-//                String name = cp.getUTF8Value(name_index);
-//                Class<? extends Attribute> attrClass = standardAttributes.get(name);
-//                if (attrClass != null) {
-//                    try {
-//                        Class<?>[] constrArgTypes = {ClassReader.class, int.class, int.class};
-//                        Constructor<? extends Attribute> constr = attrClass.getDeclaredConstructor(constrArgTypes);
-//                        return constr.newInstance(cr, name_index, data.length);
-//                    } catch (Throwable t) {
-//                        reasonForDefaultAttr = t.toString();
-//                        // fall through and use DefaultAttribute
-//                        // t.printStackTrace();
-//                    }
-//                } else {
-//                    reasonForDefaultAttr = "unknown attribute";
-//                }
-//            } catch (ConstantPoolException e) {
-//                reasonForDefaultAttr = e.toString();
-//                // fall through and use DefaultAttribute
-//            }
-//            return new DefaultAttribute(cr, name_index, data, reasonForDefaultAttr);
-//        }
+        /**
+         * Registers a decoder, and optionally a printer, for attributes with
+         * the given name. A registered decoder takes precedence over the
+         * standard decoding for that name. Providers found with
+         * {@link ServiceLoader} are registered after any explicit
+         * registration, and are skipped for names that are already bound,
+         * either explicitly or as a standard attribute.
+         */
+        public void register(String name, Decoder decoder, Printer printer) {
+            Objects.requireNonNull(name);
+            Objects.requireNonNull(decoder);
+            if (loadingProviders
+                    && (standardAttributes.containsKey(name) || customDecoders.containsKey(name)))
+                return;
+            customDecoders.put(name, decoder);
+            if (printer != null)
+                customPrinters.put(name, printer);
+            else
+                customPrinters.remove(name);
+        }
+
+        /**
+         * Returns the printer registered for attributes with the given name,
+         * or null if there is none.
+         */
+        public Printer getPrinter(String name) {
+            return customPrinters.get(name);
+        }
+
+        public Attribute createAttribute(ClassReader cr, int name_index, byte[] data)
+                throws IOException {
+            if (standardAttributes == null) {
+                init();
+            }
+
+            ConstantPool cp = cr.getConstantPool();
+            String reasonForDefaultAttr;
+            try {
+                String name = cp.getUTF8Value(name_index);
+                Decoder decoder = customDecoders.get(name);
+                Class<? extends Attribute> attrClass = standardAttributes.get(name);
+                if (decoder != null) {
+                    try {
+                        Attribute attr = decoder.decode(cr, name_index, data.length);
+                        if (attr instanceof CustomAttribute)
+                            ((CustomAttribute) attr).info = data;   // so that it can be written as it was read
+                        if (attr != null)
+                            return attr;
+                        reasonForDefaultAttr = "no attribute returned by decoder";
+                    } catch (IOException | AttributeException | RuntimeException e) {
+                        reasonForDefaultAttr = e.toString();
+                        // fall through and use DefaultAttribute
+                    }
+                } else if (attrClass != null) {
+                    try {
+                        Class<?>[] constrArgTypes = {ClassReader.class, int.class, int.class};
+                        Constructor<? extends Attribute> constr = attrClass.getDeclaredConstructor(constrArgTypes);
+                        return constr.newInstance(cr, name_index, data.length);
+                    } catch (InvocationTargetException e) {
+                        // the attribute's own decoder failed: record why
+                        reasonForDefaultAttr = e.getCause().toString();
+                        // fall through and use DefaultAttribute
+                    } catch (Throwable t) {
+                        reasonForDefaultAttr = t.toString();
+                        // fall through and use DefaultAttribute
+                    }
+                } else {
+                    reasonForDefaultAttr = "unknown attribute";
+                }
+            } catch (ConstantPoolException e) {
+                reasonForDefaultAttr = e.toString();
+                // fall through and use DefaultAttribute
+            }
+            return new DefaultAttribute(cr, name_index, data, reasonForDefaultAttr);
+        }
 
         protected void init() {
             standardAttributes = new HashMap<>();
-//            standardAttributes.put(AnnotationDefault, AnnotationDefault_attribute.class);
-//            standardAttributes.put(BootstrapMethods,  BootstrapMethods_attribute.class);
-//            standardAttributes.put(CharacterRangeTable, CharacterRangeTable_attribute.class);
-//            standardAttributes.put(Code,              Code_attribute.class);
-//            standardAttributes.put(CompilationID,     CompilationID_attribute.class);
-//            standardAttributes.put(ConstantValue,     ConstantValue_attribute.class);
-//            standardAttributes.put(Deprecated,        Deprecated_attribute.class);
-//            standardAttributes.put(EnclosingMethod,   EnclosingMethod_attribute.class);
-//            standardAttributes.put(Exceptions,        Exceptions_attribute.class);
-//            standardAttributes.put(InnerClasses,      InnerClasses_attribute.class);
-//            standardAttributes.put(LineNumberTable,   LineNumberTable_attribute.class);
-//            standardAttributes.put(LocalVariableTable, LocalVariableTable_attribute.class);
-//            standardAttributes.put(LocalVariableTypeTable, LocalVariableTypeTable_attribute.class);
-//            standardAttributes.put(MethodParameters,  MethodParameters_attribute.class);
-//            standardAttributes.put(Module,            Module_attribute.class);
-//            standardAttributes.put(ModuleHashes,      ModuleHashes_attribute.class);
-//            standardAttributes.put(ModuleMainClass,   ModuleMainClass_attribute.class);
-//            standardAttributes.put(ModulePackages,    ModulePackages_attribute.class);
-//            standardAttributes.put(ModuleResolution,  ModuleResolution_attribute.class);
-//            standardAttributes.put(ModuleTarget,      ModuleTarget_attribute.class);
-//            standardAttributes.put(NestHost, NestHost_attribute.class);
-//            standardAttributes.put(NestMembers, NestMembers_attribute.class);
-//            standardAttributes.put(Record, Record_attribute.class);
-//            standardAttributes.put(RuntimeInvisibleAnnotations, RuntimeInvisibleAnnotations_attribute.class);
-//            standardAttributes.put(RuntimeInvisibleParameterAnnotations, RuntimeInvisibleParameterAnnotations_attribute.class);
-//            standardAttributes.put(RuntimeVisibleAnnotations, RuntimeVisibleAnnotations_attribute.class);
-//            standardAttributes.put(RuntimeVisibleParameterAnnotations, RuntimeVisibleParameterAnnotations_attribute.class);
-//            standardAttributes.put(RuntimeVisibleTypeAnnotations, RuntimeVisibleTypeAnnotations_attribute.class);
-//            standardAttributes.put(RuntimeInvisibleTypeAnnotations, RuntimeInvisibleTypeAnnotations_attribute.class);
-//            standardAttributes.put(PermittedSubclasses, PermittedSubclasses_attribute.class);
-//            standardAttributes.put(Signature,         Signature_attribute.class);
-//            standardAttributes.put(SourceDebugExtension, SourceDebugExtension_attribute.class);
-//            standardAttributes.put(SourceFile,        SourceFile_attribute.class);
-//            standardAttributes.put(SourceID,          SourceID_attribute.class);
-//            standardAttributes.put(StackMap,          StackMap_attribute.class);
-//            standardAttributes.put(StackMapTable,     StackMapTable_attribute.class);
-//            standardAttributes.put(Synthetic,         Synthetic_attribute.class);
+            standardAttributes.put(AnnotationDefault, AnnotationDefault_attribute.class);
+            standardAttributes.put(BootstrapMethods,  BootstrapMethods_attribute.class);
+            standardAttributes.put(CharacterRangeTable, CharacterRangeTable_attribute.class);
+            standardAttributes.put(Code,              Code_attribute.class);
+            standardAttributes.put(CompilationID,     CompilationID_attribute.class);
+            standardAttributes.put(ConstantValue,     ConstantValue_attribute.class);
+            standardAttributes.put(Deprecated,        Deprecated_attribute.class);
+            standardAttributes.put(EnclosingMethod,   EnclosingMethod_attribute.class);
+            standardAttributes.put(Exceptions,        Exceptions_attribute.class);
+            standardAttributes.put(InnerClasses,      InnerClasses_attribute.class);
+            standardAttributes.put(LineNumberTable,   LineNumberTable_attribute.class);
+            standardAttributes.put(LocalVariableTable, LocalVariableTable_attribute.class);
+            standardAttributes.put(LocalVariableTypeTable, LocalVariableTypeTable_attribute.class);
+            standardAttributes.put(MethodParameters,  MethodParameters_attribute.class);
+            standardAttributes.put(Module,            Module_attribute.class);
+            standardAttributes.put(ModuleHashes,      ModuleHashes_attribute.class);
+            standardAttributes.put(ModuleMainClass,   ModuleMainClass_attribute.class);
+            standardAttributes.put(ModulePackages,    ModulePackages_attribute.class);
+            standardAttributes.put(ModuleResolution,  ModuleResolution_attribute.class);
+            standardAttributes.put(ModuleTarget,      ModuleTarget_attribute.class);
+            standardAttributes.put(NestHost, NestHost_attribute.class);
+            standardAttributes.put(NestMembers, NestMembers_attribute.class);
+            standardAttributes.put(Record, Record_attribute.class);
+            standardAttributes.put(RuntimeInvisibleAnnotations, RuntimeInvisibleAnnotations_attribute.class);
+            standardAttributes.put(RuntimeInvisibleParameterAnnotations, RuntimeInvisibleParameterAnnotations_attribute.class);
+            standardAttributes.put(RuntimeVisibleAnnotations, RuntimeVisibleAnnotations_attribute.class);
+            standardAttributes.put(RuntimeVisibleParameterAnnotations, RuntimeVisibleParameterAnnotations_attribute.class);
+            standardAttributes.put(RuntimeVisibleTypeAnnotations, RuntimeVisibleTypeAnnotations_attribute.class);
+            standardAttributes.put(RuntimeInvisibleTypeAnnotations, RuntimeInvisibleTypeAnnotations_attribute.class);
+            standardAttributes.put(PermittedSubclasses, PermittedSubclasses_attribute.class);
+            standardAttributes.put(Signature,         Signature_attribute.class);
+            standardAttributes.put(SourceDebugExtension, SourceDebugExtension_attribute.class);
+            standardAttributes.put(SourceFile,        SourceFile_attribute.class);
+            standardAttributes.put(SourceID,          SourceID_attribute.class);
+            standardAttributes.put(StackMap,          StackMap_attribute.class);
+            standardAttributes.put(StackMapTable,     StackMapTable_attribute.class);
+            standardAttributes.put(Synthetic,         Synthetic_attribute.class);
+
+            Iterator<Provider> providers = ServiceLoader.load(Provider.class).iterator();
+            loadingProviders = true;
+            try {
+                while (true) {
+                    try {
+                        if (!providers.hasNext())
+                            break;
+                        providers.next().register(this);
+                    } catch (ServiceConfigurationError e) {
+                        // skip a provider that cannot be loaded
+                    }
+                }
+            } finally {
+                loadingProviders = false;
+            }
         }
 
         private Map<String,Class<? extends Attribute>> standardAttributes;
+        private boolean loadingProviders;
+        private final Map<String,Decoder> customDecoders = new HashMap<>();
+        private final Map<String,Printer> customPrinters = new HashMap<>();
+    }
+
+    /**
+     * Decodes an attribute that is not one of the standard attributes.
+     * When called, the reader is positioned at the start of the attribute's
+     * info, and only those {@code length} bytes are available.
+     */
+    public interface Decoder {
+        Attribute decode(ClassReader cr, int name_index, int length)
+                throws IOException, AttributeException;
     }
 
-//    public static Attribute read(ClassReader cr) throws IOException {
-//        return cr.readAttribute();
-//    }
+    /**
+     * Prints the contents of an attribute created by a {@link Decoder}
+     * in verbose listings. Each line written to {@code out} is indented
+     * to match the surrounding output.
+     */
+    public interface Printer {
+        void print(Attribute attr, ConstantPool constant_pool, PrintWriter out)
+                throws ConstantPoolException;
+    }
+
+    /**
+     * A plugin that registers decoders and printers with a factory.
+     * Implementations are found with {@link ServiceLoader}, and are
+     * registered the first time the factory creates an attribute.
+     * A provider cannot replace a standard attribute, or a decoder
+     * registered before it.
+     */
+    public interface Provider {
+        void register(Factory factory);
+    }
+
+    public static Attribute read(ClassReader cr) throws IOException {
+        return cr.readAttribute();
+    }
 
     protected Attribute(int name_index, int length) {
         attribute_name_index = name_index;
         attribute_length = length;
     }
 
-//    public String getName(ConstantPool constant_pool) throws ConstantPoolException {
-//        return constant_pool.getUTF8Value(attribute_name_index);
-//    }
+    public String getName(ConstantPool constant_pool) throws ConstantPoolException {
+        return constant_pool.getUTF8Value(attribute_name_index);
+    }
 
-//    public abstract <R,D> R accept(Attribute.Visitor<R,D> visitor, D data);
+    public abstract <R,D> R accept(Attribute.Visitor<R,D> visitor, D data);
 
     public int byteLength() {
         return 6 + attribute_length;
@@ -178,45 +279,48 @@ public abstract class Attribute {
     public final int attribute_name_index;
     public final int attribute_length;
 
-
-//    public interface Visitor<R,P> {
-//        R visitBootstrapMethods(BootstrapMethods_attribute attr, P p);
-//        R visitDefault(DefaultAttribute attr, P p);
-//        R visitAnnotationDefault(AnnotationDefault_attribute attr, P p);
-//        R visitCharacterRangeTable(CharacterRangeTable_attribute attr, P p);
-//        R visitCode(Code_attribute attr, P p);
-//        R visitCompilationID(CompilationID_attribute attr, P p);
-//        R visitConstantValue(ConstantValue_attribute attr, P p);
-//        R visitDeprecated(Deprecated_attribute attr, P p);
-//        R visitEnclosingMethod(EnclosingMethod_attribute attr, P p);
-//        R visitExceptions(Exceptions_attribute attr, P p);
-//        R visitInnerClasses(InnerClasses_attribute attr, P p);
-//        R visitLineNumberTable(LineNumberTable_attribute attr, P p);
-//        R visitLocalVariableTable(LocalVariableTable_attribute attr, P p);
-//        R visitLocalVariableTypeTable(LocalVariableTypeTable_attribute attr, P p);
-//        R visitMethodParameters(MethodParameters_attribute attr, P p);
-//        R visitModule(Module_attribute attr, P p);
-//        R visitModuleHashes(ModuleHashes_attribute attr, P p);
-//        R visitModuleMainClass(ModuleMainClass_attribute attr, P p);
-//        R visitModulePackages(ModulePackages_attribute attr, P p);
-//        R visitModuleResolution(ModuleResolution_attribute attr, P p);
-//        R visitModuleTarget(ModuleTarget_attribute attr, P p);
-//        R visitNestHost(NestHost_attribute attr, P p);
-//        R visitNestMembers(NestMembers_attribute attr, P p);
-//        R visitRecord(Record_attribute attr, P p);
-//        R visitRuntimeVisibleAnnotations(RuntimeVisibleAnnotations_attribute attr, P p);
-//        R visitRuntimeInvisibleAnnotations(RuntimeInvisibleAnnotations_attribute attr, P p);
-//        R visitRuntimeVisibleParameterAnnotations(RuntimeVisibleParameterAnnotations_attribute attr, P p);
-//        R visitRuntimeInvisibleParameterAnnotations(RuntimeInvisibleParameterAnnotations_attribute attr, P p);
-//        R visitRuntimeVisibleTypeAnnotations(RuntimeVisibleTypeAnnotations_attribute attr, P p);
-//        R visitRuntimeInvisibleTypeAnnotations(RuntimeInvisibleTypeAnnotations_attribute attr, P p);
-//        R visitPermittedSubclasses(PermittedSubclasses_attribute attr, P p);
-//        R visitSignature(Signature_attribute attr, P p);
-//        R visitSourceDebugExtension(SourceDebugExtension_attribute attr, P p);
-//        R visitSourceFile(SourceFile_attribute attr, P p);
-//        R visitSourceID(SourceID_attribute attr, P p);
-//        R visitStackMap(StackMap_attribute attr, P p);
-//        R visitStackMapTable(StackMapTable_attribute attr, P p);
-//        R visitSynthetic(Synthetic_attribute attr, P p);
-//    }
+    public interface Visitor<R,P> {
+        R visitBootstrapMethods(BootstrapMethods_attribute attr, P p);
+        R visitDefault(DefaultAttribute attr, P p);
+        R visitAnnotationDefault(AnnotationDefault_attribute attr, P p);
+        R visitCharacterRangeTable(CharacterRangeTable_attribute attr, P p);
+        R visitCode(Code_attribute attr, P p);
+        R visitCompilationID(CompilationID_attribute attr, P p);
+        R visitConstantValue(ConstantValue_attribute attr, P p);
+        R visitDeprecated(Deprecated_attribute attr, P p);
+        R visitEnclosingMethod(EnclosingMethod_attribute attr, P p);
+        R visitExceptions(Exceptions_attribute attr, P p);
+        R visitInnerClasses(InnerClasses_attribute attr, P p);
+        R visitLineNumberTable(LineNumberTable_attribute attr, P p);
+        R visitLocalVariableTable(LocalVariableTable_attribute attr, P p);
+        R visitLocalVariableTypeTable(LocalVariableTypeTable_attribute attr, P p);
+        R visitMethodParameters(MethodParameters_attribute attr, P p);
+        R visitModule(Module_attribute attr, P p);
+        R visitModuleHashes(ModuleHashes_attribute attr, P p);
+        R visitModuleMainClass(ModuleMainClass_attribute attr, P p);
+        R visitModulePackages(ModulePackages_attribute attr, P p);
+        R visitModuleResolution(ModuleResolution_attribute attr, P p);
+        R visitModuleTarget(ModuleTarget_attribute attr, P p);
+        R visitNestHost(NestHost_attribute attr, P p);
+        R visitNestMembers(NestMembers_attribute attr, P p);
+        R visitRecord(Record_attribute attr, P p);
+        R visitRuntimeVisibleAnnotations(RuntimeVisibleAnnotations_attribute attr, P p);
+        R visitRuntimeInvisibleAnnotations(RuntimeInvisibleAnnotations_attribute attr, P p);
+        R visitRuntimeVisibleParameterAnnotations(RuntimeVisibleParameterAnnotations_attribute attr, P p);
+        R visitRuntimeInvisibleParameterAnnotations(RuntimeInvisibleParameterAnnotations_attribute attr, P p);
+        R visitRuntimeVisibleTypeAnnotations(RuntimeVisibleTypeAnnotations_attribute attr, P p);
+        R visitRuntimeInvisibleTypeAnnotations(RuntimeInvisibleTypeAnnotations_attribute attr, P p);
+        R visitPermittedSubclasses(PermittedSubclasses_attribute attr, P p);
+        R visitSignature(Signature_attribute attr, P p);
+        R visitSourceDebugExtension(SourceDebugExtension_attribute attr, P p);
+        R visitSourceFile(SourceFile_attribute attr, P p);
+        R visitSourceID(SourceID_attribute attr, P p);
+        R visitStackMap(StackMap_attribute attr, P p);
+        R visitStackMapTable(StackMapTable_attribute attr, P p);
+        R visitSynthetic(Synthetic_attribute attr, P p);
+        /** Custom attributes are ignored unless a visitor overrides this. */
+        default R visitCustom(CustomAttribute attr, P p) {
+            return null;
+        }
+    }
 }
@@ -0,0 +1,37 @@
+/*
+ * Copyright (c) 2021 by Andrew Binstock.
+ *
+ * Portions of this file are copyright Oracle Corp.
+ * Those portions are licensed under GPL v. 2.0
+ * with the Oracle classpath exception. Due to the
+ * requirements of that license, the portions that
+ * are copyrighted by Andrew Binstock are licensed
+ * using the same terms and requirements.
+ */
+
+package org.jacobin.jadis.classfile;
+
+/**
+ * A convenient base class for attributes created by an
+ * {@link Attribute.Decoder} registered on {@link Attribute.Factory}.
+ * Visitors see these through {@link Attribute.Visitor#visitCustom}.
+ */
+public abstract class CustomAttribute extends Attribute {
+    protected CustomAttribute(int name_index, int length) {
+        super(name_index, length);
+    }
+
+    public <R, D> R accept(Visitor<R, D> visitor, D data) {
+        return visitor.visitCustom(this, data);
+    }
+
+    /**
+     * Returns the content of the attribute as it was read, after its name
+     * and length, or null if it was not read by {@link Attribute.Factory}.
+     */
+    public byte[] getInfo() {
+        return info;
+    }
+
+    byte[] info;
+}
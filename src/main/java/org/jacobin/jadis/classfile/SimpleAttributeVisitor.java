@@ -0,0 +1,212 @@
+/*
+ * Copyright (c) 2021 by Andrew Binstock.
+ *
+ * Portions of this file are copyright Oracle Corp.
+ * Those portions are licensed under GPL v. 2.0
+ * with the Oracle classpath exception. Due to the
+ * requirements of that license, the portions that
+ * are copyrighted by Andrew Binstock are licensed
+ * using the same terms and requirements.
+ */
+
+package org.jacobin.jadis.classfile;
+
+/**
+ * A simple implementation of {@link Attribute.Visitor}. Every visit method
+ * calls {@link #defaultAction}, which returns {@link #DEFAULT_VALUE}, so
+ * subclasses need only override the methods for the attributes they care about.
+ *
+ * @param <R> the return type of this visitor's methods; use {@link Void}
+ *            for visitors that do not need to return results
+ * @param <P> the type of the additional parameter to this visitor's methods;
+ *            use {@code Void} for visitors that do not need one
+ */
+public class SimpleAttributeVisitor<R,P> implements Attribute.Visitor<R,P> {
+    /**
+     * The value returned by {@link #defaultAction}.
+     */
+    protected final R DEFAULT_VALUE;
+
+    /**
+     * Creates a visitor whose default value is {@code null}.
+     */
+    protected SimpleAttributeVisitor() {
+        DEFAULT_VALUE = null;
+    }
+
+    /**
+     * Creates a visitor with the given default value.
+     *
+     * @param defaultValue the value to return from {@link #defaultAction}
+     */
+    protected SimpleAttributeVisitor(R defaultValue) {
+        DEFAULT_VALUE = defaultValue;
+    }
+
+    /**
+     * The action taken by every visit method unless it is overridden.
+     *
+     * @param attr the attribute being visited
+     * @param p    the additional parameter
+     * @return {@link #DEFAULT_VALUE}
+     */
+    protected R defaultAction(Attribute attr, P p) {
+        return DEFAULT_VALUE;
+    }
+
+    public R visitBootstrapMethods(BootstrapMethods_attribute attr, P p) {
+        return defaultAction(attr, p);
+    }
+
+    public R visitDefault(DefaultAttribute attr, P p) {
+        return defaultAction(attr, p);
+    }
+
+    public R visitAnnotationDefault(AnnotationDefault_attribute attr, P p) {
+        return defaultAction(attr, p);
+    }
+
+    public R visitCharacterRangeTable(CharacterRangeTable_attribute attr, P p) {
+        return defaultAction(attr, p);
+    }
+
+    public R visitCode(Code_attribute attr, P p) {
+        return defaultAction(attr, p);
+    }
+
+    public R visitCompilationID(CompilationID_attribute attr, P p) {
+        return defaultAction(attr, p);
+    }
+
+    public R visitConstantValue(ConstantValue_attribute attr, P p) {
+        return defaultAction(attr, p);
+    }
+
+    public R visitDeprecated(Deprecated_attribute attr, P p) {
+        return defaultAction(attr, p);
+    }
+
+    public R visitEnclosingMethod(EnclosingMethod_attribute attr, P p) {
+        return defaultAction(attr, p);
+    }
+
+    public R visitExceptions(Exceptions_attribute attr, P p) {
+        return defaultAction(attr, p);
+    }
+
+    public R visitInnerClasses(InnerClasses_attribute attr, P p) {
+        return defaultAction(attr, p);
+    }
+
+    public R visitLineNumberTable(LineNumberTable_attribute attr, P p) {
+        return defaultAction(attr, p);
+    }
+
+    public R visitLocalVariableTable(LocalVariableTable_attribute attr, P p) {
+        return defaultAction(attr, p);
+    }
+
+    public R visitLocalVariableTypeTable(LocalVariableTypeTable_attribute attr, P p) {
+        return defaultAction(attr, p);
+    }
+
+    public R visitMethodParameters(MethodParameters_attribute attr, P p) {
+        return defaultAction(attr, p);
+    }
+
+    public R visitModule(Module_attribute attr, P p) {
+        return defaultAction(attr, p);
+    }
+
+    public R visitModuleHashes(ModuleHashes_attribute attr, P p) {
+        return defaultAction(attr, p);
+    }
+
+    public R visitModuleMainClass(ModuleMainClass_attribute attr, P p) {
+        return defaultAction(attr, p);
+    }
+
+    public R visitModulePackages(ModulePackages_attribute attr, P p) {
+        return defaultAction(attr, p);
+    }
+
+    public R visitModuleResolution(ModuleResolution_attribute attr, P p) {
+        return defaultAction(attr, p);
+    }
+
+    public R visitModuleTarget(ModuleTarget_attribute attr, P p) {
+        return defaultAction(attr, p);
+    }
+
+    public R visitNestHost(NestHost_attribute attr, P p) {
+        return defaultAction(attr, p);
+    }
+
+    public R visitNestMembers(NestMembers_attribute attr, P p) {
+        return defaultAction(attr, p);
+    }
+
+    public R visitRecord(Record_attribute attr, P p) {
+        return defaultAction(attr, p);
+    }
+
+    public R visitRuntimeVisibleAnnotations(RuntimeVisibleAnnotations_attribute attr, P p) {
+        return defaultAction(attr, p);
+    }
+
+    public R visitRuntimeInvisibleAnnotations(RuntimeInvisibleAnnotations_attribute attr, P p) {
+        return defaultAction(attr, p);
+    }
+
+    public R visitRuntimeVisibleParameterAnnotations(RuntimeVisibleParameterAnnotations_attribute attr, P p) {
+        return defaultAction(attr, p);
+    }
+
+    public R visitRuntimeInvisibleParameterAnnotations(RuntimeInvisibleParameterAnnotations_attribute attr, P p) {
+        return defaultAction(attr, p);
+    }
+
+    public R visitRuntimeVisibleTypeAnnotations(RuntimeVisibleTypeAnnotations_attribute attr, P p) {
+        return defaultAction(attr, p);
+    }
+
+    public R visitRuntimeInvisibleTypeAnnotations(RuntimeInvisibleTypeAnnotations_attribute attr, P p) {
+        return defaultAction(attr, p);
+    }
+
+    public R visitPermittedSubclasses(PermittedSubclasses_attribute attr, P p) {
+        return defaultAction(attr, p);
+    }
+
+    public R visitSignature(Signature_attribute attr, P p) {
+        return defaultAction(attr, p);
+    }
+
+    public R visitSourceDebugExtension(SourceDebugExtension_attribute attr, P p) {
+        return defaultAction(attr, p);
+    }
+
+    public R visitSourceFile(SourceFile_attribute attr, P p) {
+        return defaultAction(attr, p);
+    }
+
+    public R visitSourceID(SourceID_attribute attr, P p) {
+        return defaultAction(attr, p);
+    }
+
+    public R visitStackMap(StackMap_attribute attr, P p) {
+        return defaultAction(attr, p);
+    }
+
+    public R visitStackMapTable(StackMapTable_attribute attr, P p) {
+        return defaultAction(attr, p);
+    }
+
+    public R visitSynthetic(Synthetic_attribute attr, P p) {
+        return defaultAction(attr, p);
+    }
+
+    public R visitCustom(CustomAttribute attr, P p) {
+        return defaultAction(attr, p);
+    }
+}
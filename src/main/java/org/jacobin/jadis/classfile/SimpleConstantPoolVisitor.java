@@ -0,0 +1,116 @@
+/*
+ * Copyright (c) 2021 by Andrew Binstock.
+ *
+ * Portions of this file are copyright Oracle Corp.
+ * Those portions are licensed under GPL v. 2.0
+ * with the Oracle classpath exception. Due to the
+ * requirements of that license, the portions that
+ * are copyrighted by Andrew Binstock are licensed
+ * using the same terms and requirements.
+ */
+
+package org.jacobin.jadis.classfile;
+
+import org.jacobin.jadis.classfile.ConstantPool.*;
+
+/**
+ * A simple implementation of {@link ConstantPool.Visitor}. Every visit method
+ * calls {@link #defaultAction}, which returns {@link #DEFAULT_VALUE}, so
+ * subclasses need only override the methods for the entries they care about.
+ *
+ * @param <R> the return type of this visitor's methods
+ * @param <P> the type of the additional parameter to this visitor's methods
+ */
+public class SimpleConstantPoolVisitor<R,P> implements ConstantPool.Visitor<R,P> {
+    /**
+     * The value returned by {@link #defaultAction}.
+     */
+    protected final R DEFAULT_VALUE;
+
+    protected SimpleConstantPoolVisitor() {
+        DEFAULT_VALUE = null;
+    }
+
+    protected SimpleConstantPoolVisitor(R defaultValue) {
+        DEFAULT_VALUE = defaultValue;
+    }
+
+    /**
+     * The action taken by every visit method unless it is overridden.
+     *
+     * @param info the constant pool entry being visited
+     * @param p    the additional parameter
+     * @return {@link #DEFAULT_VALUE}
+     */
+    protected R defaultAction(CPInfo info, P p) {
+        return DEFAULT_VALUE;
+    }
+
+    public R visitClass(CONSTANT_Class_info info, P p) {
+        return defaultAction(info, p);
+    }
+
+    public R visitDouble(CONSTANT_Double_info info, P p) {
+        return defaultAction(info, p);
+    }
+
+    public R visitFieldref(CONSTANT_Fieldref_info info, P p) {
+        return defaultAction(info, p);
+    }
+
+    public R visitFloat(CONSTANT_Float_info info, P p) {
+        return defaultAction(info, p);
+    }
+
+    public R visitInteger(CONSTANT_Integer_info info, P p) {
+        return defaultAction(info, p);
+    }
+
+    public R visitInterfaceMethodref(CONSTANT_InterfaceMethodref_info info, P p) {
+        return defaultAction(info, p);
+    }
+
+    public R visitInvokeDynamic(CONSTANT_InvokeDynamic_info info, P p) {
+        return defaultAction(info, p);
+    }
+
+    public R visitDynamicConstant(CONSTANT_Dynamic_info info, P p) {
+        return defaultAction(info, p);
+    }
+
+    public R visitLong(CONSTANT_Long_info info, P p) {
+        return defaultAction(info, p);
+    }
+
+    public R visitMethodref(CONSTANT_Methodref_info info, P p) {
+        return defaultAction(info, p);
+    }
+
+    public R visitMethodHandle(CONSTANT_MethodHandle_info info, P p) {
+        return defaultAction(info, p);
+    }
+
+    public R visitMethodType(CONSTANT_MethodType_info info, P p) {
+        return defaultAction(info, p);
+    }
+
+    public R visitModule(CONSTANT_Module_info info, P p) {
+        return defaultAction(info, p);
+    }
+
+    public R visitNameAndType(CONSTANT_NameAndType_info info, P p) {
+        return defaultAction(info, p);
+    }
+
+    public R visitPackage(CONSTANT_Package_info info, P p) {
+        return defaultAction(info, p);
+    }
+
+    public R visitString(CONSTANT_String_info info, P p) {
+        return defaultAction(info, p);
+    }
+
+    public R visitUtf8(CONSTANT_Utf8_info info, P p) {
+        return defaultAction(info, p);
+    }
+}
@@ -0,0 +1,91 @@
+/*
+ * Copyright (c) 2021 by Andrew Binstock.
+ *
+ * Portions of this file are copyright Oracle Corp.
+ * Those portions are licensed under GPL v. 2.0
+ * with the Oracle classpath exception. Due to the
+ * requirements of that license, the portions that
+ * are copyrighted by Andrew Binstock are licensed
+ * using the same terms and requirements.
+ */
+
+package org.jacobin.jadis.classfile;
+
+/**
+ * A simple implementation of {@link Instruction.KindVisitor}. Every visit
+ * method calls {@link #defaultAction}, which returns {@link #DEFAULT_VALUE},
+ * so subclasses need only override the methods for the kinds of instruction
+ * they care about.
+ *
+ * @param <R> the return type of this visitor's methods
+ * @param <P> the type of the additional parameter to this visitor's methods
+ */
+public class SimpleInstructionVisitor<R,P> implements Instruction.KindVisitor<R,P> {
+    /**
+     * The value returned by {@link #defaultAction}.
+     */
+    protected final R DEFAULT_VALUE;
+
+    protected SimpleInstructionVisitor() {
+        DEFAULT_VALUE = null;
+    }
+
+    protected SimpleInstructionVisitor(R defaultValue) {
+        DEFAULT_VALUE = defaultValue;
+    }
+
+    /**
+     * The action taken by every visit method unless it is overridden.
+     *
+     * @param instr the instruction being visited
+     * @param p     the additional parameter
+     * @return {@link #DEFAULT_VALUE}
+     */
+    protected R defaultAction(Instruction instr, P p) {
+        return DEFAULT_VALUE;
+    }
+
+    public R visitNoOperands(Instruction instr, P p) {
+        return defaultAction(instr, p);
+    }
+
+    public R visitArrayType(Instruction instr, Instruction.TypeKind kind, P p) {
+        return defaultAction(instr, p);
+    }
+
+    public R visitBranch(Instruction instr, int offset, P p) {
+        return defaultAction(instr, p);
+    }
+
+    public R visitConstantPoolRef(Instruction instr, int index, P p) {
+        return defaultAction(instr, p);
+    }
+
+    public R visitConstantPoolRefAndValue(Instruction instr, int index, int value, P p) {
+        return defaultAction(instr, p);
+    }
+
+    public R visitLocal(Instruction instr, int index, P p) {
+        return defaultAction(instr, p);
+    }
+
+    public R visitLocalAndValue(Instruction instr, int index, int value, P p) {
+        return defaultAction(instr, p);
+    }
+
+    public R visitLookupSwitch(Instruction instr, int default_, int npairs, int[] matches, int[] offsets, P p) {
+        return defaultAction(instr, p);
+    }
+
+    public R visitTableSwitch(Instruction instr, int default_, int low, int high, int[] offsets, P p) {
+        return defaultAction(instr, p);
+    }
+
+    public R visitValue(Instruction instr, int value, P p) {
+        return defaultAction(instr, p);
+    }
+
+    public R visitUnknown(Instruction instr, P p) {
+        return defaultAction(instr, p);
+    }
+}
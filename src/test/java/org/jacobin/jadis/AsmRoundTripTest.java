@@ -0,0 +1,505 @@
+/*
+ * Copyright (c) 2021 by Andrew Binstock.
+ *
+ * Portions of this file are copyright Oracle Corp.
+ * Those portions are licensed under GPL v. 2.0
+ * with the Oracle classpath exception. Due to the
+ * requirements of that license, the portions that
+ * are copyrighted by Andrew Binstock are licensed
+ * using the same terms and requirements.
+ *
+ */
+
+package org.jacobin.jadis;
+
+import java.io.ByteArrayInputStream;
+import java.io.IOException;
+import java.io.InputStream;
+import java.io.PrintWriter;
+import java.io.StringWriter;
+import java.lang.reflect.InvocationTargetException;
+import java.lang.reflect.Method;
+import java.nio.charset.StandardCharsets;
+import java.nio.file.Files;
+import java.nio.file.Path;
+import java.nio.file.Paths;
+import java.util.ArrayList;
+import java.util.Arrays;
+import java.util.Collections;
+import java.util.List;
+import java.util.Map;
+import java.util.function.Consumer;
+import java.util.function.IntSupplier;
+import java.util.stream.Collectors;
+import java.util.stream.Stream;
+
+import org.jacobin.jadis.asm.AsmException;
+import org.jacobin.jadis.asm.Assembler;
+import org.jacobin.jadis.asm.ClassHierarchy;
+import org.jacobin.jadis.classfile.Attribute;
+import org.jacobin.jadis.classfile.Attributes;
+import org.jacobin.jadis.classfile.ClassFile;
+import org.jacobin.jadis.classfile.ClassReader;
+import org.jacobin.jadis.classfile.Code_attribute;
+import org.jacobin.jadis.classfile.ConstantPool;
+import org.jacobin.jadis.classfile.ConstantPoolException;
+import org.jacobin.jadis.classfile.CustomAttribute;
+import org.jacobin.jadis.classfile.Field;
+import org.jacobin.jadis.classfile.Record_attribute;
+
+/*
+ *  Round-trip tests for jadis-asm. Each class is shown with --format=krakatau,
+ *  the listing is assembled, and the class file that results is shown again;
+ *  the two listings must be the same. A listing must have no comments, since
+ *  they stand for what Krakatau syntax cannot express, and the reassembled
+ *  class must have attributes of the same names as the original, in the class
+ *  and in each record component, field, method and Code attribute. The classes
+ *  are the test classes kept for the JSON tests, some classes of the JDK, which
+ *  use most of the instructions and directives, and a class with attributes of
+ *  other vendors, which must be kept whether or not a plugin decodes them.
+ *
+ *  The test classes are also assembled without their .stack directives, with
+ *  the frames computed by the assembler, and are then loaded, so that the JVM
+ *  verifies them. The methods of Basic are called on both the original and the
+ *  reassembled class, and must give the same results.
+ *
+ *  There is no assembler for Jasmin syntax, so the classes kept for the Jasmin
+ *  tests are shown with --format=jasmin, and the listing of each must be the
+ *  same as the .j file of the same name. Running with -Djadis.test.update=true
+ *  rewrites the .j files instead.
+ *
+ *  To run the tests, compile the sources together with this file, then run
+ *      java -cp <classes> org.jacobin.jadis.AsmRoundTripTest [<class-or-dir>...]
+ *  where the default is the JSON test resources and the JDK classes below.
+ */
+public class AsmRoundTripTest {
+
+    static final String DEFAULT_DIR = JsonFormatTest.DEFAULT_DIR;
+    static final String JASMIN_DIR = "src/test/resources/org/jacobin/jadis/jasmin";
+
+    static final String[] JDK_CLASSES = {
+        "java.lang.String",
+        "java.lang.Math",
+        "java.util.HashMap",
+        "java.util.concurrent.ConcurrentHashMap",
+        "java.util.regex.Pattern",
+        "java.lang.invoke.MethodHandles",
+        "java.io.ObjectInputStream"
+    };
+
+    /*
+     *  A class with attributes of other vendors, in the class, a field, a method
+     *  and its code, which are written by their content.
+     */
+    static final String VENDOR = String.join( "\n",
+        ".version 52 0",
+        ".class public super Vendor",
+        ".super java/lang/Object",
+        ".attribute VendorClass b'\\x00\\x01class'",
+        "",
+        ".field private x I .fieldattributes",
+        "    .attribute VendorField b'field'",
+        ".end fieldattributes",
+        "",
+        ".method public <init> : ()V",
+        "    .code stack 1 locals 1",
+        "L0:     aload_0",
+        "L1:     invokespecial Method java/lang/Object <init> ()V",
+        "L4:     return",
+        "        .attribute VendorCode b'\\xca\\xfe'",
+        "    .end code",
+        "    .attribute VendorMethod b''",
+        ".end method",
+        ".end class",
+        "" );
+
+    static final String[] VENDOR_ATTRIBUTES = { "VendorClass", "VendorField", "VendorMethod", "VendorCode" };
+
+    public static void main( String[] args ) throws IOException {
+        AsmRoundTripTest t = new AsmRoundTripTest();
+        int failures = 0;
+        if ( args.length == 0 ) {
+            failures += t.runDir( Paths.get( DEFAULT_DIR ), true );
+            for ( String c : JDK_CLASSES ) {
+                failures += t.roundTrip( c, c );
+            }
+            failures += t.runVendor();
+            failures += t.runJasmin( Paths.get( JASMIN_DIR ), Boolean.getBoolean( "jadis.test.update" ) );
+        } else {
+            for ( String arg : args ) {
+                if ( Files.isDirectory( Paths.get( arg ) ) ) {
+                    failures += t.runDir( Paths.get( arg ), false );
+                } else {
+                    failures += t.roundTrip( arg, arg );
+                }
+            }
+        }
+        if ( failures > 0 ) {
+            System.err.println( failures + " test(s) failed" );
+            System.exit( 1 );
+        }
+    }
+
+    AsmRoundTripTest() throws IOException {
+        work = Files.createTempDirectory( "jadis-asm" );
+    }
+
+    int runDir( Path dir, boolean verify ) throws IOException {
+        List<Path> classes;
+        try ( Stream<Path> s = Files.walk( dir ) ) {
+            classes = s.filter( p -> p.toString().endsWith( ".class" ) )
+                       .sorted()
+                       .collect( Collectors.toList() );
+        }
+        if ( classes.isEmpty() ) {
+            throw new IOException( "no test classes found in " + dir );
+        }
+        int failures = 0;
+        for ( Path c : classes ) {
+            String name = dir.relativize( c ).toString().replace( '\\', '/' );
+            failures += roundTrip( c.toString(), name );
+            if ( verify && !name.contains( "module-info" ) ) {
+                failures += computeFrames( c, name );
+            }
+        }
+        return failures;
+    }
+
+    /*
+     *  Round-trips the class with attributes of other vendors, first with the
+     *  attributes not decoded, then with them decoded by a plugin.
+     */
+    int runVendor() throws IOException {
+        String name = "Vendor";
+        Path file;
+        try {
+            file = write( assemble( VENDOR, false ) );
+        } catch ( AsmException e ) {
+            System.err.println( "FAIL " + name + ": line " + e.line + ": " + e.getMessage() );
+            return 1;
+        }
+        String listing = show( file.toString() );
+        for ( String a : VENDOR_ATTRIBUTES ) {
+            if ( !listing.contains( ".attribute " + a + " " ) ) {
+                System.err.println( "FAIL " + name + ": no " + a + " attribute in\n" + listing );
+                return 1;
+            }
+        }
+        int failures = roundTrip( file.toString(), name );
+        plugins = factory -> {
+            for ( String a : VENDOR_ATTRIBUTES ) {
+                factory.register( a, VendorAttribute::new, null );
+            }
+        };
+        try {
+            failures += roundTrip( file.toString(), name + ", decoded by a plugin" );
+        } finally {
+            plugins = factory -> { };
+        }
+        return failures;
+    }
+
+    /*
+     *  An attribute of another vendor, as decoded by a plugin, which knows only
+     *  its name and length.
+     */
+    static class VendorAttribute extends CustomAttribute {
+        VendorAttribute( ClassReader cr, int name_index, int length ) {
+            super( name_index, length );
+        }
+    }
+
+    /*
+     *  Compares the Jasmin listing of each class in a directory with its .j file.
+     */
+    int runJasmin( Path dir, boolean update ) throws IOException {
+        List<Path> classes;
+        try ( Stream<Path> s = Files.walk( dir ) ) {
+            classes = s.filter( p -> p.toString().endsWith( ".class" ) )
+                       .sorted()
+                       .collect( Collectors.toList() );
+        }
+        if ( classes.isEmpty() ) {
+            throw new IOException( "no test classes found in " + dir );
+        }
+        int failures = 0;
+        for ( Path c : classes ) {
+            String name = dir.relativize( c ).toString().replace( '\\', '/' ) + " (Jasmin)";
+            Path golden = Paths.get( c.toString().replaceAll( "\\.class$", ".j" ) );
+            String actual = show( "--format=jasmin", c.toString(), factory -> { } );
+            if ( update ) {
+                Files.write( golden, actual.getBytes( StandardCharsets.UTF_8 ) );
+                System.out.println( "updated " + golden );
+            } else if ( !Files.exists( golden ) ) {
+                System.err.println( "FAIL " + name + ": no " + golden );
+                failures++;
+            } else {
+                String expected = new String( Files.readAllBytes( golden ), StandardCharsets.UTF_8 );
+                if ( expected.equals( actual ) ) {
+                    System.out.println( "ok   " + name );
+                } else {
+                    System.err.println( "FAIL " + name + ": " + JsonFormatTest.firstDifference( expected, actual ) );
+                    failures++;
+                }
+            }
+        }
+        return failures;
+    }
+
+    /*
+     *  Shows a class, assembles the listing, and shows the result. The listing
+     *  must have no comments, since a comment stands for something that cannot
+     *  be written, and the reassembled class must have the same attributes as
+     *  the original.
+     */
+    int roundTrip( String arg, String name ) {
+        try {
+            String expected = show( arg );
+            String comment = firstComment( expected );
+            if ( comment != null ) {
+                System.err.println( "FAIL " + name + ": " + comment );
+                return 1;
+            }
+            Map<String, byte[]> classes = assemble( expected, false );
+            String actual = show( write( classes ).toString() );
+            if ( !expected.equals( actual ) ) {
+                System.err.println( "FAIL " + name + ": " + JsonFormatTest.firstDifference( expected, actual ) );
+                return 1;
+            }
+            String expectedAttributes;
+            try ( InputStream in = read( arg ) ) {
+                expectedAttributes = attributeNames( ClassFile.read( in ) );
+            }
+            byte[] reassembled = classes.values().iterator().next();
+            String actualAttributes = attributeNames( ClassFile.read( new ByteArrayInputStream( reassembled ) ) );
+            if ( !expectedAttributes.equals( actualAttributes ) ) {
+                System.err.println( "FAIL " + name + ": attributes: "
+                                    + JsonFormatTest.firstDifference( expectedAttributes, actualAttributes ) );
+                return 1;
+            }
+            System.out.println( "ok   " + name );
+            return 0;
+        } catch ( AsmException e ) {
+            System.err.println( "FAIL " + name + ": line " + e.line + ": " + e.getMessage() );
+            return 1;
+        } catch ( IOException | ConstantPoolException e ) {
+            System.err.println( "FAIL " + name + ": " + e );
+            return 1;
+        }
+    }
+
+    /*
+     *  Assembles a class without its frames, computing them instead, and loads
+     *  the result so that it is verified. For Basic, the methods are also called.
+     */
+    int computeFrames( Path c, String name ) {
+        try {
+            byte[] original = Files.readAllBytes( c );
+            Map<String, byte[]> classes = assemble( withoutFrames( show( c.toString() ) ), true );
+            String className = classes.keySet().iterator().next();
+            Class<?> reassembled = Class.forName( className, true, new ByteLoader( classes ) );
+            if ( className.equals( "Basic" ) ) {
+                Class<?> cls = Class.forName( className, true, new ByteLoader( Map.of( className, original ) ) );
+                String expected = callAll( cls );
+                String actual = callAll( reassembled );
+                if ( !expected.equals( actual ) ) {
+                    System.err.println( "FAIL " + name + " with computed frames: "
+                                        + JsonFormatTest.firstDifference( expected, actual ) );
+                    return 1;
+                }
+            }
+            System.out.println( "ok   " + name + " with computed frames" );
+            return 0;
+        } catch ( AsmException e ) {
+            System.err.println( "FAIL " + name + " with computed frames: line " + e.line + ": " + e.getMessage() );
+            return 1;
+        } catch ( IOException | ReflectiveOperationException | LinkageError e ) {
+            System.err.println( "FAIL " + name + " with computed frames: " + e );
+            return 1;
+        }
+    }
+
+    /*
+     *  Calls each method of a new instance that takes no arguments or one int,
+     *  and returns the results, one per line.
+     */
+    static String callAll( Class<?> cls ) throws ReflectiveOperationException {
+        Object instance = cls.getDeclaredConstructor().newInstance();
+        Method[] methods = cls.getDeclaredMethods();
+        Arrays.sort( methods, ( a, b ) -> a.toString().compareTo( b.toString() ) );
+        StringBuilder sb = new StringBuilder();
+        for ( Method m : methods ) {
+            List<Object[]> calls = new ArrayList<>();
+            if ( m.getParameterCount() == 0 ) {
+                calls.add( new Object[0] );
+            } else if ( Arrays.equals( m.getParameterTypes(), new Class<?>[] { int.class } ) ) {
+                for ( int i : new int[] { -100, 0, 1, 2, 3, 4, 1000 } ) {
+                    calls.add( new Object[] { i } );
+                }
+            }
+            m.setAccessible( true );
+            for ( Object[] args : calls ) {
+                Object result;
+                try {
+                    result = m.invoke( instance, args );
+                    if ( result instanceof IntSupplier ) {
+                        result = ( (IntSupplier) result ).getAsInt();
+                    }
+                } catch ( InvocationTargetException e ) {
+                    result = e.getCause().getClass().getName();
+                }
+                sb.append( m.getName() ).append( Arrays.toString( args ) )
+                  .append( " = " ).append( result ).append( "\n" );
+            }
+        }
+        return sb.toString();
+    }
+
+    String show( String arg ) throws IOException {
+        return show( "--format=krakatau", arg, plugins );
+    }
+
+    /*
+     *  Shows a class in the given format, with decoders registered by plugins.
+     */
+    static String show( String format, String arg, Consumer<Attribute.Factory> plugins ) throws IOException {
+        StringWriter sw = new StringWriter();
+        PrintWriter pw = new PrintWriter( sw );
+        JavapTask task = new JavapTask();
+        task.setLog( pw );
+        plugins.accept( task.attributeFactory );
+        int rc = task.run( new String[] { format, arg } );
+        pw.flush();
+        String out = sw.toString().replace( System.lineSeparator(), "\n" );
+        if ( rc != JavapTask.EXIT_OK ) {
+            throw new IOException( "exit code " + rc + "\n" + out );
+        }
+        return out;
+    }
+
+    /*
+     *  Opens a class given by its file name, or by its name on the class path.
+     */
+    static InputStream read( String arg ) throws IOException {
+        if ( Files.exists( Paths.get( arg ) ) ) {
+            return Files.newInputStream( Paths.get( arg ) );
+        }
+        InputStream in = ClassLoader.getSystemResourceAsStream( arg.replace( '.', '/' ) + ".class" );
+        if ( in == null ) {
+            throw new IOException( "class not found: " + arg );
+        }
+        return in;
+    }
+
+    static Map<String, byte[]> assemble( String text, boolean computeFrames ) throws AsmException {
+        ClassHierarchy hierarchy = new ClassHierarchy( AsmRoundTripTest.class.getClassLoader() );
+        Assembler assembler = new Assembler( hierarchy, false, computeFrames );
+        assembler.scan( text );
+        return assembler.assemble( text );
+    }
+
+    Path write( Map<String, byte[]> classes ) throws IOException {
+        Path file = null;
+        for ( Map.Entry<String, byte[]> e : classes.entrySet() ) {
+            file = work.resolve( e.getKey().replace( '/', '.' ) + ".class" );
+            Files.write( file, e.getValue() );
+        }
+        return file;
+    }
+
+    /*
+     *  Returns the first comment in a listing, or null if there is none.
+     */
+    static String firstComment( String listing ) {
+        return Stream.of( listing.split( "\n" ) )
+                     .map( String::trim )
+                     .filter( l -> l.startsWith( ";" ) )
+                     .findFirst()
+                     .orElse( null );
+    }
+
+    /*
+     *  Lists the names of the attributes of a class, and of each of its record
+     *  components, fields, methods and Code attributes, one line for each. The
+     *  names are sorted, since the assembler need not keep their order.
+     */
+    static String attributeNames( ClassFile cf ) throws ConstantPoolException {
+        ConstantPool cp = cf.constant_pool;
+        StringBuilder sb = new StringBuilder();
+        sb.append( "class: " ).append( names( cf.attributes, cp ) ).append( "\n" );
+        Attribute record = cf.attributes.get( Attribute.Record );
+        if ( record instanceof Record_attribute ) {
+            for ( Record_attribute.ComponentInfo c : ( (Record_attribute) record ).component_info_arr ) {
+                sb.append( "component " ).append( c.getName( cp ) ).append( ": " )
+                  .append( names( c.attributes, cp ) ).append( "\n" );
+            }
+        }
+        for ( Field f : cf.fields ) {
+            sb.append( "field " ).append( f.getName( cp ) ).append( ": " )
+              .append( names( f.attributes, cp ) ).append( "\n" );
+        }
+        for ( org.jacobin.jadis.classfile.Method m : cf.methods ) {
+            String method = m.getName( cp ) + m.descriptor.getValue( cp );
+            sb.append( "method " ).append( method ).append( ": " )
+              .append( names( m.attributes, cp ) ).append( "\n" );
+            Attribute code = m.attributes.get( Attribute.Code );
+            if ( code instanceof Code_attribute ) {
+                sb.append( "code " ).append( method ).append( ": " )
+                  .append( names( ( (Code_attribute) code ).attributes, cp ) ).append( "\n" );
+            }
+        }
+        return sb.toString();
+    }
+
+    private static String names( Attributes attributes, ConstantPool cp ) throws ConstantPoolException {
+        List<String> names = new ArrayList<>();
+        for ( Attribute a : attributes ) {
+            names.add( a.getName( cp ) );
+        }
+        Collections.sort( names );
+        return String.join( " ", names );
+    }
+
+    /*
+     *  Removes the .stack directives, including the lines of full frames.
+     */
+    static String withoutFrames( String listing ) {
+        StringBuilder sb = new StringBuilder();
+        boolean inFrame = false;
+        for ( String l : listing.split( "\n" ) ) {
+            String t = l.trim();
+            if ( t.equals( ".stack full" ) ) {
+                inFrame = true;
+            } else if ( inFrame ) {
+                inFrame = !t.equals( ".end stack" );
+            } else if ( !t.startsWith( ".stack " ) ) {
+                sb.append( l ).append( "\n" );
+            }
+        }
+        return sb.toString();
+    }
+
+    /*
+     *  Defines classes from their class files.
+     */
+    static class ByteLoader extends ClassLoader {
+        ByteLoader( Map<String, byte[]> classes ) {
+            super( AsmRoundTripTest.class.getClassLoader() );
+            this.classes = classes;
+        }
+
+        @Override
+        protected Class<?> findClass( String name ) throws ClassNotFoundException {
+            byte[] bytes = classes.get( name.replace( '.', '/' ) );
+            if ( bytes == null ) {
+                throw new ClassNotFoundException( name );
+            }
+            return defineClass( name, bytes, 0, bytes.length );
+        }
+
+        private final Map<String, byte[]> classes;
+    }
+
+    private final Path work;
+    private Consumer<Attribute.Factory> plugins = factory -> { };
+}
@@ -0,0 +1,189 @@
+/*
+ * Copyright (c) 2021 by Andrew Binstock.
+ *
+ * Portions of this file are copyright Oracle Corp.
+ * Those portions are licensed under GPL v. 2.0
+ * with the Oracle classpath exception. Due to the
+ * requirements of that license, the portions that
+ * are copyrighted by Andrew Binstock are licensed
+ * using the same terms and requirements.
+ *
+ */
+
+package org.jacobin.jadis;
+
+import java.io.IOException;
+import java.io.PrintWriter;
+import java.io.StringWriter;
+import java.nio.charset.StandardCharsets;
+import java.nio.file.Files;
+import java.nio.file.Path;
+import java.nio.file.Paths;
+import java.util.ArrayList;
+import java.util.List;
+import java.util.stream.Collectors;
+import java.util.stream.Stream;
+
+/*
+ *  Golden tests for --format=json. Each .class file in the json test resources
+ *  is shown with --format=json, and the output is compared with the .json file
+ *  of the same name. The sources from which the classes were compiled are kept
+ *  beside them; the classes are kept too, so that the output does not depend on
+ *  the compiler used to run the tests. Then all the classes are shown in a
+ *  single run, which must write one document holding the same classes, in order.
+ *
+ *  To run the tests, compile the sources together with this file, then run
+ *      java -cp <classes> org.jacobin.jadis.JsonFormatTest [<resource-dir>]
+ *  where <resource-dir> defaults to src/test/resources/org/jacobin/jadis/json.
+ *  Running with -Djadis.test.update=true rewrites the .json files instead, after
+ *  a deliberate change to the output; any change to the schema itself must also
+ *  increment JsonWriter.SCHEMA_VERSION.
+ */
+public class JsonFormatTest {
+
+    static final String DEFAULT_DIR = "src/test/resources/org/jacobin/jadis/json";
+    static final String SCHEMA = "src/main/java/org/jacobin/jadis/resources/jadis-class.schema.json";
+
+    public static void main( String[] args ) throws IOException {
+        Path dir = Paths.get( args.length > 0 ? args[0] : DEFAULT_DIR );
+        boolean update = Boolean.getBoolean( "jadis.test.update" );
+        int failures = new JsonFormatTest().run( dir, update );
+        if ( failures > 0 ) {
+            System.err.println( failures + " test(s) failed" );
+            System.exit( 1 );
+        }
+    }
+
+    int run( Path dir, boolean update ) throws IOException {
+        List<Path> classes;
+        try ( Stream<Path> s = Files.walk( dir ) ) {
+            classes = s.filter( p -> p.toString().endsWith( ".class" ) )
+                       .sorted()
+                       .collect( Collectors.toList() );
+        }
+        if ( classes.isEmpty() ) {
+            throw new IOException( "no test classes found in " + dir );
+        }
+
+        int failures = 0;
+        for ( Path c : classes ) {
+            String name = dir.relativize( c ).toString().replace( '\\', '/' );
+            Path golden = Paths.get( c.toString().replaceAll( "\\.class$", ".json" ) );
+            String actual = show( dir, List.of( c ) );
+            if ( update ) {
+                Files.write( golden, actual.getBytes( StandardCharsets.UTF_8 ) );
+                System.out.println( "updated " + golden );
+            } else if ( !Files.exists( golden ) ) {
+                System.err.println( "FAIL " + name + ": no " + golden );
+                failures++;
+            } else {
+                String expected = new String( Files.readAllBytes( golden ), StandardCharsets.UTF_8 );
+                if ( expected.equals( actual ) ) {
+                    System.out.println( "ok   " + name );
+                } else {
+                    System.err.println( "FAIL " + name + ": " + firstDifference( expected, actual ) );
+                    failures++;
+                }
+            }
+        }
+
+        if ( !update ) {
+            failures += checkAllClasses( dir, classes );
+        }
+        failures += checkSchemaVersion();
+        return failures;
+    }
+
+    /*
+     *  Shows all the classes in one run. The document must hold the classes of
+     *  the golden files, in the same order, each written as in its own file.
+     */
+    int checkAllClasses( Path dir, List<Path> classes ) throws IOException {
+        StringBuilder expected = new StringBuilder();
+        for ( Path c : classes ) {
+            Path golden = Paths.get( c.toString().replaceAll( "\\.class$", ".json" ) );
+            String doc = new String( Files.readAllBytes( golden ), StandardCharsets.UTF_8 );
+            String[] lines = doc.split( "\n" );
+            if ( expected.length() == 0 ) {
+                expected.append( String.join( "\n", List.of( lines ).subList( 0, 4 ) ) ).append( "\n" );
+            } else {
+                expected.append( ",\n" );
+            }
+            expected.append( String.join( "\n", List.of( lines ).subList( 4, lines.length - 2 ) ) );
+        }
+        expected.append( "\n  ]\n}\n" );
+
+        String name = "all classes in one run";
+        String actual = show( dir, classes );
+        if ( !expected.toString().equals( actual ) ) {
+            System.err.println( "FAIL " + name + ": " + firstDifference( expected.toString(), actual ) );
+            return 1;
+        }
+        System.out.println( "ok   " + name );
+        return 0;
+    }
+
+    /*
+     *  Shows classes with --format=json. Each file is named by its path relative
+     *  to the resource directory, so that the output does not depend on where the
+     *  tests are run.
+     */
+    String show( Path dir, List<Path> classes ) {
+        StringWriter sw = new StringWriter();
+        PrintWriter pw = new PrintWriter( sw );
+        JavapTask task = new JavapTask();
+        task.setLog( pw );
+        List<String> args = new ArrayList<>();
+        args.add( "--format=json" );
+        for ( Path c : classes ) {
+            args.add( c.toString() );
+        }
+        int rc = task.run( args.toArray( new String[0] ) );
+        pw.flush();
+        String out = sw.toString().replace( System.lineSeparator(), "\n" );
+        if ( rc != JavapTask.EXIT_OK ) {
+            return "exit code " + rc + "\n" + out;
+        }
+        for ( Path c : classes ) {
+            String name = dir.relativize( c ).toString().replace( '\\', '/' );
+            String file = "\"file\": " + quote( c.toString() ) + ",";
+            out = out.replace( file, "\"file\": " + quote( name ) + "," );
+        }
+        return out;
+    }
+
+    /*
+     *  The published schema and the writer must agree on the version.
+     */
+    int checkSchemaVersion() throws IOException {
+        Path schema = Paths.get( SCHEMA );
+        if ( !Files.exists( schema ) ) {
+            System.out.println( "skip schema version: no " + schema );
+            return 0;
+        }
+        String text = new String( Files.readAllBytes( schema ), StandardCharsets.UTF_8 );
+        String expected = "\"schemaVersion\": { \"const\": " + JsonWriter.SCHEMA_VERSION + " }";
+        if ( !text.contains( expected ) ) {
+            System.err.println( "FAIL schema version: " + schema + " does not declare "
+                                + JsonWriter.SCHEMA_VERSION );
+            return 1;
+        }
+        System.out.println( "ok   schema version " + JsonWriter.SCHEMA_VERSION );
+        return 0;
+    }
+
+    static String firstDifference( String expected, String actual ) {
+        String[] e = expected.split( "\n", -1 );
+        String[] a = actual.split( "\n", -1 );
+        for ( int i = 0; i < Math.min( e.length, a.length ); i++ ) {
+            if ( !e[i].equals( a[i] ) ) {
+                return "line " + ( i + 1 ) + "\n  expected: " + e[i] + "\n  actual:   " + a[i];
+            }
+        }
+        return "expected " + e.length + " lines, got " + a.length;
+    }
+
+    static String quote( String s ) {
+        return "\"" + s.replace( "\\", "\\\\" ).replace( "\"", "\\\"" ) + "\"";
+    }
+}
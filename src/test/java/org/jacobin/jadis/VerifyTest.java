@@ -0,0 +1,249 @@
+/*
+ * Copyright (c) 2021 by Andrew Binstock.
+ *
+ * Portions of this file are copyright Oracle Corp.
+ * Those portions are licensed under GPL v. 2.0
+ * with the Oracle classpath exception. Due to the
+ * requirements of that license, the portions that
+ * are copyrighted by Andrew Binstock are licensed
+ * using the same terms and requirements.
+ *
+ */
+
+package org.jacobin.jadis;
+
+import java.io.IOException;
+import java.io.PrintWriter;
+import java.io.StringWriter;
+import java.nio.file.Files;
+import java.nio.file.Path;
+import java.nio.file.Paths;
+import java.util.Map;
+
+import org.jacobin.jadis.asm.AsmException;
+
+/*
+ *  Tests for --verify. The test classes kept for the JSON tests, and the JDK
+ *  classes used by the jadis-asm tests, must verify without errors. Then the
+ *  listing of Basic is broken in several ways and assembled, with the frames
+ *  as given; each class that results must be reported, with the method, the
+ *  offset, the instruction and the reason, and must also be rejected by the JVM.
+ *  So must classes with a method whose Code or StackMapTable attribute cannot
+ *  be decoded.
+ *
+ *  To run the tests, compile the sources together with this file, then run
+ *      java -cp <classes> org.jacobin.jadis.VerifyTest [<class-or-dir>...]
+ *  where the default is the JSON test resources and the JDK classes.
+ */
+public class VerifyTest {
+
+    /*
+     *  The changes made to the listing of Basic, as a line to replace, its
+     *  replacement, and the start of the error expected.
+     */
+    static final String[][] BROKEN = {
+        {
+            "        .stack stack_1 Object java/lang/RuntimeException",
+            "        .stack stack_1 Object java/lang/IllegalStateException",
+            "Basic.tryCatch()V: offset 0: aload_0: the current frame is not assignable to the stack map"
+                + " frame at the exception handler 42\n"
+                + "  expected: locals = [ class Basic ] stack = [ class java/lang/IllegalStateException ]\n"
+                + "  actual:   locals = [ class Basic ] stack = [ class java/lang/IllegalArgumentException ]"
+        },
+        {
+            "L28:    bipush 10",
+            "L28:    fconst_0",
+            "Basic.tableSwitch(I)I: offset 29: ireturn: bad type on the operand stack: expected int, found float\n"
+                + "  frame: locals = [ class Basic, int ] stack = [ float ]"
+        },
+        {
+            "L0:     iload_1\nL1:     tableswitch",
+            "L0:     aload_1\nL1:     tableswitch",
+            "Basic.tableSwitch(I)I: offset 0: aload_1: bad type in local variable 1: expected a reference, found int"
+        },
+        {
+            "        .stack same\nL31:",
+            "L31:",
+            "Basic.tableSwitch(I)I: offset 1: tableswitch { // 1 to 3 }: no stack map frame at the branch target 31"
+        },
+        {
+            "L30:    ireturn",
+            "L30:    nop",
+            "Basic.tableSwitch(I)I: offset 31: bipush 20: the current frame is not assignable to the stack map frame"
+        }
+    };
+
+    /*
+     *  Classes with a method whose Code or StackMapTable attribute cannot be
+     *  decoded, given as the listing of the class and the start of the error
+     *  expected. Each is truncated, and given as an .attribute directive.
+     */
+    static final String[][] UNDECODED = {
+        {
+            String.join( "\n",
+                ".version 52 0",
+                ".class public super Undecoded",
+                ".super java/lang/Object",
+                ".method public static f : ()V",
+                "    .attribute Code b'\\x00\\x01\\x00\\x00\\x00\\x00\\x00\\x05\\xb1'",
+                ".end method",
+                ".end class" ),
+            "Undecoded.f()V: the Code attribute cannot be decoded: "
+        },
+        {
+            String.join( "\n",
+                ".version 52 0",
+                ".class public super Undecoded",
+                ".super java/lang/Object",
+                ".method public static f : ()V",
+                "    .code stack 0 locals 0",
+                "L0:     return",
+                "        .attribute StackMapTable b'\\x00\\x01\\xff'",
+                "    .end code",
+                ".end method",
+                ".end class" ),
+            "Undecoded.f()V: the StackMapTable attribute cannot be decoded: "
+        }
+    };
+
+    public static void main( String[] args ) throws IOException {
+        VerifyTest t = new VerifyTest();
+        int failures = 0;
+        if ( args.length == 0 ) {
+            failures += t.verify( AsmRoundTripTest.DEFAULT_DIR );
+            for ( String c : AsmRoundTripTest.JDK_CLASSES ) {
+                failures += t.verify( c );
+            }
+            failures += t.runBroken( Paths.get( AsmRoundTripTest.DEFAULT_DIR, "Basic.class" ) );
+            failures += t.runUndecoded();
+        } else {
+            for ( String arg : args ) {
+                failures += t.verify( arg );
+            }
+        }
+        if ( failures > 0 ) {
+            System.err.println( failures + " test(s) failed" );
+            System.exit( 1 );
+        }
+    }
+
+    VerifyTest() throws IOException {
+        work = Files.createTempDirectory( "jadis-verify" );
+    }
+
+    /*
+     *  Verifies classes that must have no errors.
+     */
+    int verify( String arg ) {
+        StringWriter out = new StringWriter();
+        int rc = run( out, "--verify", arg );
+        if ( rc != JavapTask.EXIT_OK || !out.toString().isEmpty() ) {
+            System.err.println( "FAIL " + arg + ": exit code " + rc + "\n" + out );
+            return 1;
+        }
+        System.out.println( "ok   " + arg );
+        return 0;
+    }
+
+    int runBroken( Path basic ) throws IOException {
+        String listing = new AsmRoundTripTest().show( basic.toString() );
+        int failures = 0;
+        for ( int i = 0; i < BROKEN.length; i++ ) {
+            String name = "Basic, broken " + ( i + 1 );
+            String[] b = BROKEN[i];
+            if ( !listing.contains( b[0] ) ) {
+                System.err.println( "FAIL " + name + ": listing does not contain " + b[0] );
+                failures++;
+                continue;
+            }
+            try {
+                Map<String, byte[]> classes = AsmRoundTripTest.assemble( listing.replace( b[0], b[1] ), false );
+                Path file = work.resolve( "Basic" + ( i + 1 ) + ".class" );
+                Files.write( file, classes.get( "Basic" ) );
+                StringWriter out = new StringWriter();
+                int rc = run( out, "--verify", file.toString() );
+                String expected = "Error: " + b[2];
+                if ( rc != JavapTask.EXIT_ERROR || !out.toString().startsWith( expected ) ) {
+                    System.err.println( "FAIL " + name + ": exit code " + rc + "\n" + out
+                                        + "expected:\n" + expected );
+                    failures++;
+                    continue;
+                }
+                if ( !rejectedByJvm( "Basic", classes ) ) {
+                    System.err.println( "FAIL " + name + ": the JVM accepts the class" );
+                    failures++;
+                    continue;
+                }
+                System.out.println( "ok   " + name );
+            } catch ( AsmException e ) {
+                System.err.println( "FAIL " + name + ": line " + e.line + ": " + e.getMessage() );
+                failures++;
+            }
+        }
+        return failures;
+    }
+
+    /*
+     *  Verifies the classes with attributes that cannot be decoded, which must
+     *  each be reported, and rejected by the JVM.
+     */
+    int runUndecoded() {
+        int failures = 0;
+        for ( int i = 0; i < UNDECODED.length; i++ ) {
+            String name = "Undecoded " + ( i + 1 );
+            String[] u = UNDECODED[i];
+            try {
+                Map<String, byte[]> classes = AsmRoundTripTest.assemble( u[0], false );
+                Path file = work.resolve( "Undecoded" + ( i + 1 ) + ".class" );
+                Files.write( file, classes.get( "Undecoded" ) );
+                StringWriter out = new StringWriter();
+                int rc = run( out, "--verify", file.toString() );
+                String expected = "Error: " + u[1];
+                if ( rc != JavapTask.EXIT_ERROR || !out.toString().startsWith( expected ) ) {
+                    System.err.println( "FAIL " + name + ": exit code " + rc + "\n" + out
+                                        + "expected:\n" + expected );
+                    failures++;
+                    continue;
+                }
+                if ( !rejectedByJvm( "Undecoded", classes ) ) {
+                    System.err.println( "FAIL " + name + ": the JVM accepts the class" );
+                    failures++;
+                    continue;
+                }
+                System.out.println( "ok   " + name );
+            } catch ( AsmException e ) {
+                System.err.println( "FAIL " + name + ": line " + e.line + ": " + e.getMessage() );
+                failures++;
+            } catch ( IOException e ) {
+                System.err.println( "FAIL " + name + ": " + e );
+                failures++;
+            }
+        }
+        return failures;
+    }
+
+    static boolean rejectedByJvm( String className, Map<String, byte[]> classes ) {
+        try {
+            Class.forName( className, true, new AsmRoundTripTest.ByteLoader( classes ) );
+            return false;
+        } catch ( VerifyError | ClassFormatError e ) {
+            return true;
+        } catch ( ReflectiveOperationException | LinkageError e ) {
+            return false;
+        }
+    }
+
+    static int run( StringWriter out, String... args ) {
+        PrintWriter pw = new PrintWriter( out );
+        JavapTask task = new JavapTask();
+        task.setLog( pw );
+        int rc = task.run( args );
+        pw.flush();
+        String text = out.toString().replace( System.lineSeparator(), "\n" );
+        out.getBuffer().setLength( 0 );
+        out.write( text );
+        return rc;
+    }
+
+    private final Path work;
+}
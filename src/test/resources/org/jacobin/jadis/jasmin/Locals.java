@@ -0,0 +1,11 @@
+import java.util.ArrayList;
+import java.util.List;
+
+public class Locals {
+    static int count(String s) {
+        List<String> words = new ArrayList<>();
+        for (String w: s.split(" "))
+            words.add(w);
+        return words.size();
+    }
+}
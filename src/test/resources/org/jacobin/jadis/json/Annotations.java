@@ -0,0 +1,40 @@
+import java.lang.annotation.ElementType;
+import java.lang.annotation.Retention;
+import java.lang.annotation.RetentionPolicy;
+import java.lang.annotation.Target;
+import java.util.List;
+import java.util.Map;
+
+@Annotations.Info(name = "class", tags = {"a", "b"}, kind = ElementType.TYPE, type = String.class,
+        nested = @Annotations.Note(1))
+@Deprecated
+public class Annotations<@Annotations.TypeUse T extends @Annotations.TypeUse Comparable<T>> {
+    @Retention(RetentionPolicy.RUNTIME)
+    @interface Info {
+        String name() default "none";
+        String[] tags() default {};
+        ElementType kind() default ElementType.FIELD;
+        Class<?> type() default Object.class;
+        Note nested() default @Note(0);
+        int number() default 42;
+    }
+
+    @Retention(RetentionPolicy.CLASS)
+    @interface Note {
+        int value();
+    }
+
+    @Retention(RetentionPolicy.RUNTIME)
+    @Target({ElementType.TYPE_USE, ElementType.TYPE_PARAMETER})
+    @interface TypeUse { }
+
+    @Note(2)
+    Map<@TypeUse String, List<@TypeUse ? extends Number>> field;
+
+    void method(@Note(3) final int first, @Info(name = "second") String second) throws @TypeUse Exception {
+        @TypeUse Object local = second;
+        if (local instanceof @TypeUse String)
+            field = null;
+        String[] array = new @TypeUse String[first];
+    }
+}
@@ -0,0 +1,64 @@
+import java.io.IOException;
+import java.util.List;
+import java.util.function.IntSupplier;
+
+public class Basic implements Comparable<Basic> {
+    public static final String S = "quote \" newline \n tab \t unicode \u00e9";
+    static final long L = 1L << 40;
+    static final double D = Double.NaN;
+    static final float F = Float.NEGATIVE_INFINITY;
+    private int count;
+    protected volatile List<String> names;
+
+    public int compareTo(Basic other) {
+        return Integer.compare(count, other.count);
+    }
+
+    int tableSwitch(int i) {
+        switch (i) {
+            case 1: return 10;
+            case 2: return 20;
+            case 3: return 30;
+            default: return -1;
+        }
+    }
+
+    int lookupSwitch(int i) {
+        switch (i) {
+            case -100: return 1;
+            case 0: return 2;
+            case 1000: return 3;
+            default: return 0;
+        }
+    }
+
+    synchronized void tryCatch() throws IOException {
+        try {
+            count++;
+            if (count > 10)
+                throw new IOException("too many");
+        } catch (IllegalStateException | IllegalArgumentException e) {
+            count = 0;
+        } finally {
+            count--;
+        }
+    }
+
+    IntSupplier lambda(int base) {
+        int[] values = new int[3];
+        long[][] matrix = new long[2][3];
+        return () -> base + values.length + matrix.length;
+    }
+
+    static int wide(int a) {
+        int l0 = 0, l1 = 1, l2 = 2, l3 = 3, l4 = 4, l5 = 5, l6 = 6, l7 = 7, l8 = 8, l9 = 9;
+        a += 1000;
+        return a + l0 + l1 + l2 + l3 + l4 + l5 + l6 + l7 + l8 + l9;
+    }
+
+    class Inner {
+        int get() {
+            return count;
+        }
+    }
+}
@@ -0,0 +1,12 @@
+import java.io.Serializable;
+
+public record Point(int x, int y) implements Serializable {
+    public Point {
+        if (x < 0 || y < 0)
+            throw new IllegalArgumentException();
+    }
+
+    static sealed interface Shape permits Circle, Square { }
+    static final class Circle implements Shape { }
+    static non-sealed class Square implements Shape { }
+}
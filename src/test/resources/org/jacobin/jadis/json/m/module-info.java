@@ -0,0 +1,8 @@
+module m {
+    requires java.logging;
+    requires transitive java.sql;
+    exports p;
+    opens p to java.logging;
+    uses java.util.spi.ToolProvider;
+    provides java.util.spi.ToolProvider with p.Tool;
+}
@@ -0,0 +1,14 @@
+package p;
+
+import java.io.PrintWriter;
+import java.util.spi.ToolProvider;
+
+public class Tool implements ToolProvider {
+    public String name() {
+        return "tool";
+    }
+
+    public int run(PrintWriter out, PrintWriter err, String... args) {
+        return 0;
+    }
+}